@@ -0,0 +1,52 @@
+//! Storage access on the native mock runtime, with and without the
+//! per-invocation context cache.
+//!
+//! Run with `cargo bench -p silica-contract-sdk --bench storage`.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use silica_contract_sdk::address::{Address, AddressKind};
+use silica_contract_sdk::context::invalidate_context_cache;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::storage::{Map, storage};
+
+fn setup() {
+    mock::reset();
+    let sender = Address::new(AddressKind::Account, [0x5e; 32]);
+    mock::set_sender(sender.as_str());
+    mock::set_origin(sender.as_str());
+    mock::set_contract_address(Address::contract([0xc0; 32]).as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+}
+
+fn bench_storage(c: &mut Criterion) {
+    setup();
+    let mut balances: Map<u64, u64> = Map::new("balances");
+    for key in 0..64 {
+        balances.set(&key, &key).expect("seed");
+    }
+
+    let mut group = c.benchmark_group("storage");
+    group.bench_function("map_get/cached_context", |b| {
+        b.iter(|| balances.get(black_box(&7)).expect("read"))
+    });
+    group.bench_function("map_get/refetched_context", |b| {
+        b.iter(|| {
+            invalidate_context_cache();
+            balances.get(black_box(&7)).expect("read")
+        })
+    });
+    group.bench_function("raw_set/cached_context", |b| {
+        b.iter(|| storage().set(black_box("counter"), &1u64).expect("write"))
+    });
+    group.bench_function("raw_set/refetched_context", |b| {
+        b.iter(|| {
+            invalidate_context_cache();
+            storage().set(black_box("counter"), &1u64).expect("write")
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_storage);
+criterion_main!(benches);
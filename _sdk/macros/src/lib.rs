@@ -0,0 +1,399 @@
+//! Procedural macros for the Silica contract SDK
+//!
+//! Use them through `silica_contract_sdk`, which re-exports them; the
+//! generated code refers to the SDK by its crate name.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    FnArg, GenericArgument, Ident, ImplItem, ItemImpl, ItemTrait, Pat, PathArguments, ReturnType,
+    Signature, TraitItem, TraitItemFn, Type, parse_macro_input,
+};
+
+/// Turn a trait into a typed cross-contract interface.
+///
+/// Every method is an entrypoint of the same name. Methods take `&self`
+/// (views) or `&mut self` and return `ContractResult<T>`. Their arguments are
+/// passed as call data in declaration order and the `T` they return is the
+/// call's return data, all postcard-encoded. An `Err` fails the call.
+///
+/// ```ignore
+/// #[contract_interface]
+/// pub trait Timelock {
+///     fn schedule(&mut self, target: Address, value: u64, data: Vec<u8>,
+///                 predecessor: Option<[u8; 32]>, salt: [u8; 32], delay: u64)
+///                 -> ContractResult<[u8; 32]>;
+///     fn get_min_delay(&self) -> ContractResult<u64>;
+/// }
+/// ```
+///
+/// Besides the trait itself this generates:
+///
+/// - `TimelockRef`, a caller for a deployed contract: `TimelockRef::new(address)`
+///   has one method per entrypoint that encodes the arguments, calls the
+///   contract and decodes the result into a `ContractResult`.
+///   `TimelockRef::ENTRYPOINTS` lists the entrypoint names, and
+///   `TimelockRef::describe` reports each entrypoint's arguments and return
+///   type to an `InterfaceVisitor` such as the ABI builder.
+/// - A provided `dispatch(&mut self, entrypoint, input)` method, so a contract
+///   implementing the trait serves the interface: the compiler checks every
+///   signature, and `dispatch` decodes call data, runs the method and encodes
+///   the value it returns, passing errors on. Put [`macro@export_interface`] on the implementation to
+///   export it.
+#[proc_macro_attribute]
+pub fn contract_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            TokenStream2::from(attr).span(),
+            "contract_interface takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let item = parse_macro_input!(item as ItemTrait);
+    match expand_interface(item) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Export every method of an interface implementation as a contract
+/// entrypoint.
+///
+/// Goes on `impl Trait for Contract`, where `Trait` has
+/// [`macro@contract_interface`] and `Contract` is a unit struct. Each method
+/// gets a parameterless function of the same name that serves the call
+/// through `interface::serve`: it decodes the call data, runs the method and
+/// writes its result as return data, or fails the call so the host reverts it.
+/// `Contract::EXPORTS` lists the exported functions by entrypoint name.
+///
+/// The functions are `#[unsafe(no_mangle)]` on wasm32 only, so native tests
+/// can link several contracts that export the same entrypoint names.
+///
+/// ```ignore
+/// pub struct TimelockContract;
+///
+/// #[export_interface]
+/// impl Timelock for TimelockContract {
+///     fn get_min_delay(&self) -> ContractResult<u64> { /* ... */ }
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn export_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            TokenStream2::from(attr).span(),
+            "export_interface takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let item = parse_macro_input!(item as ItemImpl);
+    match expand_exports(item) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// An interface method with its arguments split into names and types.
+struct Entrypoint<'a> {
+    method: &'a TraitItemFn,
+    mutates: bool,
+    arg_names: Vec<Ident>,
+    arg_types: Vec<Type>,
+    /// `T` of the method's `ContractResult<T>`
+    output: &'a Type,
+}
+
+fn expand_interface(mut item: ItemTrait) -> syn::Result<TokenStream2> {
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            item.generics.span(),
+            "contract interfaces cannot be generic",
+        ));
+    }
+
+    let mut entrypoints = Vec::new();
+    for trait_item in &item.items {
+        match trait_item {
+            TraitItem::Fn(method) => entrypoints.push(entrypoint(method)?),
+            other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    "contract interfaces may only contain methods",
+                ));
+            }
+        }
+    }
+
+    let sdk = quote!(::silica_contract_sdk);
+    let vis = &item.vis;
+    let trait_name = &item.ident;
+    let ref_name = format_ident!("{}Ref", trait_name);
+    let names: Vec<String> = entrypoints
+        .iter()
+        .map(|entrypoint| entrypoint.method.sig.ident.to_string())
+        .collect();
+
+    let dispatch_arms = entrypoints
+        .iter()
+        .zip(&names)
+        .map(|(entrypoint, name)| {
+            let method = &entrypoint.method.sig.ident;
+            let arg_names = &entrypoint.arg_names;
+            let arg_types = &entrypoint.arg_types;
+            quote! {
+                #name => {
+                    let (#(#arg_names,)*): (#(#arg_types,)*) =
+                        #sdk::interface::__decode(input)?;
+                    #sdk::interface::__encode(&self.#method(#(#arg_names),*)?)
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let caller_methods = entrypoints.iter().zip(&names).map(|(entrypoint, name)| {
+        let sig = &entrypoint.method.sig;
+        let method = &sig.ident;
+        // Docs and lint allowances carry over to the caller's method
+        let docs = entrypoint
+            .method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc") || attr.path().is_ident("allow"));
+        let arg_names = &entrypoint.arg_names;
+        let arg_types = &entrypoint.arg_types;
+        let output = entrypoint.output;
+        quote! {
+            #(#docs)*
+            pub fn #method(&self, #(#arg_names: #arg_types),*) -> #sdk::error::ContractResult<#output> {
+                let input = #sdk::interface::__encode(&(#(#arg_names,)*))?;
+                let output = #sdk::context::try_context()?
+                    .call_contract(&self.address, #name, &input)?;
+                #sdk::interface::__decode(&output)
+            }
+        }
+    })
+        .collect::<Vec<_>>();
+
+    let descriptions = entrypoints
+        .iter()
+        .zip(&names)
+        .map(|(entrypoint, name)| {
+            let mutates = entrypoint.mutates;
+            let arg_names = entrypoint.arg_names.iter().map(ToString::to_string);
+            let arg_types = &entrypoint.arg_types;
+            let output = entrypoint.output;
+            let returns = if is_unit(output) {
+                quote!()
+            } else {
+                quote!(visitor.visit_returns::<#output>();)
+            };
+            quote! {
+                visitor.visit_entrypoint(#name, #mutates);
+                #(visitor.visit_arg::<#arg_types>(#arg_names);)*
+                #returns
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let dispatch_doc = format!(
+        "Decode `input` for `entrypoint`, run the matching method and encode its result.\n\n\
+         Generated by `#[contract_interface]`; fails with `InvalidArgument` for \
+         entrypoints outside `{}`.",
+        trait_name
+    );
+    item.items.push(syn::parse_quote! {
+        #[doc = #dispatch_doc]
+        fn dispatch(
+            &mut self,
+            entrypoint: &str,
+            input: &[u8],
+        ) -> #sdk::error::ContractResult<#sdk::prelude::Vec<u8>>
+        where
+            Self: Sized,
+        {
+            match entrypoint {
+                #(#dispatch_arms)*
+                _ => Err(#sdk::interface::__unknown_entrypoint(entrypoint)),
+            }
+        }
+    });
+
+    let ref_doc = format!(
+        "Caller for a deployed contract implementing [`{}`].",
+        trait_name
+    );
+    Ok(quote! {
+        #item
+
+        #[doc = #ref_doc]
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        #vis struct #ref_name {
+            address: #sdk::address::Address,
+        }
+
+        impl #ref_name {
+            /// Entrypoints of the interface, in declaration order.
+            pub const ENTRYPOINTS: &'static [&'static str] = &[#(#names),*];
+
+            /// Call the contract deployed at `address`.
+            pub fn new(address: #sdk::address::Address) -> Self {
+                Self { address }
+            }
+
+            /// Address of the called contract.
+            pub fn address(&self) -> &#sdk::address::Address {
+                &self.address
+            }
+
+            /// Report every entrypoint, in declaration order, with its
+            /// arguments and return type. `&self` methods are views.
+            pub fn describe<V: #sdk::interface::InterfaceVisitor>(visitor: &mut V) {
+                #(#descriptions)*
+            }
+
+            #(#caller_methods)*
+        }
+    })
+}
+
+fn entrypoint(method: &TraitItemFn) -> syn::Result<Entrypoint<'_>> {
+    let sig = &method.sig;
+    if !sig.generics.params.is_empty() || sig.asyncness.is_some() || sig.variadic.is_some() {
+        return Err(syn::Error::new(
+            sig.span(),
+            "entrypoints must be plain, non-generic methods",
+        ));
+    }
+    if method.default.is_some() {
+        return Err(syn::Error::new(
+            method.span(),
+            "entrypoints cannot have a default body",
+        ));
+    }
+
+    let mut inputs = sig.inputs.iter();
+    let mutates = match inputs.next() {
+        Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {
+            receiver.mutability.is_some()
+        }
+        _ => {
+            return Err(syn::Error::new(
+                sig.span(),
+                "entrypoints must take `&self` or `&mut self`",
+            ));
+        }
+    };
+
+    let mut arg_names = Vec::new();
+    let mut arg_types = Vec::new();
+    for input in inputs {
+        let FnArg::Typed(arg) = input else {
+            unreachable!("only the first argument can be a receiver");
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new(
+                arg.pat.span(),
+                "entrypoint arguments must be plain identifiers",
+            ));
+        };
+        arg_names.push(pat.ident.clone());
+        arg_types.push((*arg.ty).clone());
+    }
+
+    Ok(Entrypoint {
+        method,
+        mutates,
+        arg_names,
+        arg_types,
+        output: result_output(sig)?,
+    })
+}
+
+/// `T` of a `ContractResult<T>` return type.
+fn result_output(sig: &Signature) -> syn::Result<&Type> {
+    if let ReturnType::Type(_, ty) = &sig.output
+        && let Type::Path(path) = &**ty
+        && let Some(segment) = path.path.segments.last()
+        && segment.ident == "ContractResult"
+        && let PathArguments::AngleBracketed(arguments) = &segment.arguments
+        && arguments.args.len() == 1
+        && let Some(GenericArgument::Type(inner)) = arguments.args.first()
+    {
+        return Ok(inner);
+    }
+    Err(syn::Error::new(
+        sig.span(),
+        "entrypoints must return `ContractResult<T>`",
+    ))
+}
+
+fn is_unit(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+fn expand_exports(item: ItemImpl) -> syn::Result<TokenStream2> {
+    let Some((None, trait_path, _)) = &item.trait_ else {
+        return Err(syn::Error::new(
+            item.span(),
+            "export_interface goes on `impl Interface for Contract`",
+        ));
+    };
+    let Type::Path(contract) = &*item.self_ty else {
+        return Err(syn::Error::new(
+            item.self_ty.span(),
+            "the exported contract must be a unit struct",
+        ));
+    };
+
+    let sdk = quote!(::silica_contract_sdk);
+    let methods = item
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            ImplItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let idents = methods
+        .iter()
+        .map(|method| &method.sig.ident)
+        .collect::<Vec<_>>();
+    let names = idents
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect::<Vec<_>>();
+    let exports = methods.iter().zip(&names).map(|(method, name)| {
+        let ident = &method.sig.ident;
+        let docs = method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        quote! {
+            #(#docs)*
+            #[cfg_attr(target_arch = "wasm32", unsafe(no_mangle))]
+            pub extern "C" fn #ident() {
+                #sdk::interface::serve(#name, |entrypoint, input| {
+                    <#contract as #trait_path>::dispatch(&mut #contract, entrypoint, input)
+                });
+            }
+        }
+    });
+
+    Ok(quote! {
+        #item
+
+        impl #contract {
+            /// Exported entrypoints with the functions the host calls, which
+            /// take no wasm parameters.
+            pub const EXPORTS: &'static [(&'static str, extern "C" fn())] =
+                &[#((#names, #idents)),*];
+        }
+
+        #(#exports)*
+    })
+}
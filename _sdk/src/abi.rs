@@ -0,0 +1,800 @@
+//! Machine-readable contract ABI
+//!
+//! A [`ContractAbi`] describes a contract's entrypoints, events, errors and
+//! storage layout, with every argument and value type spelled out in the serde
+//! data model, which is what postcard encodes. Clients in any language can
+//! derive encoders from it instead of reading README tables.
+//!
+//! Contracts declare their ABI with [`AbiBuilder`] and keep the generated JSON
+//! under version control. Entrypoints come from the contract's
+//! [`contract_interface`](crate::interface::contract_interface) through
+//! [`AbiBuilder::interface`], so the ABI lists exactly what the exported
+//! entrypoints decode. SDK modules that keep storage or emit events declare
+//! them too, such as `access_control` for
+//! [`AccessControl`](crate::security::AccessControl). A test calls
+//! [`check_file`], which fails when the interface changes incompatibly:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn abi_is_compatible() {
+//!     let abi = AbiBuilder::new("timelock", env!("CARGO_PKG_VERSION"))
+//!         .interface(TimelockRef::describe)
+//!         .access_control(&[PROPOSER_ROLE])
+//!         .event::<OperationCancelled>("OperationCancelled")
+//!         .map::<[u8; 32], Operation>("operations")
+//!         .build()
+//!         .unwrap();
+//!     abi::check_file(&abi, concat!(env!("CARGO_MANIFEST_DIR"), "/abi.json")).unwrap();
+//! }
+//! ```
+//!
+//! Run the test with `UPDATE_ABI=1` to accept compatible changes. Incompatible
+//! ones are never written over; delete the file to start a new baseline.
+//!
+//! Entrypoint arguments are encoded together as one postcard tuple, in the
+//! order they are declared. Enums nested inside other types cannot be traced
+//! automatically; register them with [`AbiBuilder::ty`].
+
+extern crate std;
+
+use crate::address::{Address, AddressKind};
+use crate::error::{ContractError, ContractResult};
+use crate::interface::InterfaceVisitor;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_reflection::{
+    ContainerFormat, Format, FormatHolder, Named, Registry, Tracer, TracerConfig, VariantFormat,
+};
+
+/// Version of the ABI document format.
+pub const ABI_VERSION: u32 = 1;
+
+/// Environment variable that lets [`check_file`] rewrite a stale ABI file.
+pub const UPDATE_ENV: &str = "UPDATE_ABI";
+
+/// A contract's public interface.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContractAbi {
+    pub abi_version: u32,
+    pub name: String,
+    pub version: String,
+    /// Wire encoding of arguments, return values, events and storage.
+    pub encoding: String,
+    pub entrypoints: Vec<EntrypointAbi>,
+    pub events: Vec<EventAbi>,
+    pub errors: Vec<ErrorAbi>,
+    pub storage: Vec<StorageAbi>,
+    /// Named types referenced by `{"TYPENAME": ..}` formats above.
+    pub types: Registry,
+}
+
+/// An exported function.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntrypointAbi {
+    pub name: String,
+    /// Whether calling it may change state; views can be simulated.
+    pub mutates: bool,
+    pub args: Vec<ArgAbi>,
+    /// Format of the return data, or `None` if nothing is returned.
+    pub returns: Option<Format>,
+}
+
+/// One entrypoint argument.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArgAbi {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Format,
+}
+
+/// An event topic and the format of its data.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventAbi {
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub ty: Format,
+}
+
+/// A failure a contract reports to its callers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorAbi {
+    pub name: String,
+    pub description: String,
+}
+
+/// A storage key, or the prefix of a `Map` or `Vector`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StorageAbi {
+    pub key: String,
+    #[serde(flatten)]
+    pub kind: StorageKind,
+}
+
+/// What is stored under a [`StorageAbi`] key.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageKind {
+    /// A single value stored under `key`.
+    Value {
+        #[serde(rename = "type")]
+        ty: Format,
+    },
+    /// Entries stored under `key:hex(blake3(encoded key))`.
+    Map {
+        key_type: Format,
+        value_type: Format,
+    },
+    /// Elements under `key::item::<index>` and the length under `key::len`.
+    Vector { element: Format },
+}
+
+/// Declares a [`ContractAbi`], tracing each type as it is added.
+pub struct AbiBuilder {
+    abi: ContractAbi,
+    tracer: Tracer,
+    error: Option<String>,
+}
+
+fn reflection_error(error: serde_reflection::Error) -> String {
+    format!("{}", error)
+}
+
+impl AbiBuilder {
+    pub fn new(name: &str, version: &str) -> Self {
+        let mut builder = Self {
+            abi: ContractAbi {
+                abi_version: ABI_VERSION,
+                name: name.to_string(),
+                version: version.to_string(),
+                encoding: String::from("postcard"),
+                entrypoints: Vec::new(),
+                events: Vec::new(),
+                errors: Vec::new(),
+                storage: Vec::new(),
+                types: Registry::new(),
+            },
+            tracer: Tracer::new(TracerConfig::default()),
+            error: None,
+        };
+        // SDK types that appear in most interfaces.
+        builder.trace::<AddressKind>();
+        builder.trace::<Address>();
+        builder.trace::<ContractError>();
+        builder
+    }
+
+    fn trace<T: DeserializeOwned>(&mut self) -> Format {
+        let traced = self
+            .tracer
+            .trace_simple_type::<T>()
+            .map_err(reflection_error)
+            .and_then(|(mut format, _)| {
+                format.normalize().map_err(reflection_error)?;
+                Ok(format)
+            });
+        match traced {
+            Ok(format) => format,
+            Err(error) => {
+                self.fail(format!(
+                    "cannot trace {}: {}",
+                    core::any::type_name::<T>(),
+                    error
+                ));
+                Format::Unit
+            }
+        }
+    }
+
+    fn fail(&mut self, error: String) {
+        self.error.get_or_insert(error);
+    }
+
+    fn push_entrypoint(mut self, name: &str, mutates: bool) -> Self {
+        self.visit_entrypoint(name, mutates);
+        self
+    }
+
+    /// Declare every entrypoint of a
+    /// [`contract_interface`](crate::interface::contract_interface), as
+    /// `.interface(TimelockRef::describe)`.
+    pub fn interface(mut self, describe: impl FnOnce(&mut Self)) -> Self {
+        describe(&mut self);
+        self
+    }
+
+    /// Start a state-changing entrypoint; following [`arg`](Self::arg) and
+    /// [`returns`](Self::returns) calls describe it.
+    pub fn entrypoint(self, name: &str) -> Self {
+        self.push_entrypoint(name, true)
+    }
+
+    /// Start a read-only entrypoint.
+    pub fn view(self, name: &str) -> Self {
+        self.push_entrypoint(name, false)
+    }
+
+    /// Add an argument to the current entrypoint.
+    pub fn arg<T: DeserializeOwned>(mut self, name: &str) -> Self {
+        self.visit_arg::<T>(name);
+        self
+    }
+
+    /// Set the return type of the current entrypoint.
+    pub fn returns<T: DeserializeOwned>(mut self) -> Self {
+        self.visit_returns::<T>();
+        self
+    }
+
+    /// Declare an event emitted with [`emit`](crate::events::emit).
+    pub fn event<T: DeserializeOwned>(mut self, topic: &str) -> Self {
+        let ty = self.trace::<T>();
+        self.abi.events.push(EventAbi {
+            topic: topic.to_string(),
+            ty,
+        });
+        self
+    }
+
+    /// Declare an event emitted with the [`event!`](crate::event) macro, which
+    /// sends every field as a string.
+    pub fn string_event(mut self, topic: &str, fields: &[&str]) -> Self {
+        let fields = fields
+            .iter()
+            .map(|field| Named {
+                name: field.to_string(),
+                value: Format::Str,
+            })
+            .collect();
+        self.abi
+            .types
+            .insert(topic.to_string(), ContainerFormat::Struct(fields));
+        self.abi.events.push(EventAbi {
+            topic: topic.to_string(),
+            ty: Format::TypeName(topic.to_string()),
+        });
+        self
+    }
+
+    /// Declare a contract-specific failure.
+    pub fn error(mut self, name: &str, description: &str) -> Self {
+        self.abi.errors.push(ErrorAbi {
+            name: name.to_string(),
+            description: description.to_string(),
+        });
+        self
+    }
+
+    /// Declare a single value stored under `key`.
+    pub fn value<T: DeserializeOwned>(mut self, key: &str) -> Self {
+        let ty = self.trace::<T>();
+        self.push_storage(key, StorageKind::Value { ty })
+    }
+
+    /// Declare a [`Map`](crate::storage::Map) with prefix `prefix`.
+    pub fn map<K: DeserializeOwned, V: DeserializeOwned>(mut self, prefix: &str) -> Self {
+        let key = self.trace::<K>();
+        let value = self.trace::<V>();
+        self.push_storage(
+            prefix,
+            StorageKind::Map {
+                key_type: key,
+                value_type: value,
+            },
+        )
+    }
+
+    /// Declare a [`Vector`](crate::storage::Vector) with prefix `prefix`.
+    pub fn vector<T: DeserializeOwned>(mut self, prefix: &str) -> Self {
+        let element = self.trace::<T>();
+        self.push_storage(prefix, StorageKind::Vector { element })
+    }
+
+    fn push_storage(mut self, key: &str, kind: StorageKind) -> Self {
+        self.abi.storage.push(StorageAbi {
+            key: key.to_string(),
+            kind,
+        });
+        self
+    }
+
+    /// Trace `T` without referencing it; needed for enums nested in other types.
+    pub fn ty<T: DeserializeOwned>(mut self) -> Self {
+        self.trace::<T>();
+        self
+    }
+
+    /// Finish the ABI, failing if a type could not be traced or a nested enum
+    /// was not registered with [`ty`](Self::ty).
+    pub fn build(self) -> ContractResult<ContractAbi> {
+        if let Some(error) = self.error {
+            return Err(ContractError::InvalidArgument(format!(
+                "Invalid ABI: {}",
+                error
+            )));
+        }
+        let traced = self.tracer.registry().map_err(|error| {
+            ContractError::InvalidArgument(format!(
+                "Invalid ABI: {} (register nested enums with AbiBuilder::ty)",
+                error
+            ))
+        })?;
+        let mut abi = self.abi;
+        abi.types.extend(traced);
+        Ok(abi)
+    }
+}
+
+impl InterfaceVisitor for AbiBuilder {
+    fn visit_entrypoint(&mut self, name: &str, mutates: bool) {
+        self.abi.entrypoints.push(EntrypointAbi {
+            name: name.to_string(),
+            mutates,
+            args: Vec::new(),
+            returns: None,
+        });
+    }
+
+    fn visit_arg<T: DeserializeOwned>(&mut self, name: &str) {
+        let ty = self.trace::<T>();
+        match self.abi.entrypoints.last_mut() {
+            Some(entrypoint) => entrypoint.args.push(ArgAbi {
+                name: name.to_string(),
+                ty,
+            }),
+            None => self.fail(format!(
+                "argument `{}` declared before any entrypoint",
+                name
+            )),
+        }
+    }
+
+    fn visit_returns<T: DeserializeOwned>(&mut self) {
+        let ty = self.trace::<T>();
+        match self.abi.entrypoints.last_mut() {
+            Some(entrypoint) => entrypoint.returns = Some(ty),
+            None => self.fail(String::from("return type declared before any entrypoint")),
+        }
+    }
+}
+
+impl ContractAbi {
+    /// Pretty-printed JSON document.
+    pub fn to_json(&self) -> ContractResult<String> {
+        serde_json::to_string_pretty(self).map_err(|_| ContractError::SerializationFailed)
+    }
+
+    pub fn from_json(json: &str) -> ContractResult<Self> {
+        serde_json::from_str(json).map_err(|_| ContractError::DeserializationFailed)
+    }
+
+    /// Check that clients built against `previous` still work with `self`.
+    ///
+    /// Entrypoints, events, errors and storage entries may be added. Removing
+    /// any of them, or changing how their arguments, return values or data
+    /// are encoded, is incompatible. Type renames that keep the encoding are
+    /// fine, as are new enum variants appended after the existing ones.
+    pub fn check_compatible_with(&self, previous: &ContractAbi) -> ContractResult<()> {
+        let mut conflicts = Vec::new();
+        let compare = |conflicts: &mut Vec<String>, what: String, old: &Format, new: &Format| {
+            if !same_encoding(old, &previous.types, new, &self.types, &mut BTreeSet::new()) {
+                conflicts.push(format!("{} changed encoding", what));
+            }
+        };
+
+        for old in &previous.entrypoints {
+            let Some(new) = self.entrypoints.iter().find(|new| new.name == old.name) else {
+                conflicts.push(format!("entrypoint `{}` was removed", old.name));
+                continue;
+            };
+            if new.args.len() != old.args.len() {
+                conflicts.push(format!("entrypoint `{}` changed arity", old.name));
+            } else {
+                for (old_arg, new_arg) in old.args.iter().zip(&new.args) {
+                    compare(
+                        &mut conflicts,
+                        format!("argument `{}` of `{}`", old_arg.name, old.name),
+                        &old_arg.ty,
+                        &new_arg.ty,
+                    );
+                }
+            }
+            match (&old.returns, &new.returns) {
+                (Some(old_ty), Some(new_ty)) => compare(
+                    &mut conflicts,
+                    format!("return value of `{}`", old.name),
+                    old_ty,
+                    new_ty,
+                ),
+                (None, None) => {}
+                _ => conflicts.push(format!("return value of `{}` changed", old.name)),
+            }
+        }
+        for old in &previous.events {
+            match self.events.iter().find(|new| new.topic == old.topic) {
+                Some(new) => compare(
+                    &mut conflicts,
+                    format!("event `{}`", old.topic),
+                    &old.ty,
+                    &new.ty,
+                ),
+                None => conflicts.push(format!("event `{}` was removed", old.topic)),
+            }
+        }
+        for old in &previous.errors {
+            if !self.errors.iter().any(|new| new.name == old.name) {
+                conflicts.push(format!("error `{}` was removed", old.name));
+            }
+        }
+        for old in &previous.storage {
+            let Some(new) = self.storage.iter().find(|new| new.key == old.key) else {
+                conflicts.push(format!("storage `{}` was removed", old.key));
+                continue;
+            };
+            let what = format!("storage `{}`", old.key);
+            match (&old.kind, &new.kind) {
+                (StorageKind::Value { ty: old_ty }, StorageKind::Value { ty: new_ty }) => {
+                    compare(&mut conflicts, what, old_ty, new_ty)
+                }
+                (
+                    StorageKind::Map {
+                        key_type: old_key,
+                        value_type: old_value,
+                    },
+                    StorageKind::Map {
+                        key_type: new_key,
+                        value_type: new_value,
+                    },
+                ) => {
+                    compare(&mut conflicts, format!("{} key", what), old_key, new_key);
+                    compare(&mut conflicts, what, old_value, new_value);
+                }
+                (
+                    StorageKind::Vector { element: old_ty },
+                    StorageKind::Vector { element: new_ty },
+                ) => compare(&mut conflicts, what, old_ty, new_ty),
+                _ => conflicts.push(format!("{} changed kind", what)),
+            }
+        }
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(ContractError::InvalidArgument(format!(
+                "Incompatible ABI change: {}",
+                conflicts.join(", ")
+            )))
+        }
+    }
+}
+
+/// Whether values encoded as `old` decode as `new` and vice versa.
+pub(crate) fn same_encoding(
+    old: &Format,
+    old_types: &Registry,
+    new: &Format,
+    new_types: &Registry,
+    seen: &mut BTreeSet<(String, String)>,
+) -> bool {
+    let same_all = |old: &[Format], new: &[Format], seen: &mut BTreeSet<_>| {
+        old.len() == new.len()
+            && old
+                .iter()
+                .zip(new)
+                .all(|(old, new)| same_encoding(old, old_types, new, new_types, seen))
+    };
+    match (old, new) {
+        (Format::TypeName(old_name), Format::TypeName(new_name)) => {
+            // Recursive types: assume equal while the pair is being compared.
+            if !seen.insert((old_name.clone(), new_name.clone())) {
+                return true;
+            }
+            match (old_types.get(old_name), new_types.get(new_name)) {
+                (Some(old), Some(new)) => same_container(old, old_types, new, new_types, seen),
+                _ => false,
+            }
+        }
+        (Format::Option(old), Format::Option(new)) | (Format::Seq(old), Format::Seq(new)) => {
+            same_encoding(old, old_types, new, new_types, seen)
+        }
+        (
+            Format::Map {
+                key: old_key,
+                value: old_value,
+            },
+            Format::Map {
+                key: new_key,
+                value: new_value,
+            },
+        ) => {
+            same_encoding(old_key, old_types, new_key, new_types, seen)
+                && same_encoding(old_value, old_types, new_value, new_types, seen)
+        }
+        (Format::Tuple(old), Format::Tuple(new)) => same_all(old, new, seen),
+        (
+            Format::TupleArray {
+                content: old,
+                size: old_size,
+            },
+            Format::TupleArray {
+                content: new,
+                size: new_size,
+            },
+        ) => old_size == new_size && same_encoding(old, old_types, new, new_types, seen),
+        (Format::Variable(_), _) | (_, Format::Variable(_)) => false,
+        _ => old == new,
+    }
+}
+
+fn same_container(
+    old: &ContainerFormat,
+    old_types: &Registry,
+    new: &ContainerFormat,
+    new_types: &Registry,
+    seen: &mut BTreeSet<(String, String)>,
+) -> bool {
+    let same_fields = |old: &[Named<Format>], new: &[Named<Format>], seen: &mut BTreeSet<_>| {
+        old.len() == new.len()
+            && old.iter().zip(new).all(|(old, new)| {
+                old.name == new.name
+                    && same_encoding(&old.value, old_types, &new.value, new_types, seen)
+            })
+    };
+    let same_list = |old: &[Format], new: &[Format], seen: &mut BTreeSet<_>| {
+        old.len() == new.len()
+            && old
+                .iter()
+                .zip(new)
+                .all(|(old, new)| same_encoding(old, old_types, new, new_types, seen))
+    };
+    match (old, new) {
+        (ContainerFormat::UnitStruct, ContainerFormat::UnitStruct) => true,
+        (ContainerFormat::NewTypeStruct(old), ContainerFormat::NewTypeStruct(new)) => {
+            same_encoding(old, old_types, new, new_types, seen)
+        }
+        (ContainerFormat::TupleStruct(old), ContainerFormat::TupleStruct(new)) => {
+            same_list(old, new, seen)
+        }
+        (ContainerFormat::Struct(old), ContainerFormat::Struct(new)) => same_fields(old, new, seen),
+        (ContainerFormat::Enum(old), ContainerFormat::Enum(new)) => {
+            old.iter().all(|(index, old)| match new.get(index) {
+                Some(new) if new.name == old.name => match (&old.value, &new.value) {
+                    (VariantFormat::Unit, VariantFormat::Unit) => true,
+                    (VariantFormat::NewType(old), VariantFormat::NewType(new)) => {
+                        same_encoding(old, old_types, new, new_types, seen)
+                    }
+                    (VariantFormat::Tuple(old), VariantFormat::Tuple(new)) => {
+                        same_list(old, new, seen)
+                    }
+                    (VariantFormat::Struct(old), VariantFormat::Struct(new)) => {
+                        same_fields(old, new, seen)
+                    }
+                    _ => false,
+                },
+                _ => false,
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Compare `abi` with the ABI committed at `path`.
+///
+/// - A missing file is created.
+/// - An incompatible change fails, listing what broke.
+/// - A compatible change fails as stale unless [`UPDATE_ENV`] is set, in
+///   which case the file is rewritten.
+pub fn check_file(abi: &ContractAbi, path: &str) -> ContractResult<()> {
+    let io_error = |error: std::io::Error| {
+        ContractError::Custom(format!("Cannot access ABI file {}: {}", path, error))
+    };
+    let current = abi.to_json()? + "\n";
+    let committed = match std::fs::read_to_string(path) {
+        Ok(committed) => committed,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return std::fs::write(path, current).map_err(io_error);
+        }
+        Err(error) => return Err(io_error(error)),
+    };
+    if committed == current {
+        return Ok(());
+    }
+
+    let previous = ContractAbi::from_json(&committed)?;
+    abi.check_compatible_with(&previous)?;
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        return std::fs::write(path, current).map_err(io_error);
+    }
+    Err(ContractError::Custom(format!(
+        "ABI file {} is out of date; rerun with {}=1 to update it",
+        path, UPDATE_ENV
+    )))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::security::safe_math::Amount;
+
+    #[derive(Serialize, Deserialize)]
+    enum Status {
+        Open,
+        Closed { at: u64 },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Order {
+        owner: Address,
+        amount: Amount,
+        status: Status,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum StatusV2 {
+        Open,
+        Closed { at: u64 },
+        Disputed,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Order")]
+    struct OrderV2 {
+        owner: Address,
+        amount: Amount,
+        status: StatusV2,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Order")]
+    struct OrderV3 {
+        owner: Address,
+        amount: u64,
+        status: StatusV2,
+    }
+
+    fn base() -> AbiBuilder {
+        AbiBuilder::new("orders", "1.0.0")
+            .entrypoint("place")
+            .arg::<Amount>("amount")
+            .returns::<u64>()
+            .view("get_order")
+            .arg::<u64>("id")
+            .returns::<Option<Order>>()
+            .ty::<Status>()
+            .event::<Order>("OrderPlaced")
+            .error("OrderClosed", "The order is no longer open")
+            .value::<u64>("next_id")
+            .map::<u64, Order>("orders")
+    }
+
+    #[test]
+    fn abi_describes_the_interface() {
+        let abi = base().build().expect("abi");
+        let place = &abi.entrypoints[0];
+        assert!(place.mutates && !abi.entrypoints[1].mutates);
+        assert_eq!(place.args[0].ty, Format::U128);
+        assert_eq!(
+            abi.entrypoints[1].returns,
+            Some(Format::Option(Box::new(Format::TypeName("Order".into()))))
+        );
+        assert!(abi.types.contains_key("AddressKind"));
+        assert!(abi.types.contains_key("ContractError"));
+
+        let json = abi.to_json().expect("json");
+        assert!(json.contains("\"kind\": \"map\""));
+        assert_eq!(ContractAbi::from_json(&json).expect("parse"), abi);
+    }
+
+    // Only described here, never implemented.
+    #[allow(dead_code)]
+    #[crate::interface::contract_interface]
+    trait Orders {
+        fn place(&mut self, amount: Amount) -> ContractResult<u64>;
+        fn get_order(&self, id: u64) -> ContractResult<Option<Order>>;
+    }
+
+    #[test]
+    fn interfaces_declare_what_they_decode() {
+        let abi = AbiBuilder::new("orders", "1.0.0")
+            .interface(OrdersRef::describe)
+            .ty::<Status>()
+            .build()
+            .expect("abi");
+        assert_eq!(abi.entrypoints, base().build().unwrap().entrypoints);
+    }
+
+    #[test]
+    fn nested_enums_must_be_registered() {
+        let result = AbiBuilder::new("orders", "1.0.0")
+            .event::<Order>("OrderPlaced")
+            .build();
+        assert!(
+            matches!(result, Err(ContractError::InvalidArgument(message)) if message.contains("Status"))
+        );
+        assert!(
+            AbiBuilder::new("orders", "1.0.0")
+                .arg::<u8>("x")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn additions_are_compatible() {
+        let v1 = base().build().unwrap();
+        let v2 = AbiBuilder::new("orders", "1.1.0")
+            .entrypoint("place")
+            .arg::<Amount>("amount")
+            .returns::<u64>()
+            .view("get_order")
+            .arg::<u64>("id")
+            .returns::<Option<OrderV2>>()
+            .ty::<StatusV2>()
+            .event::<OrderV2>("OrderPlaced")
+            .event::<u64>("OrderCancelled")
+            .error("OrderClosed", "The order is no longer open")
+            .value::<u64>("next_id")
+            .map::<u64, OrderV2>("orders")
+            .value::<bool>("paused")
+            .build()
+            .unwrap();
+        v2.check_compatible_with(&v1).expect("compatible");
+        // Going back drops an event and an enum variant.
+        assert!(v1.check_compatible_with(&v2).is_err());
+    }
+
+    #[test]
+    fn encoding_changes_and_removals_are_incompatible() {
+        let v1 = base().build().unwrap();
+        let v2 = AbiBuilder::new("orders", "2.0.0")
+            .entrypoint("place")
+            .arg::<u64>("amount")
+            .returns::<u64>()
+            .ty::<StatusV2>()
+            .event::<OrderV3>("OrderPlaced")
+            .value::<u64>("next_id")
+            .vector::<OrderV3>("orders")
+            .build()
+            .unwrap();
+
+        let Err(ContractError::InvalidArgument(message)) = v2.check_compatible_with(&v1) else {
+            panic!("expected incompatibility");
+        };
+        for conflict in [
+            "argument `amount` of `place` changed encoding",
+            "entrypoint `get_order` was removed",
+            "event `OrderPlaced` changed encoding",
+            "error `OrderClosed` was removed",
+            "storage `orders` changed kind",
+        ] {
+            assert!(message.contains(conflict), "{}", message);
+        }
+    }
+
+    #[test]
+    fn check_file_guards_the_committed_abi() {
+        let path = std::env::temp_dir().join(format!("silica-abi-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let v1 = base().build().unwrap();
+        check_file(&v1, path).expect("baseline written");
+        check_file(&v1, path).expect("unchanged");
+
+        let grown = base().view("count").returns::<u64>().build().unwrap();
+        assert!(matches!(
+            check_file(&grown, path),
+            Err(ContractError::Custom(_))
+        ));
+
+        let broken = AbiBuilder::new("orders", "2.0.0").build().unwrap();
+        assert!(matches!(
+            check_file(&broken, path),
+            Err(ContractError::InvalidArgument(_))
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
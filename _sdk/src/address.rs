@@ -0,0 +1,312 @@
+//! Chain addresses (bech32m, `chert1...`)
+//!
+//! An address is a kind byte followed by a 32-byte payload, encoded as bech32m
+//! under the `chert` human-readable part. Account addresses commit to the BLAKE3
+//! hash of the owner's public key; contract addresses are assigned by the
+//! runtime at deployment.
+
+use crate::error::{ContractError, ContractResult};
+use alloc::string::String;
+use alloc::vec::Vec;
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{Bech32m, Hrp};
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Human-readable part shared by every address on the chain.
+pub const ADDRESS_HRP: &str = "chert";
+
+/// Length of the address payload in bytes.
+pub const ADDRESS_PAYLOAD_LENGTH: usize = 32;
+
+const HRP: Hrp = Hrp::parse_unchecked(ADDRESS_HRP);
+
+/// What an address refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AddressKind {
+    /// An externally owned account controlled by a key pair.
+    Account,
+    /// A deployed contract.
+    Contract,
+}
+
+impl AddressKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            AddressKind::Account => 0,
+            AddressKind::Contract => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(AddressKind::Account),
+            1 => Some(AddressKind::Contract),
+            _ => None,
+        }
+    }
+}
+
+/// A validated chain address.
+///
+/// The canonical lowercase encoding is kept alongside the raw bytes, so the
+/// address can be used wherever a `&str` is expected without re-encoding.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address {
+    kind: AddressKind,
+    payload: [u8; ADDRESS_PAYLOAD_LENGTH],
+    encoded: String,
+}
+
+impl Address {
+    /// Build an address from its kind and raw payload.
+    pub fn new(kind: AddressKind, payload: [u8; ADDRESS_PAYLOAD_LENGTH]) -> Self {
+        let mut data = [0u8; ADDRESS_PAYLOAD_LENGTH + 1];
+        data[0] = kind.to_byte();
+        data[1..].copy_from_slice(&payload);
+        let encoded = bech32::encode_lower::<Bech32m>(HRP, &data)
+            .expect("fixed-size payload is always encodable");
+
+        Self {
+            kind,
+            payload,
+            encoded,
+        }
+    }
+
+    /// Account address for an Ed25519 public key.
+    pub fn from_public_key(public_key: &[u8; 32]) -> Self {
+        Self::new(AddressKind::Account, *blake3::hash(public_key).as_bytes())
+    }
+
+    /// Contract address with the given payload.
+    pub fn contract(payload: [u8; ADDRESS_PAYLOAD_LENGTH]) -> Self {
+        Self::new(AddressKind::Contract, payload)
+    }
+
+    /// Parse and validate a bech32m-encoded address.
+    pub fn parse(value: &str) -> ContractResult<Self> {
+        let checked = CheckedHrpstring::new::<Bech32m>(value)
+            .map_err(|_| invalid_address("Invalid address checksum or encoding"))?;
+        if !checked.hrp().as_str().eq_ignore_ascii_case(ADDRESS_HRP) {
+            return Err(invalid_address("Invalid address prefix"));
+        }
+
+        let data: Vec<u8> = checked.byte_iter().collect();
+        if data.len() != ADDRESS_PAYLOAD_LENGTH + 1 {
+            return Err(invalid_address("Invalid address length"));
+        }
+        let kind = AddressKind::from_byte(data[0])
+            .ok_or_else(|| invalid_address("Unknown address kind"))?;
+
+        let mut payload = [0u8; ADDRESS_PAYLOAD_LENGTH];
+        payload.copy_from_slice(&data[1..]);
+        Ok(Self::new(kind, payload))
+    }
+
+    /// Parse an address and require it to be of `kind`.
+    pub fn parse_kind(value: &str, kind: AddressKind) -> ContractResult<Self> {
+        let address = Self::parse(value)?;
+        if address.kind != kind {
+            return Err(invalid_address("Unexpected address kind"));
+        }
+        Ok(address)
+    }
+
+    /// Whether this is an account or a contract address.
+    pub fn kind(&self) -> AddressKind {
+        self.kind
+    }
+
+    /// Whether this address refers to a deployed contract.
+    pub fn is_contract(&self) -> bool {
+        self.kind == AddressKind::Contract
+    }
+
+    /// Raw 32-byte payload.
+    pub fn payload(&self) -> &[u8; ADDRESS_PAYLOAD_LENGTH] {
+        &self.payload
+    }
+
+    /// Canonical bech32m encoding.
+    pub fn as_str(&self) -> &str {
+        &self.encoded
+    }
+}
+
+fn invalid_address(message: &'static str) -> ContractError {
+    ContractError::InvalidArgument(String::from(message))
+}
+
+impl FromStr for Address {
+    type Err = ContractError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encoded)
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Address({})", self.encoded)
+    }
+}
+
+impl Deref for Address {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.encoded
+    }
+}
+
+impl AsRef<str> for Address {
+    fn as_ref(&self) -> &str {
+        &self.encoded
+    }
+}
+
+impl PartialEq<str> for Address {
+    fn eq(&self, other: &str) -> bool {
+        self.encoded == other
+    }
+}
+
+impl PartialEq<&str> for Address {
+    fn eq(&self, other: &&str) -> bool {
+        self.encoded == *other
+    }
+}
+
+impl PartialEq<String> for Address {
+    fn eq(&self, other: &String) -> bool {
+        &self.encoded == other
+    }
+}
+
+/// Human-readable formats get the bech32m string; binary formats such as
+/// postcard get the compact `(kind, payload)` pair.
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.encoded)
+        } else {
+            (self.kind, self.payload).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            Address::parse(&encoded).map_err(|_| D::Error::custom("invalid address"))
+        } else {
+            let (kind, payload) =
+                <(AddressKind, [u8; ADDRESS_PAYLOAD_LENGTH])>::deserialize(deserializer)?;
+            Ok(Address::new(kind, payload))
+        }
+    }
+}
+
+/// Borsh encodes the kind as one byte followed by the 32-byte payload.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Address {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        writer.write_all(&[self.kind.to_byte()])?;
+        writer.write_all(&self.payload)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Address {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let kind = AddressKind::from_byte(u8::deserialize_reader(reader)?).ok_or_else(|| {
+            borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, "invalid address kind")
+        })?;
+        let payload = <[u8; ADDRESS_PAYLOAD_LENGTH]>::deserialize_reader(reader)?;
+        Ok(Address::new(kind, payload))
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn encoding_roundtrips() {
+        let address = Address::new(AddressKind::Account, [7; 32]);
+        assert!(address.as_str().starts_with("chert1"));
+
+        let parsed = Address::parse(address.as_str()).expect("parse");
+        assert_eq!(parsed, address);
+        assert_eq!(parsed.kind(), AddressKind::Account);
+        assert_eq!(parsed.payload(), &[7; 32]);
+
+        let upper = address.as_str().to_ascii_uppercase();
+        assert_eq!(Address::parse(&upper).expect("uppercase parse"), address);
+    }
+
+    #[test]
+    fn kinds_are_distinguished() {
+        let account = Address::new(AddressKind::Account, [1; 32]);
+        let contract = Address::contract([1; 32]);
+        assert_ne!(account, contract);
+        assert!(contract.is_contract());
+
+        assert!(Address::parse_kind(contract.as_str(), AddressKind::Contract).is_ok());
+        assert!(Address::parse_kind(contract.as_str(), AddressKind::Account).is_err());
+    }
+
+    #[test]
+    fn invalid_addresses_are_rejected() {
+        let valid = Address::new(AddressKind::Account, [3; 32]).to_string();
+
+        // Single-character typo breaks the checksum.
+        let mut typo = valid.clone().into_bytes();
+        let last = typo.len() - 1;
+        typo[last] = if typo[last] == b'q' { b'p' } else { b'q' };
+        let typo = String::from_utf8(typo).unwrap();
+
+        for candidate in [
+            "",
+            "target_contract_address",
+            "chert1sender000000000000000000",
+            typo.as_str(),
+            &valid.replacen("chert", "cosmos", 1),
+        ] {
+            assert!(Address::parse(candidate).is_err(), "{candidate} accepted");
+        }
+
+        let legacy_bech32 =
+            bech32::encode::<bech32::Bech32>(HRP, &[0u8; ADDRESS_PAYLOAD_LENGTH + 1]).unwrap();
+        assert!(Address::parse(&legacy_bech32).is_err());
+    }
+
+    #[test]
+    fn postcard_encoding_is_compact() {
+        let address = Address::contract([9; 32]);
+        let bytes = postcard::to_allocvec(&address).expect("encode");
+        assert_eq!(bytes.len(), 1 + ADDRESS_PAYLOAD_LENGTH);
+
+        let decoded: Address = postcard::from_bytes(&bytes).expect("decode");
+        assert_eq!(decoded, address);
+    }
+
+    #[test]
+    fn public_key_addresses_are_accounts() {
+        let address = Address::from_public_key(&[5; 32]);
+        assert_eq!(address.kind(), AddressKind::Account);
+        assert_eq!(address.payload(), blake3::hash(&[5; 32]).as_bytes());
+    }
+}
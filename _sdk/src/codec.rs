@@ -0,0 +1,274 @@
+//! Serialization codecs for storage, call data, return data and events
+//!
+//! [`Postcard`] is the default everywhere. A contract whose state or payloads
+//! must be readable by clients in other languages can pick another codec and
+//! use it consistently:
+//!
+//! - [`Borsh`] (feature `borsh`): a fixed, well-specified binary layout with
+//!   libraries in most languages. Types derive `BorshSerialize` and
+//!   `BorshDeserialize` instead of (or alongside) serde.
+//! - [`Json`] (feature `json`): canonical JSON, for clients that want a
+//!   self-describing format. Equal values always encode to the same bytes.
+//!
+//! ```rust,ignore
+//! type Codec = silica_contract_sdk::codec::Borsh;
+//!
+//! let balances: Map<Address, Amount, Codec> = Map::new("balances");
+//! let args: TransferArgs = context().call_data_as::<Codec, _>()?;
+//! emit_with::<Codec, _>("Transfer", &event);
+//! ```
+//!
+//! A codec is part of a contract's storage layout: data written with one codec
+//! cannot be read back with another, and [`Map`](crate::storage::Map) hashes
+//! keys in their encoded form.
+
+use crate::error::{ContractError, ContractResult};
+use alloc::vec::Vec;
+
+#[cfg(feature = "json")]
+mod json;
+
+/// Encodes values of type `T`.
+///
+/// Codecs are stateless marker types; they are selected with a type parameter
+/// rather than passed around.
+pub trait Encode<T: ?Sized> {
+    fn encode(value: &T) -> ContractResult<Vec<u8>>;
+}
+
+/// Decodes values of type `T`.
+pub trait Decode<T> {
+    fn decode(bytes: &[u8]) -> ContractResult<T>;
+}
+
+/// A codec that can both encode and decode `T`.
+pub trait Codec<T>: Encode<T> + Decode<T> {}
+
+impl<C, T> Codec<T> for C where C: Encode<T> + Decode<T> {}
+
+/// The default codec: compact postcard encoding of serde types.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Postcard;
+
+impl<T: ?Sized + serde::Serialize> Encode<T> for Postcard {
+    fn encode(value: &T) -> ContractResult<Vec<u8>> {
+        postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+    }
+}
+
+impl<T> Decode<T> for Postcard
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    fn decode(bytes: &[u8]) -> ContractResult<T> {
+        postcard::from_bytes(bytes).map_err(|_| ContractError::DeserializationFailed)
+    }
+}
+
+/// Borsh encoding of types implementing the borsh traits.
+#[cfg(feature = "borsh")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Borsh;
+
+#[cfg(feature = "borsh")]
+impl<T: ?Sized + borsh::BorshSerialize> Encode<T> for Borsh {
+    fn encode(value: &T) -> ContractResult<Vec<u8>> {
+        borsh::to_vec(value).map_err(|_| ContractError::SerializationFailed)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<T> Decode<T> for Borsh
+where
+    T: borsh::BorshDeserialize,
+{
+    fn decode(bytes: &[u8]) -> ContractResult<T> {
+        borsh::from_slice(bytes).map_err(|_| ContractError::DeserializationFailed)
+    }
+}
+
+/// Canonical JSON encoding of serde types.
+///
+/// Output is compact UTF-8 with every object's keys, struct fields included,
+/// sorted by UTF-16 code units as in RFC 8785, so a `HashMap` encodes to the
+/// same bytes as the equal `BTreeMap`. Integers up to 2^53 - 1 in magnitude
+/// are JSON numbers; larger ones, such as most amounts, are decimal strings
+/// so JavaScript clients read them exactly. Decoding accepts integers in
+/// either form. Floats cannot be encoded. Addresses are bech32m strings.
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl<T: ?Sized + serde::Serialize> Encode<T> for Json {
+    fn encode(value: &T) -> ContractResult<Vec<u8>> {
+        json::to_vec(value)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> Decode<T> for Json
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    fn decode(bytes: &[u8]) -> ContractResult<T> {
+        json::from_slice(bytes)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::ffi::mock;
+    use crate::security::safe_math::Amount;
+    use crate::storage::Map;
+    use alloc::collections::BTreeMap;
+    use alloc::string::String;
+
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[cfg_attr(
+        feature = "borsh",
+        derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+    )]
+    struct Transfer {
+        from: Address,
+        to: Address,
+        amount: Amount,
+        memo: Option<String>,
+        tags: BTreeMap<String, u32>,
+    }
+
+    fn transfer() -> Transfer {
+        Transfer {
+            from: Address::from_public_key(&[1; 32]),
+            to: Address::contract([2; 32]),
+            amount: 5 * 10u128.pow(24),
+            memo: Some(String::from("rent")),
+            tags: [(String::from("b"), 2), (String::from("a"), 1)]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn round_trip<C: Codec<Transfer>>() -> Vec<u8> {
+        let value = transfer();
+        let bytes = C::encode(&value).expect("encode");
+        assert_eq!(C::decode(&bytes).expect("decode"), value);
+        assert_eq!(C::encode(&value).expect("re-encode"), bytes);
+        assert!(C::decode(&bytes[..bytes.len() - 1]).is_err());
+        bytes
+    }
+
+    /// Store and read back through `Map`, as a contract using `C` would.
+    fn stored_round_trip<C: Encode<Address> + Codec<Transfer>>() {
+        let value = transfer();
+        let mut transfers: Map<Address, Transfer, C> = Map::new("transfers");
+        transfers.set(&value.from, &value).expect("store");
+        assert_eq!(transfers.get(&value.from).expect("load"), Some(value));
+    }
+
+    #[test]
+    fn postcard_round_trips() {
+        let _guard = mock::test_env();
+        round_trip::<Postcard>();
+        stored_round_trip::<Postcard>();
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn borsh_round_trips() {
+        let _guard = mock::test_env();
+        stored_round_trip::<Borsh>();
+        let bytes = round_trip::<Borsh>();
+        // Addresses are a kind byte and a 32-byte payload; amounts 16 bytes.
+        assert_eq!(bytes.len(), 33 + 33 + 16 + 9 + 22);
+        assert_eq!((bytes[0], bytes[33]), (0, 1));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trips_canonically() {
+        let _guard = mock::test_env();
+        stored_round_trip::<Json>();
+        let bytes = round_trip::<Json>();
+        let text = core::str::from_utf8(&bytes).unwrap();
+        let value = transfer();
+        // Fields sort by name, and the amount is too large for a JSON number.
+        assert_eq!(
+            text,
+            alloc::format!(
+                "{{\"amount\":\"5000000000000000000000000\",\"from\":\"{}\",\"memo\":\"rent\",\
+                 \"tags\":{{\"a\":1,\"b\":2}},\"to\":\"{}\"}}",
+                value.from,
+                value.to
+            )
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_map_order_does_not_change_the_encoding() {
+        extern crate std;
+        use std::collections::HashMap;
+
+        let mut forward = HashMap::new();
+        let mut backward = HashMap::with_capacity(64);
+        for i in 0..32u64 {
+            forward.insert(alloc::format!("k{}", i), i);
+            backward.insert(alloc::format!("k{}", 31 - i), 31 - i);
+        }
+        let bytes = Json::encode(&forward).unwrap();
+        assert_eq!(Json::encode(&backward).unwrap(), bytes);
+        let sorted: BTreeMap<_, _> = forward.into_iter().collect();
+        assert_eq!(Json::encode(&sorted).unwrap(), bytes);
+        let decoded: HashMap<String, u64> = Json::decode(&bytes).unwrap();
+        assert_eq!(Json::encode(&decoded).unwrap(), bytes);
+
+        // Keys sort by UTF-16 code units: U+1F600 is a surrogate pair, D83D DE00.
+        let keys: BTreeMap<String, u8> = [
+            (String::from("\u{fb33}"), 1),
+            (String::from("\u{1f600}"), 2),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            Json::encode(&keys).unwrap(),
+            "{\"\u{1f600}\":2,\"\u{fb33}\":1}".as_bytes()
+        );
+
+        // Integer map keys are strings, and read back as integers.
+        let ids: BTreeMap<u64, bool> = [(10, true), (9, false)].into_iter().collect();
+        let bytes = Json::encode(&ids).unwrap();
+        assert_eq!(bytes, b"{\"10\":true,\"9\":false}");
+        assert_eq!(Json::decode(&bytes).ok(), Some(ids));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_writes_unsafe_integers_as_strings() {
+        const MAX_SAFE: u64 = (1 << 53) - 1;
+        let value = (
+            MAX_SAFE,
+            MAX_SAFE + 1,
+            -(MAX_SAFE as i64),
+            i128::MIN,
+            u128::MAX,
+        );
+        let bytes = Json::encode(&value).unwrap();
+        assert_eq!(
+            core::str::from_utf8(&bytes).unwrap(),
+            alloc::format!(
+                "[9007199254740991,\"9007199254740992\",-9007199254740991,\"{}\",\"{}\"]",
+                i128::MIN,
+                u128::MAX
+            )
+        );
+        assert_eq!(Json::decode(&bytes).ok(), Some(value));
+        // Small integers may also arrive as strings.
+        assert_eq!(Json::decode(b"[\"7\"]").ok(), Some([7u8]));
+        assert!(<Json as Decode<[u8; 1]>>::decode(b"[\"256\"]").is_err());
+
+        assert!(Json::encode(&1.5f64).is_err());
+    }
+}
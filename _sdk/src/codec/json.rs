@@ -0,0 +1,661 @@
+//! Canonical JSON for the [`Json`](super::Json) codec
+//!
+//! Values are built into a `serde_json::Value` tree and written without
+//! whitespace, with object keys sorted by their UTF-16 code units as in
+//! RFC 8785, so equal values encode to equal bytes whatever map type held
+//! them. Integers beyond ±(2^53 - 1), which JavaScript numbers cannot hold
+//! exactly, are written as decimal strings; decoding accepts either form
+//! wherever an integer is expected. Floats are refused.
+
+use crate::error::{ContractError, ContractResult};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use serde_json::{Map, Number, Value};
+
+/// Largest integer every JSON client reads back exactly.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Encode `value` as canonical JSON.
+pub(super) fn to_vec<T: ?Sized + Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    let tree = value
+        .serialize(ValueSerializer)
+        .map_err(|_| ContractError::SerializationFailed)?;
+    let mut out = Vec::new();
+    write_value(&tree, &mut out)?;
+    Ok(out)
+}
+
+/// Decode JSON, accepting integers written as numbers or decimal strings.
+pub(super) fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> ContractResult<T> {
+    let tree: Value =
+        serde_json::from_slice(bytes).map_err(|_| ContractError::DeserializationFailed)?;
+    T::deserialize(ValueDeserializer(tree)).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) -> ContractResult<()> {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(true) => out.extend_from_slice(b"true"),
+        Value::Bool(false) => out.extend_from_slice(b"false"),
+        Value::Number(number) => out.extend_from_slice(number.to_string().as_bytes()),
+        Value::String(text) => write_string(text, out)?,
+        Value::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(entries) => {
+            let mut entries: Vec<_> = entries.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push(b'{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_string(key, out)?;
+                out.push(b':');
+                write_value(item, out)?;
+            }
+            out.push(b'}');
+        }
+    }
+    Ok(())
+}
+
+/// serde_json escapes only what RFC 8785 requires.
+fn write_string(text: &str, out: &mut Vec<u8>) -> ContractResult<()> {
+    let escaped = serde_json::to_vec(text).map_err(|_| ContractError::SerializationFailed)?;
+    out.extend_from_slice(&escaped);
+    Ok(())
+}
+
+fn unsigned(value: u128) -> Value {
+    if value <= u128::from(MAX_SAFE_INTEGER) {
+        Value::Number(Number::from(value as u64))
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+fn signed(value: i128) -> Value {
+    if value.unsigned_abs() <= u128::from(MAX_SAFE_INTEGER) {
+        Value::Number(Number::from(value as i64))
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+/// Wrap the content of an enum variant as `{"Variant": content}`, as
+/// serde_json represents it.
+fn wrap(variant: Option<&'static str>, content: Value) -> Value {
+    match variant {
+        Some(variant) => {
+            let mut object = Map::new();
+            object.insert(variant.to_string(), content);
+            Value::Object(object)
+        }
+        None => content,
+    }
+}
+
+/// Any failure; the codec reports its own error kinds.
+#[derive(Debug)]
+struct Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value is not canonical JSON")
+    }
+}
+
+impl de::StdError for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Error
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Error
+    }
+}
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        Ok(signed(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(unsigned(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(unsigned(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(unsigned(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(unsigned(v.into()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        Ok(unsigned(v))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Value, Error> {
+        Err(Error)
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Value, Error> {
+        Err(Error)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Array(
+            v.iter().map(|byte| Value::Number((*byte).into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        Ok(wrap(Some(variant), value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Error> {
+        Ok(SeqSerializer {
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Error> {
+        Ok(SeqSerializer {
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer, Error> {
+        Ok(MapSerializer {
+            variant: None,
+            entries: Map::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<MapSerializer, Error> {
+        self.serialize_map(None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<MapSerializer, Error> {
+        Ok(MapSerializer {
+            variant: Some(variant),
+            entries: Map::new(),
+            key: None,
+        })
+    }
+}
+
+struct SeqSerializer {
+    variant: Option<&'static str>,
+    items: Vec<Value>,
+}
+
+impl SeqSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        Ok(wrap(self.variant, Value::Array(self.items)))
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+struct MapSerializer {
+    variant: Option<&'static str>,
+    entries: Map<String, Value>,
+    key: Option<String>,
+}
+
+impl MapSerializer {
+    fn insert<T: ?Sized + Serialize>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        self.entries.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        Ok(wrap(self.variant, Value::Object(self.entries)))
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    /// Keys must serialize as strings or integers; integers become strings.
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(match key.serialize(ValueSerializer)? {
+            Value::String(key) => key,
+            Value::Number(key) => key.to_string(),
+            _ => return Err(Error),
+        });
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().ok_or(Error)?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+struct ValueDeserializer(Value);
+
+/// Integer hints also accept decimal strings, as written for large integers
+/// and for integer map keys.
+macro_rules! deserialize_integers {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.0 {
+                Value::String(text) => visitor.$visit(text.parse::<$ty>().map_err(|_| Error)?),
+                other => ValueDeserializer(other).deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+                (Some(v), _) => visitor.visit_u64(v),
+                (None, Some(v)) => visitor.visit_i64(v),
+                (None, None) => visitor.visit_f64(number.as_f64().ok_or(Error)?),
+            },
+            Value::String(v) => visitor.visit_string(v),
+            Value::Array(items) => {
+                let mut seq = SeqAccess(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                if seq.0.next().is_some() {
+                    return Err(Error);
+                }
+                Ok(value)
+            }
+            Value::Object(entries) => {
+                let mut map = MapAccess {
+                    entries: entries.into_iter(),
+                    value: None,
+                };
+                let value = visitor.visit_map(&mut map)?;
+                if map.entries.next().is_some() {
+                    return Err(Error);
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    deserialize_integers! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(ValueDeserializer(other)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::String(variant) => visitor.visit_enum(EnumAccess {
+                variant,
+                content: None,
+            }),
+            Value::Object(entries) if entries.len() == 1 => {
+                let (variant, content) = entries.into_iter().next().ok_or(Error)?;
+                visitor.visit_enum(EnumAccess {
+                    variant,
+                    content: Some(content),
+                })
+            }
+            _ => Err(Error),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqAccess(alloc::vec::IntoIter<Value>);
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|item| seed.deserialize(ValueDeserializer(item)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct MapAccess {
+    entries: serde_json::map::IntoIter,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(ValueDeserializer(Value::String(key)))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(ValueDeserializer(self.value.take().ok_or(Error)?))
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    content: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = VariantAccess;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantAccess), Error> {
+        let variant: de::value::StringDeserializer<Error> = self.variant.into_deserializer();
+        Ok((seed.deserialize(variant)?, VariantAccess(self.content)))
+    }
+}
+
+struct VariantAccess(Option<Value>);
+
+impl VariantAccess {
+    fn content(self) -> Result<ValueDeserializer, Error> {
+        self.0.map(ValueDeserializer).ok_or(Error)
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.0 {
+            None | Some(Value::Null) => Ok(()),
+            Some(_) => Err(Error),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.content()?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self.content()?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self.content()?, visitor)
+    }
+}
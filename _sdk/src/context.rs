@@ -1,33 +1,54 @@
 //! Execution context for smart contracts
 
+use crate::address::{Address, AddressKind};
+use crate::codec::{Decode, Encode};
 use crate::error::{ContractError, ContractResult};
 use crate::ffi;
+use crate::forwarder;
+use crate::security::safe_math::{self, Amount};
 use crate::security::validation;
-use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use serde::Serialize;
+use spin::Mutex;
 
 const MAX_BLOCK_HEIGHT: u64 = 1_000_000_000_000_000; // ~10^15 blocks
 const MAX_BLOCK_TIMESTAMP: u64 = 10_000_000_000_000; // ~300 years of seconds
+const MAX_SHARD_COUNT: u32 = 1_024;
+const MAX_CALLER_DEPTH: u32 = 64;
+
+static CONTEXT_CACHE: Mutex<Option<Arc<Context>>> = Mutex::new(None);
 
 /// Execution context for the current transaction
 #[derive(Clone, Debug)]
 pub struct Context {
-    sender: String,
-    contract_address: String,
+    sender: Address,
+    origin: Address,
+    contract_address: Address,
     block_height: u64,
     block_timestamp: u64,
-    value: u64,
+    value: Amount,
+    tx_hash: [u8; 32],
+    chain_id: u64,
+    shard_id: u32,
+    gas_price: u64,
+    caller_depth: u32,
 }
 
 impl Context {
     /// Get the transaction sender address
-    pub fn sender(&self) -> &str {
+    pub fn sender(&self) -> &Address {
         &self.sender
     }
 
+    /// Get the account that signed the transaction, which differs from the
+    /// sender when this call was made by another contract
+    pub fn origin(&self) -> &Address {
+        &self.origin
+    }
+
     /// Get the current contract address
-    pub fn contract_address(&self) -> &str {
+    pub fn contract_address(&self) -> &Address {
         &self.contract_address
     }
 
@@ -42,15 +63,71 @@ impl Context {
     }
 
     /// Get the amount of tokens sent with the transaction
-    pub fn value(&self) -> u64 {
+    pub fn value(&self) -> Amount {
         self.value
     }
 
+    /// Get the hash of the transaction being executed
+    pub fn tx_hash(&self) -> &[u8; 32] {
+        &self.tx_hash
+    }
+
+    /// Get the id of the chain the contract runs on
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Get the id of the shard the contract lives on
+    pub fn shard_id(&self) -> u32 {
+        self.shard_id
+    }
+
+    /// Get the gas price paid by the transaction
+    pub fn gas_price(&self) -> u64 {
+        self.gas_price
+    }
+
+    /// Get the number of contract calls between the origin and this call
+    /// (0 for a call made directly by a transaction)
+    pub fn caller_depth(&self) -> u32 {
+        self.caller_depth
+    }
+
+    /// Get the attached value as a `u64`, failing if it does not fit.
+    pub fn value_u64(&self) -> ContractResult<u64> {
+        safe_math::to_u64(self.value)
+    }
+
+    /// Fetch the randomness beacon output for `round`.
+    ///
+    /// Fails with [`ContractError::RandomnessUnavailable`] if the host has not
+    /// produced that round yet.
+    pub fn random_beacon(&self, round: u64) -> ContractResult<[u8; 32]> {
+        ffi::get_random_beacon(round)
+    }
+
     /// Read the call data payload provided with this invocation.
     pub fn call_data(&self) -> ContractResult<Vec<u8>> {
         ffi::read_call_data()
     }
 
+    /// The account this call acts for: the signer of a request relayed by a
+    /// trusted forwarder, otherwise [`Context::sender`].
+    pub fn msg_sender(&self) -> ContractResult<Address> {
+        Ok(match forwarder::forwarded(self)? {
+            Some((_, signer)) => signer,
+            None => self.sender.clone(),
+        })
+    }
+
+    /// Call data with a trusted forwarder's signer suffix removed.
+    pub fn msg_data(&self) -> ContractResult<Vec<u8>> {
+        match forwarder::forwarded(self)? {
+            Some((data, _)) => Ok(data),
+            None => self.call_data(),
+        }
+    }
+
     /// Write return data back to the host in postcard-encoded form.
     pub fn return_data<T: Serialize>(&self, value: &T) -> ContractResult<()> {
         let payload =
@@ -58,25 +135,48 @@ impl Context {
         ffi::write_return_data(&payload)
     }
 
+    /// Decode the call data payload with codec `C`.
+    pub fn call_data_as<C: Decode<T>, T>(&self) -> ContractResult<T> {
+        C::decode(&self.call_data()?)
+    }
+
+    /// Write return data back to the host encoded with codec `C`.
+    pub fn return_data_with<C: Encode<T>, T: ?Sized>(&self, value: &T) -> ContractResult<()> {
+        ffi::write_return_data(&C::encode(value)?)
+    }
+
     /// Write raw return bytes to the host without additional serialization.
     pub fn return_bytes(&self, data: &[u8]) -> ContractResult<()> {
         ffi::write_return_data(data)
     }
 
     /// Transfer tokens from the current contract to a recipient.
-    pub fn transfer_tokens(&self, recipient: &str, amount: u64) -> ContractResult<()> {
-        validation::validate_address(recipient)?;
+    pub fn transfer_tokens(
+        &self,
+        recipient: &Address,
+        amount: impl Into<Amount>,
+    ) -> ContractResult<()> {
+        let amount = amount.into();
         validation::validate_positive_amount(amount)?;
         ffi::transfer_tokens(recipient, amount)
     }
 
+    /// Call `entrypoint` on another contract, which sees this contract as its
+    /// sender, and return its raw return data.
+    pub fn call_contract(
+        &self,
+        target: &Address,
+        entrypoint: &str,
+        input: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        ffi::call_contract(target, entrypoint, input)
+    }
+
     /// Ensure the attached value is at least the requested amount.
-    pub fn require_min_value(&self, required: u64) -> ContractResult<()> {
+    pub fn require_min_value(&self, required: impl Into<Amount>) -> ContractResult<()> {
+        let required = required.into();
         if self.value < required {
-            return Err(ContractError::InsufficientBalance {
-                required,
-                available: self.value,
-            });
+            return Err(ContractError::insufficient_balance(required, self.value));
         }
         Ok(())
     }
@@ -90,24 +190,68 @@ pub fn context() -> Context {
 /// Attempt to fetch the current execution context with validation.
 ///
 /// This fetches context data once per invocation and performs defensive validation
-/// against obviously malformed host inputs.
+/// against obviously malformed host inputs. Later calls are served from a cache.
 pub fn try_context() -> ContractResult<Context> {
-    let sender = ffi::get_sender_address();
-    let contract_address = ffi::get_contract_addr();
+    with_context(Context::clone)
+}
+
+/// Run `f` against the current execution context without cloning it.
+///
+/// The context is read from the host on first use and cached for the rest of
+/// the invocation, so hot paths such as storage access do not cross the FFI
+/// boundary again. The cache is not locked while `f` runs, so `f` may use the
+/// context API itself.
+pub(crate) fn with_context<R>(f: impl FnOnce(&Context) -> R) -> ContractResult<R> {
+    let ctx = cached_context()?;
+    Ok(f(&ctx))
+}
+
+fn cached_context() -> ContractResult<Arc<Context>> {
+    if let Some(ctx) = CONTEXT_CACHE.lock().as_ref() {
+        return Ok(ctx.clone());
+    }
+    let ctx = Arc::new(fetch_context()?);
+    *CONTEXT_CACHE.lock() = Some(ctx.clone());
+    Ok(ctx)
+}
+
+/// Drop the cached context so the next access re-reads it from the host.
+///
+/// The SDK calls this when [`serve`](crate::interface::serve) starts an
+/// entrypoint and after every outgoing or delegate call returns, since the
+/// called code may have re-entered this instance. Entrypoints written by hand
+/// should call it before anything else. Runtimes that switch the executing
+/// frame in-process, like the native mock, also call it when entering a call.
+pub fn invalidate_context_cache() {
+    *CONTEXT_CACHE.lock() = None;
+}
+
+fn fetch_context() -> ContractResult<Context> {
+    let sender = Address::parse(&ffi::get_sender_address())?;
+    let origin = Address::parse_kind(&ffi::get_origin(), AddressKind::Account)?;
+    let contract_address = Address::parse(&ffi::get_contract_addr())?;
     let block_height = ffi::get_block_height();
     let block_timestamp = ffi::get_block_timestamp();
     let value = ffi::get_value();
+    let chain_id = ffi::get_chain_id();
+    let shard_id = ffi::get_shard_id();
+    let caller_depth = ffi::get_caller_depth();
 
-    validation::validate_address(&sender)?;
-    validation::validate_address(&contract_address)?;
     ensure_block_parameters(block_height, block_timestamp)?;
+    ensure_chain_parameters(chain_id, shard_id, caller_depth)?;
 
     Ok(Context {
         sender,
+        origin,
         contract_address,
         block_height,
         block_timestamp,
         value,
+        tx_hash: ffi::get_tx_hash(),
+        chain_id,
+        shard_id,
+        gas_price: ffi::get_gas_price(),
+        caller_depth,
     })
 }
 
@@ -135,45 +279,124 @@ fn ensure_block_parameters(height: u64, timestamp: u64) -> ContractResult<()> {
     Ok(())
 }
 
+fn ensure_chain_parameters(chain_id: u64, shard_id: u32, caller_depth: u32) -> ContractResult<()> {
+    if chain_id == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Chain id cannot be zero".into(),
+        ));
+    }
+    if shard_id >= MAX_SHARD_COUNT {
+        return Err(ContractError::InvalidArgument(
+            "Shard id exceeds maximum bound".into(),
+        ));
+    }
+    if caller_depth > MAX_CALLER_DEPTH {
+        return Err(ContractError::InvalidArgument(
+            "Caller depth exceeds maximum bound".into(),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
     use crate::ffi::mock;
 
-    fn prepare_mock_env() {
+    fn prepare_mock_env() -> std::sync::MutexGuard<'static, ()> {
+        let guard = mock::serial();
         mock::reset();
-        mock::set_sender("chert1sender000000000000000000");
-        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_sender(mock::test_sender().as_str());
+        mock::set_origin(mock::test_sender().as_str());
+        mock::set_contract_address(mock::test_contract().as_str());
         mock::set_block_height(42);
         mock::set_block_timestamp(1_700_000_000);
         mock::set_value(1_000);
+        guard
     }
 
     #[test]
     fn context_initializes_from_mock_runtime() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
 
         let ctx = try_context().expect("context should be available");
 
-        assert_eq!(ctx.sender(), "chert1sender000000000000000000");
-        assert_eq!(ctx.contract_address(), "chert1contract0000000000000000");
+        assert_eq!(ctx.sender(), &mock::test_sender());
+        assert_eq!(ctx.contract_address(), &mock::test_contract());
         assert_eq!(ctx.block_height(), 42);
         assert_eq!(ctx.block_timestamp(), 1_700_000_000);
         assert_eq!(ctx.value(), 1_000);
+        assert_eq!(ctx.origin(), &mock::test_sender());
+        assert_eq!(ctx.chain_id(), 1);
+        assert_eq!(ctx.caller_depth(), 0);
+    }
+
+    #[test]
+    fn context_exposes_transaction_and_chain_data() {
+        let _guard = prepare_mock_env();
+        let origin = Address::from_public_key(&[4; 32]);
+        mock::set_origin(origin.as_str());
+        mock::set_tx_hash([0xab; 32]);
+        mock::set_chain_id(7);
+        mock::set_shard_id(3);
+        mock::set_gas_price(25);
+
+        let ctx = try_context().expect("context should be available");
+        assert_eq!(ctx.origin(), &origin);
+        assert_eq!(ctx.tx_hash(), &[0xab; 32]);
+        assert_eq!(ctx.chain_id(), 7);
+        assert_eq!(ctx.shard_id(), 3);
+        assert_eq!(ctx.gas_price(), 25);
+
+        // Nested calls keep the origin and go one level deeper.
+        mock::invoke(
+            mock::test_contract().as_str(),
+            mock::test_contract().as_str(),
+            || {
+                let inner = try_context().expect("nested context");
+                assert_eq!(inner.origin(), &origin);
+                assert_eq!(inner.caller_depth(), 1);
+            },
+        );
+    }
+
+    #[test]
+    fn context_rejects_out_of_bounds_chain_data() {
+        let _guard = prepare_mock_env();
+
+        mock::set_chain_id(0);
+        assert!(try_context().is_err());
+        mock::set_chain_id(1);
+
+        mock::set_shard_id(MAX_SHARD_COUNT);
+        assert!(try_context().is_err());
+        mock::set_shard_id(0);
+
+        mock::set_caller_depth(MAX_CALLER_DEPTH + 1);
+        assert!(try_context().is_err());
+        mock::set_caller_depth(0);
+
+        // Contracts cannot originate transactions.
+        mock::set_origin(mock::test_contract().as_str());
+        assert!(try_context().is_err());
     }
 
     #[test]
     fn context_rejects_invalid_addresses() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         mock::set_sender("");
 
         let err = try_context().expect_err("empty sender must be rejected");
         assert!(matches!(err, ContractError::InvalidArgument(_)));
+
+        mock::set_sender("chert1sender000000000000000000");
+        let err = try_context().expect_err("non-bech32m sender must be rejected");
+        assert!(matches!(err, ContractError::InvalidArgument(_)));
     }
 
     #[test]
     fn return_data_roundtrip() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let ctx = try_context().expect("context should be available");
 
         ctx.return_data(&64u32).expect("return data must succeed");
@@ -185,7 +408,7 @@ mod tests {
 
     #[test]
     fn call_data_roundtrip() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let payload = b"call-data";
         mock::set_call_data(payload);
 
@@ -194,14 +417,45 @@ mod tests {
         assert_eq!(data, payload);
     }
 
+    #[test]
+    fn random_beacon_serves_published_rounds() {
+        let _guard = prepare_mock_env();
+        let ctx = try_context().expect("context should be available");
+
+        mock::set_random_beacon(7, [9; 32]);
+        assert_eq!(ctx.random_beacon(7).expect("beacon"), [9; 32]);
+        assert!(matches!(
+            ctx.random_beacon(8),
+            Err(ContractError::RandomnessUnavailable)
+        ));
+    }
+
+    #[test]
+    fn random_beacon_is_seedable() {
+        let _guard = prepare_mock_env();
+        let ctx = try_context().expect("context should be available");
+
+        mock::set_random_seed([1; 32]);
+        let first = ctx.random_beacon(7).expect("beacon");
+        assert_eq!(first, ctx.random_beacon(7).expect("beacon"));
+        assert_ne!(first, ctx.random_beacon(8).expect("beacon"));
+        assert!(matches!(
+            ctx.random_beacon(43),
+            Err(ContractError::RandomnessUnavailable)
+        ));
+
+        mock::set_random_seed([2; 32]);
+        assert_ne!(first, ctx.random_beacon(7).expect("beacon"));
+    }
+
     #[test]
     fn require_min_value_enforces_bound() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let ctx = try_context().expect("context should be available");
 
-        assert!(ctx.require_min_value(500).is_ok());
+        assert!(ctx.require_min_value(500u64).is_ok());
         let err = ctx
-            .require_min_value(2_000)
+            .require_min_value(2_000u64)
             .expect_err("insufficient balance expected");
         match err {
             ContractError::InsufficientBalance {
@@ -217,22 +471,122 @@ mod tests {
 
     #[test]
     fn transfer_tokens_validates_inputs() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let ctx = try_context().expect("context should be available");
 
-        assert!(
-            ctx.transfer_tokens("chert1recipient000000000000", 500)
-                .is_ok()
-        );
-
-        let address_err = ctx
-            .transfer_tokens("", 10)
-            .expect_err("empty recipient should fail");
-        assert!(matches!(address_err, ContractError::InvalidArgument(_)));
+        let recipient = Address::from_public_key(&[0x11; 32]);
+        assert!(ctx.transfer_tokens(&recipient, 500u64).is_ok());
 
         let amount_err = ctx
-            .transfer_tokens("chert1recipient000000000000", 0)
+            .transfer_tokens(&recipient, 0u64)
             .expect_err("zero amount should fail");
         assert!(matches!(amount_err, ContractError::InvalidArgument(_)));
+
+        let wide: Amount = 5 * 10u128.pow(24);
+        assert!(ctx.transfer_tokens(&recipient, wide).is_ok());
+    }
+
+    #[test]
+    fn value_supports_wide_amounts() {
+        let _guard = prepare_mock_env();
+        let eighteen_decimals: Amount = 1_000_000 * 10u128.pow(18);
+        mock::set_value(eighteen_decimals);
+        let ctx = try_context().expect("context should be available");
+
+        assert_eq!(ctx.value(), eighteen_decimals);
+        assert!(matches!(ctx.value_u64(), Err(ContractError::Overflow)));
+        assert!(ctx.require_min_value(eighteen_decimals).is_ok());
+        assert!(matches!(
+            ctx.require_min_value(eighteen_decimals + 1),
+            Err(ContractError::InsufficientBalance { available, .. }) if available == eighteen_decimals
+        ));
+
+        mock::set_value(1_000);
+        assert_eq!(try_context().unwrap().value_u64().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn storage_reuses_the_cached_context() {
+        let _guard = prepare_mock_env();
+        let balances: crate::storage::Map<u64, u64> = crate::storage::Map::new("balances");
+        balances.get(&0).expect("warm the cache");
+
+        // Host calls are counted per thread, so other tests cannot skew this.
+        let before = mock::host_calls();
+        for key in 0..10 {
+            balances.get(&key).expect("read");
+        }
+        // One host call per read; the context is not fetched again.
+        assert_eq!(mock::host_calls() - before, 10);
+
+        invalidate_context_cache();
+        let before = mock::host_calls();
+        balances.get(&0).expect("read");
+        assert!(mock::host_calls() - before > 1);
+    }
+
+    /// Cache the current context with another block height, as code that
+    /// re-entered this instance from a different frame would leave it.
+    fn plant_stale_context() {
+        let mut stale = try_context().expect("context");
+        stale.block_height = 7;
+        *CONTEXT_CACHE.lock() = Some(Arc::new(stale));
+    }
+
+    fn stale_code(_entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+        plant_stale_context();
+        Ok(Vec::new())
+    }
+
+    #[test]
+    fn entrypoints_and_returning_calls_refresh_the_context() {
+        let _guard = prepare_mock_env();
+
+        plant_stale_context();
+        assert_eq!(try_context().unwrap().block_height(), 7);
+        let mut seen = 0;
+        crate::interface::serve("probe", |_, _| {
+            seen = try_context()?.block_height();
+            Ok(Vec::new())
+        });
+        assert_eq!(seen, 42);
+
+        let callee = Address::contract([0x0d; 32]);
+        mock::register_code([0x0d; 32], stale_code);
+        mock::set_code_hash(callee.as_str(), [0x0d; 32]);
+        let ctx = try_context().unwrap();
+        ctx.call_contract(&callee, "probe", &[]).unwrap();
+        assert_eq!(try_context().unwrap().block_height(), 42);
+
+        crate::upgrade::delegate_call(&[0x0d; 32], "probe", &[]).unwrap();
+        assert_eq!(try_context().unwrap().block_height(), 42);
+    }
+
+    #[test]
+    fn context_is_not_locked_while_in_use() {
+        let _guard = prepare_mock_env();
+        let nested = with_context(|outer| {
+            try_context().map(|inner| inner.block_height() == outer.block_height())
+        });
+        assert!(nested.unwrap().unwrap());
+    }
+
+    #[test]
+    fn context_changes_invalidate_the_cache() {
+        let _guard = prepare_mock_env();
+        assert_eq!(try_context().unwrap().block_height(), 42);
+
+        mock::set_block_height(43);
+        assert_eq!(try_context().unwrap().block_height(), 43);
+
+        let other = Address::contract([0x0c; 32]);
+        let seen = mock::invoke(other.as_str(), mock::test_sender().as_str(), || {
+            try_context().unwrap().contract_address().clone()
+        });
+        assert_eq!(seen, other);
+        assert_eq!(
+            try_context().unwrap().contract_address(),
+            &mock::test_contract()
+        );
     }
 }
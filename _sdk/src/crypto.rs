@@ -5,6 +5,15 @@ use crate::ffi;
 use alloc::string::String;
 use alloc::vec::Vec;
 
+pub mod groth16;
+pub mod typed_data;
+pub mod vrf;
+pub mod zk;
+
+pub use groth16::{VerifyingKeyRegistry, groth16_verify};
+pub use typed_data::{Domain, TypedData, verify_typed};
+pub use vrf::vrf_verify;
+
 /// SIMD-enabled cryptographic operations for high-performance batch processing
 pub mod simd {
     use super::*;
@@ -94,42 +103,11 @@ pub fn batch_verify_signatures_fallback(
     Ok(results)
 }
 
-/// Generate multiple key pairs efficiently (for testing/benchmarking)
-pub fn generate_keypairs(count: usize) -> ContractResult<Vec<([u8; 32], [u8; 32])>> {
-    use blake3::Hasher;
-    use ed25519_dalek::SigningKey;
-
-    const MAX_BATCH: usize = 1_024;
-    if count > MAX_BATCH {
-        return Err(ContractError::InvalidArgument(String::from(
-            "Keypair batch exceeds static bound",
-        )));
-    }
-
-    let mut pairs = Vec::with_capacity(count);
-    for index in 0..count {
-        let mut hasher = Hasher::new();
-    hasher.update(b"silica-contract-sdk-keygen");
-        hasher.update(&(index as u64).to_le_bytes());
-        let digest = hasher.finalize();
-
-        let mut seed = [0u8; 32];
-        seed.copy_from_slice(&digest.as_bytes()[..32]);
-
-        let signing_key = SigningKey::from_bytes(&seed);
-        let public = signing_key.verifying_key().to_bytes();
-        let secret = signing_key.to_bytes();
-        pairs.push((public, secret));
-    }
-
-    Ok(pairs)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::Keyring;
     use blake3::hash;
-    use ed25519_dalek::{Signer, SigningKey};
     use wasm_bindgen_test::wasm_bindgen_test;
 
     #[wasm_bindgen_test]
@@ -142,28 +120,21 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn ed25519_signature_roundtrip() {
-        let key_material = generate_keypairs(1).expect("key generation");
-        let signing_key = SigningKey::from_bytes(&key_material[0].1);
+        let alice = Keyring::new().alice();
         let message = b"context integrity";
-        let signature = signing_key.sign(message);
-        let verified = verify_signature(
-            &signing_key.verifying_key().to_bytes(),
-            message,
-            &signature.to_bytes(),
-        )
-        .expect("verification should succeed");
+        let signature = alice.sign(message);
+        let verified = verify_signature(&alice.public_key(), message, &signature)
+            .expect("verification should succeed");
         assert!(verified);
     }
 
     #[wasm_bindgen_test]
     fn batch_verification_consistency() {
-        let key_material = generate_keypairs(1).expect("key generation");
-        let signing_key = SigningKey::from_bytes(&key_material[0].1);
+        let alice = Keyring::new().alice();
         let message = b"batch message";
-        let signature = signing_key.sign(message);
 
-        let pubkeys = vec![signing_key.verifying_key().to_bytes()];
-        let signatures = vec![signature.to_bytes()];
+        let pubkeys = [alice.public_key()];
+        let signatures = [alice.sign(message)];
         let messages: Vec<&[u8]> = vec![message.as_ref()];
 
         let results = batch_verify_signatures(&[&pubkeys[0]], &messages, &[&signatures[0]])
@@ -186,22 +157,22 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn simd_batch_verify_matches_fallback() {
-        let key_material = generate_keypairs(3).expect("keypairs");
-        let signing_keys: Vec<SigningKey> = key_material
-            .iter()
-            .map(|(_, secret)| SigningKey::from_bytes(secret))
-            .collect();
+        let keyring = Keyring::new();
+        let signers = [
+            keyring.account("alice"),
+            keyring.account("bob"),
+            keyring.account("charlie"),
+        ];
 
         let messages: Vec<Vec<u8>> = vec![b"msg-0".to_vec(), b"msg-1".to_vec(), b"msg-2".to_vec()];
 
-        let mut pubkeys = Vec::with_capacity(signing_keys.len());
-        let mut signatures = Vec::with_capacity(signing_keys.len());
-        let mut message_refs: Vec<&[u8]> = Vec::with_capacity(signing_keys.len());
+        let mut pubkeys = Vec::with_capacity(signers.len());
+        let mut signatures = Vec::with_capacity(signers.len());
+        let mut message_refs: Vec<&[u8]> = Vec::with_capacity(signers.len());
 
-        for (idx, signer) in signing_keys.iter().enumerate() {
-            pubkeys.push(signer.verifying_key().to_bytes());
-            let signature = signer.sign(&messages[idx]);
-            signatures.push(signature.to_bytes());
+        for (idx, signer) in signers.iter().enumerate() {
+            pubkeys.push(signer.public_key());
+            signatures.push(signer.sign(&messages[idx]));
             message_refs.push(messages[idx].as_slice());
         }
 
@@ -218,14 +189,4 @@ mod tests {
 
         assert_eq!(simd_results, fallback_results);
     }
-
-    #[wasm_bindgen_test]
-    fn generate_keypairs_produces_requested_amount() {
-        let pairs = generate_keypairs(4).expect("keypairs");
-        assert_eq!(pairs.len(), 4);
-        for (public, secret) in pairs {
-            assert_eq!(public.len(), 32);
-            assert_eq!(secret.len(), 32);
-        }
-    }
 }
@@ -0,0 +1,264 @@
+//! Groth16 succinct proof verification (BN254)
+//!
+//! Pairing checks are far too expensive to run in interpreted wasm, so
+//! verification is a host import. The native mock runtime verifies with the
+//! arkworks backend, which lets contract tests use real circuits.
+//!
+//! Encodings follow arkworks' compressed canonical serialization:
+//! - verifying key: `VerifyingKey<Bn254>::serialize_compressed`
+//! - proof: `Proof<Bn254>::serialize_compressed` (128 bytes)
+//! - public inputs: one 32-byte little-endian canonical scalar per input
+
+use crate::error::{ContractError, ContractResult};
+use crate::ffi;
+use crate::storage::Map;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Length of an encoded public input (a BN254 scalar field element).
+pub const PUBLIC_INPUT_LENGTH: usize = 32;
+
+/// Length of a compressed Groth16 proof over BN254.
+pub const PROOF_LENGTH: usize = 128;
+
+/// Upper bound on stored verifying keys, leaving room for the storage encoding.
+pub const MAX_VERIFYING_KEY_LENGTH: usize = 32_768;
+
+/// Verify a Groth16 `proof` against `vk` and the given public inputs.
+///
+/// Returns `Ok(false)` for a well-formed proof that does not verify and
+/// [`ContractError::InvalidProof`] for malformed keys, inputs or proofs.
+pub fn groth16_verify(
+    vk: &[u8],
+    public_inputs: &[[u8; PUBLIC_INPUT_LENGTH]],
+    proof: &[u8],
+) -> ContractResult<bool> {
+    if proof.len() != PROOF_LENGTH {
+        return Err(ContractError::InvalidProof);
+    }
+    ffi::call_groth16_verify(vk, public_inputs, proof)
+}
+
+/// Verifying keys persisted in contract storage, addressed by circuit id.
+///
+/// Keys are immutable once registered; remove and re-register to rotate one.
+pub struct VerifyingKeyRegistry {
+    keys: Map<String, Vec<u8>>,
+}
+
+impl VerifyingKeyRegistry {
+    /// Create a registry under a unique storage prefix.
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            keys: Map::new(prefix),
+        }
+    }
+
+    /// Store a verifying key for `circuit_id`, returning its BLAKE3 fingerprint.
+    pub fn register(&mut self, circuit_id: &str, vk: &[u8]) -> ContractResult<[u8; 32]> {
+        if vk.is_empty() || vk.len() > MAX_VERIFYING_KEY_LENGTH {
+            return Err(ContractError::InvalidArgument(String::from(
+                "Invalid verifying key length",
+            )));
+        }
+
+        let id = circuit_id.to_string();
+        if self.keys.contains_key(&id)? {
+            return Err(ContractError::InvalidArgument(String::from(
+                "Verifying key already registered",
+            )));
+        }
+
+        self.keys.set(&id, &vk.to_vec())?;
+        Ok(super::hash_blake3(vk))
+    }
+
+    /// Load the verifying key registered for `circuit_id`.
+    pub fn get(&self, circuit_id: &str) -> ContractResult<Option<Vec<u8>>> {
+        self.keys.get(&circuit_id.to_string())
+    }
+
+    /// Check whether a key is registered for `circuit_id`.
+    pub fn contains(&self, circuit_id: &str) -> ContractResult<bool> {
+        self.keys.contains_key(&circuit_id.to_string())
+    }
+
+    /// Remove the key registered for `circuit_id`.
+    pub fn remove(&mut self, circuit_id: &str) -> ContractResult<()> {
+        self.keys.remove(&circuit_id.to_string())
+    }
+
+    /// Verify a proof against the key registered for `circuit_id`.
+    pub fn verify(
+        &self,
+        circuit_id: &str,
+        public_inputs: &[[u8; PUBLIC_INPUT_LENGTH]],
+        proof: &[u8],
+    ) -> ContractResult<bool> {
+        let vk = self
+            .get(circuit_id)?
+            .ok_or_else(|| ContractError::InvalidArgument(String::from("Unknown verifying key")))?;
+        groth16_verify(&vk, public_inputs, proof)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod backend {
+    use super::PUBLIC_INPUT_LENGTH;
+    use alloc::vec::Vec;
+    use ark_bn254::{Bn254, Fr};
+    use ark_groth16::{Groth16, Proof, VerifyingKey, prepare_verifying_key};
+    use ark_serialize::CanonicalDeserialize;
+
+    /// Verify a proof, returning `None` when any encoding is malformed.
+    pub(crate) fn verify(
+        vk: &[u8],
+        public_inputs: &[[u8; PUBLIC_INPUT_LENGTH]],
+        proof: &[u8],
+    ) -> Option<bool> {
+        let vk = VerifyingKey::<Bn254>::deserialize_compressed(vk).ok()?;
+        let proof = Proof::<Bn254>::deserialize_compressed(proof).ok()?;
+        let inputs = public_inputs
+            .iter()
+            .map(|input| Fr::deserialize_compressed(&input[..]).ok())
+            .collect::<Option<Vec<Fr>>>()?;
+
+        if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+            return None;
+        }
+
+        let pvk = prepare_verifying_key(&vk);
+        Groth16::<Bn254>::verify_proof(&pvk, &proof, &inputs).ok()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+    use ark_bn254::{Bn254, Fr};
+    use ark_groth16::Groth16;
+    use ark_relations::lc;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use ark_serialize::CanonicalSerialize;
+    use ark_snark::SNARK;
+    use rand_chacha::ChaCha20Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    /// Proves knowledge of `a` and `b` such that `a * b = c` for public `c`.
+    #[derive(Clone)]
+    struct MultiplyCircuit {
+        a: Option<Fr>,
+        b: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for MultiplyCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b = cs.new_witness_variable(|| self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            let c = cs.new_input_variable(|| {
+                let a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+                Ok(a * b)
+            })?;
+            cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + c)
+        }
+    }
+
+    struct Fixture {
+        vk: Vec<u8>,
+        proof: Vec<u8>,
+    }
+
+    fn fixture() -> Fixture {
+        let mut rng = ChaCha20Rng::from_seed([42; 32]);
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(
+            MultiplyCircuit { a: None, b: None },
+            &mut rng,
+        )
+        .expect("setup");
+        let proof = Groth16::<Bn254>::prove(
+            &pk,
+            MultiplyCircuit {
+                a: Some(Fr::from(3u64)),
+                b: Some(Fr::from(11u64)),
+            },
+            &mut rng,
+        )
+        .expect("prove");
+
+        let mut vk_bytes = Vec::new();
+        vk.serialize_compressed(&mut vk_bytes).expect("vk encoding");
+        let mut proof_bytes = Vec::new();
+        proof
+            .serialize_compressed(&mut proof_bytes)
+            .expect("proof encoding");
+
+        Fixture {
+            vk: vk_bytes,
+            proof: proof_bytes,
+        }
+    }
+
+    fn input(value: u64) -> [u8; PUBLIC_INPUT_LENGTH] {
+        let mut encoded = [0u8; PUBLIC_INPUT_LENGTH];
+        Fr::from(value)
+            .serialize_compressed(&mut encoded[..])
+            .expect("input encoding");
+        encoded
+    }
+
+    #[test]
+    fn groth16_verifies_real_circuit() {
+        let fixture = fixture();
+
+        assert!(groth16_verify(&fixture.vk, &[input(33)], &fixture.proof).expect("verify"));
+        assert!(!groth16_verify(&fixture.vk, &[input(34)], &fixture.proof).expect("verify"));
+    }
+
+    #[test]
+    fn groth16_rejects_malformed_encodings() {
+        let fixture = fixture();
+
+        let short_proof = &fixture.proof[..PROOF_LENGTH - 1];
+        assert!(matches!(
+            groth16_verify(&fixture.vk, &[input(33)], short_proof),
+            Err(ContractError::InvalidProof)
+        ));
+        assert!(matches!(
+            groth16_verify(&fixture.vk, &[input(33), input(1)], &fixture.proof),
+            Err(ContractError::InvalidProof)
+        ));
+        assert!(matches!(
+            groth16_verify(&fixture.vk, &[[0xff; PUBLIC_INPUT_LENGTH]], &fixture.proof),
+            Err(ContractError::InvalidProof)
+        ));
+    }
+
+    #[test]
+    fn registry_stores_and_verifies_keys() {
+        let fixture = fixture();
+        let _guard = mock::test_env();
+
+        let mut registry = VerifyingKeyRegistry::new("vk");
+        let fingerprint = registry
+            .register("multiply", &fixture.vk)
+            .expect("register");
+        assert_eq!(fingerprint, crate::crypto::hash_blake3(&fixture.vk));
+        assert!(registry.register("multiply", &fixture.vk).is_err());
+
+        assert!(
+            registry
+                .verify("multiply", &[input(33)], &fixture.proof)
+                .expect("verify")
+        );
+        assert!(
+            registry
+                .verify("unknown", &[input(33)], &fixture.proof)
+                .is_err()
+        );
+
+        registry.remove("multiply").expect("remove");
+        assert!(!registry.contains("multiply").expect("contains"));
+    }
+}
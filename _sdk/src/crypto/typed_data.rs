@@ -0,0 +1,242 @@
+//! Structured-data signing (typed, domain-separated message hashing)
+//!
+//! Off-chain approvals such as permits or vote-by-signature must be hashed the
+//! same way by every client and every contract. A [`Domain`] binds a digest to
+//! one contract on one chain, and [`TypedData`] binds it to one message type, so
+//! a signature produced for one context can never be replayed in another.
+//!
+//! The signed digest is
+//! `blake3(PREFIX || domain_separator || struct_hash(message))`, where
+//! `struct_hash` covers the type name and the postcard encoding of the message.
+
+use crate::address::Address;
+use crate::context::try_context;
+use crate::error::{ContractError, ContractResult};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Version tag prepended to every typed-data digest.
+pub const TYPED_DATA_PREFIX: &[u8] = b"\x19silica-typed-data-v1";
+
+const DOMAIN_TAG: &[u8] = b"silica-typed-data-v1/domain";
+const STRUCT_TAG: &[u8] = b"silica-typed-data-v1/struct";
+
+/// A message type that can be hashed and signed as typed data.
+///
+/// `TYPE_NAME` should describe the message layout (for example
+/// `"Permit(owner,spender,value,nonce,deadline)"`) and must be unique per
+/// message type within a contract.
+pub trait TypedData: Serialize {
+    /// Type descriptor mixed into the struct hash.
+    const TYPE_NAME: &'static str;
+
+    /// Hash of the type descriptor and the canonical encoding of the message.
+    fn struct_hash(&self) -> ContractResult<[u8; 32]> {
+        struct_hash(self)
+    }
+}
+
+/// Signing domain binding typed data to a contract deployment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Domain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub contract_address: Address,
+}
+
+impl Domain {
+    /// Create a domain for an explicit contract address.
+    pub fn new(name: &str, version: &str, chain_id: u64, contract_address: &Address) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            chain_id,
+            contract_address: contract_address.clone(),
+        }
+    }
+
+    /// Create a domain for the currently executing contract.
+    pub fn for_current_contract(name: &str, version: &str, chain_id: u64) -> ContractResult<Self> {
+        let ctx = try_context()?;
+        Ok(Self::new(name, version, chain_id, ctx.contract_address()))
+    }
+
+    /// Domain separator committed to by every digest in this domain.
+    pub fn separator(&self) -> ContractResult<[u8; 32]> {
+        let encoded =
+            postcard::to_allocvec(self).map_err(|_| ContractError::SerializationFailed)?;
+        Ok(tagged_hash(DOMAIN_TAG, &[&encoded]))
+    }
+
+    /// Digest that signers sign for `message` in this domain.
+    pub fn hash_typed<T: TypedData>(&self, message: &T) -> ContractResult<[u8; 32]> {
+        typed_data_hash(self, message)
+    }
+
+    /// Verify an Ed25519 signature over `message` in this domain.
+    pub fn verify_typed<T: TypedData>(
+        &self,
+        signer: &[u8; 32],
+        message: &T,
+        signature: &[u8; 64],
+    ) -> ContractResult<bool> {
+        verify_typed(self, signer, message, signature)
+    }
+}
+
+/// Hash the type descriptor and postcard encoding of a message.
+pub fn struct_hash<T: TypedData + ?Sized>(message: &T) -> ContractResult<[u8; 32]> {
+    let encoded = postcard::to_allocvec(message).map_err(|_| ContractError::SerializationFailed)?;
+    let type_hash = super::hash_blake3(T::TYPE_NAME.as_bytes());
+    Ok(tagged_hash(STRUCT_TAG, &[&type_hash, &encoded]))
+}
+
+/// Compute the digest to sign for `message` under `domain`.
+pub fn typed_data_hash<T: TypedData>(domain: &Domain, message: &T) -> ContractResult<[u8; 32]> {
+    let separator = domain.separator()?;
+    let message_hash = message.struct_hash()?;
+    Ok(tagged_hash(TYPED_DATA_PREFIX, &[&separator, &message_hash]))
+}
+
+/// Verify that `signer` signed `message` under `domain`.
+pub fn verify_typed<T: TypedData>(
+    domain: &Domain,
+    signer: &[u8; 32],
+    message: &T,
+    signature: &[u8; 64],
+) -> ContractResult<bool> {
+    let digest = typed_data_hash(domain, message)?;
+    super::verify_signature(signer, &digest, signature)
+}
+
+fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let total = tag.len() + parts.iter().map(|part| 8 + part.len()).sum::<usize>();
+    let mut preimage = Vec::with_capacity(total);
+    preimage.extend_from_slice(tag);
+    for part in parts {
+        // Length-prefix each part so adjacent fields cannot be re-split.
+        preimage.extend_from_slice(&(part.len() as u64).to_le_bytes());
+        preimage.extend_from_slice(part);
+    }
+    super::hash_blake3(&preimage)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[derive(Serialize)]
+    struct Permit {
+        owner: String,
+        spender: String,
+        value: u64,
+        nonce: u64,
+        deadline: u64,
+    }
+
+    impl TypedData for Permit {
+        const TYPE_NAME: &'static str = "Permit(owner,spender,value,nonce,deadline)";
+    }
+
+    #[derive(Serialize)]
+    struct Approval {
+        owner: String,
+        spender: String,
+        value: u64,
+        nonce: u64,
+        deadline: u64,
+    }
+
+    impl TypedData for Approval {
+        const TYPE_NAME: &'static str = "Approval(owner,spender,value,nonce,deadline)";
+    }
+
+    fn permit() -> Permit {
+        Permit {
+            owner: "chert1owner00000000000000000".to_string(),
+            spender: "chert1spender000000000000000".to_string(),
+            value: 500,
+            nonce: 0,
+            deadline: 1_700_000_600,
+        }
+    }
+
+    fn token() -> Address {
+        Address::contract([0xc0; 32])
+    }
+
+    fn domain() -> Domain {
+        Domain::new("Token", "1", 7, &token())
+    }
+
+    #[test]
+    fn typed_hash_is_deterministic() {
+        let first = domain().hash_typed(&permit()).expect("hash");
+        let second = domain().hash_typed(&permit()).expect("hash");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn typed_hash_is_domain_separated() {
+        let base = domain().hash_typed(&permit()).expect("hash");
+
+        let other_chain = Domain::new("Token", "1", 8, &token());
+        let other_contract = Domain::new("Token", "1", 7, &Address::contract([0xc1; 32]));
+        let other_version = Domain::new("Token", "2", 7, &token());
+
+        assert_ne!(base, other_chain.hash_typed(&permit()).expect("hash"));
+        assert_ne!(base, other_contract.hash_typed(&permit()).expect("hash"));
+        assert_ne!(base, other_version.hash_typed(&permit()).expect("hash"));
+    }
+
+    #[test]
+    fn typed_hash_is_type_separated() {
+        let permit = permit();
+        let approval = Approval {
+            owner: permit.owner.clone(),
+            spender: permit.spender.clone(),
+            value: permit.value,
+            nonce: permit.nonce,
+            deadline: permit.deadline,
+        };
+
+        assert_ne!(
+            domain().hash_typed(&permit).expect("hash"),
+            domain().hash_typed(&approval).expect("hash")
+        );
+    }
+
+    #[test]
+    fn verify_typed_accepts_matching_signature_only() {
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let signer = signing_key.verifying_key().to_bytes();
+        let digest = domain().hash_typed(&permit()).expect("hash");
+        let signature = signing_key.sign(&digest).to_bytes();
+
+        assert!(verify_typed(&domain(), &signer, &permit(), &signature).expect("verify"));
+
+        let mut tampered = permit();
+        tampered.value += 1;
+        assert!(!verify_typed(&domain(), &signer, &tampered, &signature).expect("verify"));
+
+        let foreign = Domain::new("Token", "1", 8, &token());
+        assert!(
+            !foreign
+                .verify_typed(&signer, &permit(), &signature)
+                .expect("verify")
+        );
+    }
+
+    #[test]
+    fn domain_for_current_contract_uses_context() {
+        let _guard = mock::test_env();
+
+        let current = Domain::for_current_contract("Token", "1", 7).expect("domain");
+        let expected = Domain::new("Token", "1", 7, &mock::test_contract());
+        assert_eq!(current, expected);
+    }
+}
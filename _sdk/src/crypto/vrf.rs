@@ -0,0 +1,214 @@
+//! Verifiable random functions (ECVRF-EDWARDS25519-SHA512-TAI, RFC 9381)
+//!
+//! Proof verification is dispatched to the host runtime. The native mock runtime
+//! uses the pure-Rust implementation below, which also provides a prover so
+//! contract tests can produce real proofs.
+
+use crate::error::ContractResult;
+use crate::ffi;
+
+/// Length of an encoded ECVRF proof (`Gamma || c || s`).
+pub const VRF_PROOF_LENGTH: usize = 80;
+
+/// Length of the randomness returned by [`vrf_verify`].
+pub const VRF_OUTPUT_LENGTH: usize = 32;
+
+/// Verify a VRF `proof` for `input` under `pubkey` and return its output.
+///
+/// The output is the first 32 bytes of the RFC 9381 `proof_to_hash` value.
+/// Returns [`ContractError::InvalidProof`](crate::error::ContractError::InvalidProof)
+/// when the proof does not verify.
+pub fn vrf_verify(
+    pubkey: &[u8; 32],
+    input: &[u8],
+    proof: &[u8; VRF_PROOF_LENGTH],
+) -> ContractResult<[u8; VRF_OUTPUT_LENGTH]> {
+    ffi::call_vrf_verify(pubkey, input, proof)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod ecvrf {
+    use super::{VRF_OUTPUT_LENGTH, VRF_PROOF_LENGTH};
+    use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+    use curve25519_dalek::scalar::{Scalar, clamp_integer};
+    use sha2::{Digest, Sha512};
+
+    const SUITE: u8 = 0x03;
+
+    /// Verify a proof, returning the VRF output on success.
+    pub(crate) fn verify(
+        pubkey: &[u8; 32],
+        input: &[u8],
+        proof: &[u8; VRF_PROOF_LENGTH],
+    ) -> Option<[u8; VRF_OUTPUT_LENGTH]> {
+        let y = CompressedEdwardsY(*pubkey).decompress()?;
+        if y.is_small_order() {
+            return None;
+        }
+
+        let (gamma, c, s) = decode_proof(proof)?;
+        let h = encode_to_curve(pubkey, input)?;
+
+        let u = EdwardsPoint::vartime_double_scalar_mul_basepoint(&-c, &y, &s);
+        let v = s * h - c * gamma;
+
+        if challenge(&y, &h, &gamma, &u, &v) != c {
+            return None;
+        }
+        Some(proof_to_output(&gamma))
+    }
+
+    /// Produce a proof and output for `input` with an Ed25519 secret key.
+    pub(crate) fn prove(
+        secret: &[u8; 32],
+        input: &[u8],
+    ) -> ([u8; VRF_PROOF_LENGTH], [u8; VRF_OUTPUT_LENGTH]) {
+        let expanded = Sha512::digest(secret);
+        let mut scalar_bytes = [0u8; 32];
+        scalar_bytes.copy_from_slice(&expanded[..32]);
+        let x = Scalar::from_bytes_mod_order(clamp_integer(scalar_bytes));
+        let y = EdwardsPoint::mul_base(&x);
+        let pubkey = y.compress().to_bytes();
+
+        let h = encode_to_curve(&pubkey, input).expect("try-and-increment terminates");
+        let gamma = x * h;
+
+        let mut nonce_hasher = Sha512::new();
+        nonce_hasher.update(&expanded[32..]);
+        nonce_hasher.update(h.compress().as_bytes());
+        let k = Scalar::from_bytes_mod_order_wide(&nonce_hasher.finalize().into());
+
+        let c = challenge(&y, &h, &gamma, &EdwardsPoint::mul_base(&k), &(k * h));
+        let s = k + c * x;
+
+        let mut proof = [0u8; VRF_PROOF_LENGTH];
+        proof[..32].copy_from_slice(gamma.compress().as_bytes());
+        proof[32..48].copy_from_slice(&c.as_bytes()[..16]);
+        proof[48..].copy_from_slice(s.as_bytes());
+        (proof, proof_to_output(&gamma))
+    }
+
+    fn decode_proof(proof: &[u8; VRF_PROOF_LENGTH]) -> Option<(EdwardsPoint, Scalar, Scalar)> {
+        let mut gamma_bytes = [0u8; 32];
+        gamma_bytes.copy_from_slice(&proof[..32]);
+        let gamma = CompressedEdwardsY(gamma_bytes).decompress()?;
+
+        let mut c_bytes = [0u8; 32];
+        c_bytes[..16].copy_from_slice(&proof[32..48]);
+        let c = Scalar::from_canonical_bytes(c_bytes).into_option()?;
+
+        let mut s_bytes = [0u8; 32];
+        s_bytes.copy_from_slice(&proof[48..]);
+        let s = Scalar::from_canonical_bytes(s_bytes).into_option()?;
+
+        Some((gamma, c, s))
+    }
+
+    fn encode_to_curve(pubkey: &[u8; 32], input: &[u8]) -> Option<EdwardsPoint> {
+        for counter in 0..=u8::MAX {
+            let mut hasher = Sha512::new();
+            hasher.update([SUITE, 0x01]);
+            hasher.update(pubkey);
+            hasher.update(input);
+            hasher.update([counter, 0x00]);
+            let digest = hasher.finalize();
+
+            let mut candidate = [0u8; 32];
+            candidate.copy_from_slice(&digest[..32]);
+            if let Some(point) = CompressedEdwardsY(candidate).decompress() {
+                return Some(point.mul_by_cofactor());
+            }
+        }
+        None
+    }
+
+    fn challenge(
+        y: &EdwardsPoint,
+        h: &EdwardsPoint,
+        gamma: &EdwardsPoint,
+        u: &EdwardsPoint,
+        v: &EdwardsPoint,
+    ) -> Scalar {
+        let mut hasher = Sha512::new();
+        hasher.update([SUITE, 0x02]);
+        for point in [y, h, gamma, u, v] {
+            hasher.update(point.compress().as_bytes());
+        }
+        hasher.update([0x00]);
+        let digest = hasher.finalize();
+
+        let mut c_bytes = [0u8; 32];
+        c_bytes[..16].copy_from_slice(&digest[..16]);
+        Scalar::from_bytes_mod_order(c_bytes)
+    }
+
+    fn proof_to_output(gamma: &EdwardsPoint) -> [u8; VRF_OUTPUT_LENGTH] {
+        let mut hasher = Sha512::new();
+        hasher.update([SUITE, 0x03]);
+        hasher.update(gamma.mul_by_cofactor().compress().as_bytes());
+        hasher.update([0x00]);
+        let digest = hasher.finalize();
+
+        let mut output = [0u8; VRF_OUTPUT_LENGTH];
+        output.copy_from_slice(&digest[..VRF_OUTPUT_LENGTH]);
+        output
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::error::ContractError;
+
+    fn decode_hex<const N: usize>(value: &str) -> [u8; N] {
+        let mut out = [0u8; N];
+        hex::decode_to_slice(value, &mut out).expect("valid hex");
+        out
+    }
+
+    #[test]
+    fn rfc9381_example_16_verifies() {
+        let pubkey: [u8; 32] =
+            decode_hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+        let proof: [u8; VRF_PROOF_LENGTH] = decode_hex(
+            "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f\
+             26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab12\
+             68a1b0db10836d9826a528ca76567805",
+        );
+        let expected: [u8; VRF_OUTPUT_LENGTH] =
+            decode_hex("90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff");
+
+        let output = vrf_verify(&pubkey, b"", &proof).expect("RFC vector must verify");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn prove_then_verify_roundtrip() {
+        let secret = [7u8; 32];
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&secret);
+        let pubkey = signing_key.verifying_key().to_bytes();
+
+        let (proof, output) = ecvrf::prove(&secret, b"round-42");
+        assert_eq!(
+            vrf_verify(&pubkey, b"round-42", &proof).expect("verify"),
+            output
+        );
+    }
+
+    #[test]
+    fn tampered_proof_or_input_is_rejected() {
+        let secret = [7u8; 32];
+        let pubkey = ed25519_dalek::SigningKey::from_bytes(&secret)
+            .verifying_key()
+            .to_bytes();
+        let (proof, _) = ecvrf::prove(&secret, b"round-42");
+
+        let err = vrf_verify(&pubkey, b"round-43", &proof).expect_err("wrong input");
+        assert!(matches!(err, ContractError::InvalidProof));
+
+        let mut tampered = proof;
+        tampered[50] ^= 0x01;
+        let err = vrf_verify(&pubkey, b"round-42", &tampered).expect_err("tampered proof");
+        assert!(matches!(err, ContractError::InvalidProof));
+    }
+}
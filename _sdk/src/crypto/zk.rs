@@ -0,0 +1,295 @@
+//! Pedersen commitments and Bulletproof range proofs
+//!
+//! Commitments are compressed Ristretto points `v·G + r·H` using the
+//! Bulletproofs Pedersen generators. Curve arithmetic and proof verification are
+//! dispatched to the host runtime; the native mock runtime uses the pure-Rust
+//! backend below, which also exposes provers for contract tests.
+
+use crate::error::{ContractError, ContractResult};
+use crate::ffi;
+use alloc::string::String;
+
+/// Length of a compressed Pedersen commitment.
+pub const COMMITMENT_LENGTH: usize = 32;
+
+/// Maximum number of commitments covered by one aggregated range proof.
+pub const MAX_AGGREGATED_COMMITMENTS: usize = 16;
+
+/// Transcript label shared by provers and verifiers.
+pub const RANGE_PROOF_TRANSCRIPT_LABEL: &[u8] = b"silica-range-proof-v1";
+
+/// A compressed Pedersen commitment.
+pub type Commitment = [u8; COMMITMENT_LENGTH];
+
+/// Homomorphically add two commitments.
+pub fn commitment_add(a: &Commitment, b: &Commitment) -> ContractResult<Commitment> {
+    ffi::call_pedersen_add(a, b)
+}
+
+/// Homomorphically subtract commitment `b` from `a`.
+pub fn commitment_sub(a: &Commitment, b: &Commitment) -> ContractResult<Commitment> {
+    ffi::call_pedersen_sub(a, b)
+}
+
+/// Check that `commitment` opens to `value` with `blinding`.
+pub fn verify_opening(
+    commitment: &Commitment,
+    value: u64,
+    blinding: &[u8; 32],
+) -> ContractResult<bool> {
+    ffi::call_pedersen_verify_opening(commitment, value, blinding)
+}
+
+/// Check that the inputs and outputs commit to the same total.
+///
+/// Blinding factors must also balance, which is what a private transfer proves
+/// when the sender chooses the output blindings.
+pub fn verify_balance(inputs: &[Commitment], outputs: &[Commitment]) -> ContractResult<bool> {
+    if inputs.is_empty() || outputs.is_empty() {
+        return Err(ContractError::InvalidArgument(String::from(
+            "Balance check requires inputs and outputs",
+        )));
+    }
+
+    let mut difference = inputs[0];
+    for input in &inputs[1..] {
+        difference = commitment_add(&difference, input)?;
+    }
+    for output in outputs {
+        difference = commitment_sub(&difference, output)?;
+    }
+    Ok(difference == [0u8; COMMITMENT_LENGTH])
+}
+
+/// Verify that `commitment` hides a value in `[0, 2^bits)`.
+pub fn verify_range_proof(
+    commitment: &Commitment,
+    proof: &[u8],
+    bits: u32,
+) -> ContractResult<bool> {
+    verify_aggregated_range_proof(core::slice::from_ref(commitment), proof, bits)
+}
+
+/// Verify an aggregated proof that every commitment hides a value in `[0, 2^bits)`.
+///
+/// The number of commitments must be a power of two no larger than
+/// [`MAX_AGGREGATED_COMMITMENTS`], and `bits` must be 8, 16, 32 or 64.
+pub fn verify_aggregated_range_proof(
+    commitments: &[Commitment],
+    proof: &[u8],
+    bits: u32,
+) -> ContractResult<bool> {
+    validate_range_parameters(commitments.len(), bits)?;
+    ffi::call_range_proof_verify(commitments, proof, bits)
+}
+
+fn validate_range_parameters(count: usize, bits: u32) -> ContractResult<()> {
+    if !matches!(bits, 8 | 16 | 32 | 64) {
+        return Err(ContractError::InvalidArgument(String::from(
+            "Range proof bit size must be 8, 16, 32 or 64",
+        )));
+    }
+    if count == 0 || count > MAX_AGGREGATED_COMMITMENTS || !count.is_power_of_two() {
+        return Err(ContractError::InvalidArgument(String::from(
+            "Range proof commitment count must be a power of two up to 16",
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod backend {
+    use super::{Commitment, MAX_AGGREGATED_COMMITMENTS, RANGE_PROOF_TRANSCRIPT_LABEL};
+    use alloc::vec::Vec;
+    use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+    use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+    use curve25519_dalek::scalar::Scalar;
+    use merlin::Transcript;
+    use rand_chacha::ChaCha20Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    fn decompress(commitment: &Commitment) -> Option<RistrettoPoint> {
+        CompressedRistretto(*commitment).decompress()
+    }
+
+    fn generators() -> (BulletproofGens, PedersenGens) {
+        (
+            BulletproofGens::new(64, MAX_AGGREGATED_COMMITMENTS),
+            PedersenGens::default(),
+        )
+    }
+
+    pub(crate) fn add(a: &Commitment, b: &Commitment) -> Option<Commitment> {
+        Some((decompress(a)? + decompress(b)?).compress().to_bytes())
+    }
+
+    pub(crate) fn sub(a: &Commitment, b: &Commitment) -> Option<Commitment> {
+        Some((decompress(a)? - decompress(b)?).compress().to_bytes())
+    }
+
+    pub(crate) fn commit(value: u64, blinding: &[u8; 32]) -> Commitment {
+        PedersenGens::default()
+            .commit(Scalar::from(value), Scalar::from_bytes_mod_order(*blinding))
+            .compress()
+            .to_bytes()
+    }
+
+    pub(crate) fn verify_opening(
+        commitment: &Commitment,
+        value: u64,
+        blinding: &[u8; 32],
+    ) -> Option<bool> {
+        decompress(commitment)?;
+        Some(commit(value, blinding) == *commitment)
+    }
+
+    pub(crate) fn verify_range(
+        commitments: &[Commitment],
+        proof: &[u8],
+        bits: u32,
+    ) -> Option<bool> {
+        let proof = RangeProof::from_bytes(proof).ok()?;
+        let compressed: Vec<CompressedRistretto> = commitments
+            .iter()
+            .map(|commitment| CompressedRistretto(*commitment))
+            .collect();
+
+        // Verifier randomness only batches the final multiscalar check, so a
+        // seed derived from the proof keeps the mock deterministic.
+        let mut rng = ChaCha20Rng::from_seed(*blake3::hash(&proof.to_bytes()).as_bytes());
+        let (bp_gens, pc_gens) = generators();
+        let mut transcript = Transcript::new(RANGE_PROOF_TRANSCRIPT_LABEL);
+        Some(
+            proof
+                .verify_multiple_with_rng(
+                    &bp_gens,
+                    &pc_gens,
+                    &mut transcript,
+                    &compressed,
+                    bits as usize,
+                    &mut rng,
+                )
+                .is_ok(),
+        )
+    }
+
+    pub(crate) fn prove_range(
+        values: &[u64],
+        blindings: &[[u8; 32]],
+        bits: u32,
+    ) -> Option<(Vec<u8>, Vec<Commitment>)> {
+        let scalars: Vec<Scalar> = blindings
+            .iter()
+            .map(|blinding| Scalar::from_bytes_mod_order(*blinding))
+            .collect();
+
+        let mut seed = blake3::Hasher::new();
+        for (value, blinding) in values.iter().zip(blindings) {
+            seed.update(&value.to_le_bytes());
+            seed.update(blinding);
+        }
+        let mut rng = ChaCha20Rng::from_seed(*seed.finalize().as_bytes());
+
+        let (bp_gens, pc_gens) = generators();
+        let mut transcript = Transcript::new(RANGE_PROOF_TRANSCRIPT_LABEL);
+        let (proof, commitments) = RangeProof::prove_multiple_with_rng(
+            &bp_gens,
+            &pc_gens,
+            &mut transcript,
+            values,
+            &scalars,
+            bits as usize,
+            &mut rng,
+        )
+        .ok()?;
+
+        Some((
+            proof.to_bytes(),
+            commitments.iter().map(|point| point.to_bytes()).collect(),
+        ))
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    #[test]
+    fn opening_checks_value_and_blinding() {
+        let commitment = mock::pedersen_commit(250, &[3; 32]);
+
+        assert!(verify_opening(&commitment, 250, &[3; 32]).expect("opening"));
+        assert!(!verify_opening(&commitment, 251, &[3; 32]).expect("opening"));
+        assert!(!verify_opening(&commitment, 250, &[4; 32]).expect("opening"));
+    }
+
+    #[test]
+    fn commitments_are_homomorphic() {
+        let a = mock::pedersen_commit(100, &[1; 32]);
+        let b = mock::pedersen_commit(40, &[2; 32]);
+
+        let sum = commitment_add(&a, &b).expect("add");
+        let difference = commitment_sub(&sum, &b).expect("sub");
+        assert_eq!(difference, a);
+
+        // 60 + 40 hides the same value as 100, but with different blindings
+        // the totals only balance when the blindings do too.
+        let c = mock::pedersen_commit(60, &[1; 32]);
+        assert!(!verify_balance(&[a], &[c, b]).expect("balance"));
+    }
+
+    #[test]
+    fn balance_holds_when_values_and_blindings_sum() {
+        let blinding_in = [0x20; 32];
+        let input = mock::pedersen_commit(100, &blinding_in);
+
+        let mut blinding_a = [0u8; 32];
+        blinding_a[0] = 0x05;
+        let mut blinding_b = blinding_in;
+        blinding_b[0] -= 0x05;
+
+        let output_a = mock::pedersen_commit(70, &blinding_a);
+        let output_b = mock::pedersen_commit(30, &blinding_b);
+        assert!(verify_balance(&[input], &[output_a, output_b]).expect("balance"));
+    }
+
+    #[test]
+    fn malformed_commitment_is_rejected() {
+        let invalid = [0xff; COMMITMENT_LENGTH];
+        let valid = mock::pedersen_commit(1, &[1; 32]);
+        assert!(commitment_add(&invalid, &valid).is_err());
+        assert!(verify_opening(&invalid, 1, &[1; 32]).is_err());
+    }
+
+    #[test]
+    fn single_range_proof_roundtrip() {
+        let (proof, commitments) = mock::prove_range(&[1_000], &[[7; 32]], 32);
+
+        assert!(verify_range_proof(&commitments[0], &proof, 32).expect("verify"));
+
+        let other = mock::pedersen_commit(1_000, &[8; 32]);
+        assert!(!verify_range_proof(&other, &proof, 32).expect("verify"));
+    }
+
+    #[test]
+    fn aggregated_range_proof_roundtrip() {
+        let values = [1, 2, 3, u32::MAX as u64];
+        let blindings = [[1; 32], [2; 32], [3; 32], [4; 32]];
+        let (proof, commitments) = mock::prove_range(&values, &blindings, 32);
+
+        assert!(verify_aggregated_range_proof(&commitments, &proof, 32).expect("verify"));
+        assert!(!verify_aggregated_range_proof(&commitments, &proof, 64).expect("verify"));
+    }
+
+    #[test]
+    fn range_parameters_are_validated() {
+        let commitment = mock::pedersen_commit(1, &[1; 32]);
+        assert!(verify_range_proof(&commitment, &[], 12).is_err());
+        assert!(verify_aggregated_range_proof(&[commitment; 3], &[], 32).is_err());
+        assert!(matches!(
+            verify_range_proof(&commitment, &[1, 2, 3], 32),
+            Err(ContractError::InvalidProof)
+        ));
+    }
+}
@@ -1,10 +1,12 @@
 //! Error types for smart contracts
 
+use crate::security::safe_math::Amount;
 use alloc::string::String;
 use core::fmt;
 
 /// Contract execution errors
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "abi", derive(serde::Serialize, serde::Deserialize))]
 pub enum ContractError {
     /// Storage read operation failed
     StorageReadFailed,
@@ -22,7 +24,7 @@ pub enum ContractError {
     Unauthorized,
 
     /// Insufficient balance for operation
-    InsufficientBalance { required: u64, available: u64 },
+    InsufficientBalance { required: Amount, available: Amount },
 
     /// Invalid argument provided
     InvalidArgument(String),
@@ -30,6 +32,9 @@ pub enum ContractError {
     /// Contract call failed
     ContractCallFailed(String),
 
+    /// The host rejected a code upgrade
+    CodeUpgradeFailed,
+
     /// Token transfer failed
     TransferFailed,
 
@@ -42,6 +47,12 @@ pub enum ContractError {
     /// Signature verification failed
     InvalidSignature,
 
+    /// Cryptographic proof verification failed
+    InvalidProof,
+
+    /// Randomness beacon output is not available for the requested round
+    RandomnessUnavailable,
+
     /// Arithmetic overflow
     Overflow,
 
@@ -51,6 +62,25 @@ pub enum ContractError {
     /// Reentrancy attack detected
     ReentrancyDetected,
 
+    /// Operation is halted by the circuit breaker
+    Paused,
+
+    /// Operation is only allowed while paused
+    NotPaused,
+
+    /// Sequential nonce does not match the signer's next nonce
+    InvalidNonce { expected: u64, provided: u64 },
+
+    /// Unordered nonce has already been used or invalidated
+    NonceAlreadyUsed { nonce: u64 },
+
+    /// Signed request presented after its deadline
+    Expired { deadline: u64 },
+
+    /// Rate limit or spending cap exhausted; `retry_after` is measured in the
+    /// limiter's clock units (seconds or blocks)
+    RateLimited { retry_after: u64 },
+
     /// Custom error with message
     Custom(String),
 }
@@ -75,17 +105,40 @@ impl fmt::Display for ContractError {
             }
             ContractError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
             ContractError::ContractCallFailed(msg) => write!(f, "Contract call failed: {}", msg),
+            ContractError::CodeUpgradeFailed => write!(f, "Code upgrade failed"),
             ContractError::TransferFailed => write!(f, "Transfer failed"),
             ContractError::CallDataUnavailable => write!(f, "Call data unavailable"),
             ContractError::ReturnDataWriteFailed => write!(f, "Unable to write return data"),
             ContractError::InvalidSignature => write!(f, "Invalid signature"),
+            ContractError::InvalidProof => write!(f, "Invalid proof"),
+            ContractError::RandomnessUnavailable => write!(f, "Randomness unavailable"),
             ContractError::Overflow => write!(f, "Arithmetic overflow"),
             ContractError::Underflow => write!(f, "Arithmetic underflow"),
             ContractError::ReentrancyDetected => write!(f, "Reentrancy attack detected"),
+            ContractError::Paused => write!(f, "Contract is paused"),
+            ContractError::NotPaused => write!(f, "Contract is not paused"),
+            ContractError::InvalidNonce { expected, provided } => {
+                write!(f, "Invalid nonce: expected {}, got {}", expected, provided)
+            }
+            ContractError::NonceAlreadyUsed { nonce } => write!(f, "Nonce {} already used", nonce),
+            ContractError::Expired { deadline } => write!(f, "Deadline {} has passed", deadline),
+            ContractError::RateLimited { retry_after } => {
+                write!(f, "Rate limit exceeded: retry after {}", retry_after)
+            }
             ContractError::Custom(msg) => write!(f, "{}", msg),
         }
     }
 }
 
+impl ContractError {
+    /// Build an [`ContractError::InsufficientBalance`] from amounts of any width.
+    pub fn insufficient_balance(required: impl Into<Amount>, available: impl Into<Amount>) -> Self {
+        ContractError::InsufficientBalance {
+            required: required.into(),
+            available: available.into(),
+        }
+    }
+}
+
 /// Result type for contract operations
 pub type ContractResult<T> = Result<T, ContractError>;
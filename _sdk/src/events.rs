@@ -1,5 +1,6 @@
 //! Event emission for smart contracts (memory pool optimized)
 
+use crate::codec::Encode;
 use crate::ffi;
 use serde::Serialize;
 
@@ -11,7 +12,17 @@ pub fn emit<T: Serialize>(topic: &str, data: &T) {
     }
 }
 
+/// Emit an event whose data is encoded with codec `C` instead of postcard
+pub fn emit_with<C: Encode<T>, T: ?Sized>(topic: &str, data: &T) {
+    if let Ok(data_bytes) = C::encode(data) {
+        ffi::emit_event_internal(topic, &data_bytes);
+    }
+}
+
 /// Log a debug message (only visible in development)
+///
+/// Prefer the leveled macros in [`crate::logging`], which compile out of
+/// release builds.
 pub fn log(message: &str) {
     ffi::log_message(message);
 }
@@ -32,7 +43,7 @@ macro_rules! event {
             // Create event data with string values
             let event_data = EventData {
                 $($field: {
-                    alloc::format!("{}", $value)
+                    $crate::prelude::format!("{}", $value)
                 }),*
             };
 
@@ -6,14 +6,87 @@
 //! so contracts can be exercised without a full blockchain node.
 
 use crate::error::{ContractError, ContractResult};
+use crate::security::safe_math::Amount;
 use alloc::string::String;
 #[cfg(target_arch = "wasm32")]
 use alloc::vec;
 use alloc::vec::Vec;
 
+/// Wasm value type of a host function parameter or result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostType {
+    I32,
+    I64,
+}
+
+/// An `env` function the runtime provides to contracts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HostFunction {
+    pub name: &'static str,
+    pub params: &'static [HostType],
+    pub results: &'static [HostType],
+}
+
+const fn host_fn(
+    name: &'static str,
+    params: &'static [HostType],
+    results: &'static [HostType],
+) -> HostFunction {
+    HostFunction {
+        name,
+        params,
+        results,
+    }
+}
+
+use HostType::{I32, I64};
+
+/// Every function a contract may import from the `env` module, with its wasm
+/// signature. Mirrors the `extern` block below; tools use it to check that a
+/// compiled contract only asks the runtime for what it provides.
+pub const HOST_FUNCTIONS: &[HostFunction] = &[
+    host_fn("state_read", &[I32; 6], &[I32]),
+    host_fn("state_write", &[I32; 6], &[I32]),
+    host_fn("log", &[I32, I32], &[]),
+    host_fn("log_record", &[I32, I32], &[]),
+    host_fn("emit_event", &[I32; 4], &[]),
+    host_fn("transfer_u128", &[I32; 3], &[I32]),
+    host_fn("get_block_height", &[], &[I64]),
+    host_fn("get_block_timestamp", &[], &[I64]),
+    host_fn("get_sender", &[I32], &[I32]),
+    host_fn("get_contract_address", &[I32], &[I32]),
+    host_fn("get_value_u128", &[I32], &[]),
+    host_fn("get_origin", &[I32], &[I32]),
+    host_fn("get_tx_hash", &[I32], &[]),
+    host_fn("get_chain_id", &[], &[I64]),
+    host_fn("get_shard_id", &[], &[I32]),
+    host_fn("get_gas_price", &[], &[I64]),
+    host_fn("get_caller_depth", &[], &[I32]),
+    host_fn("get_call_data_length", &[], &[I32]),
+    host_fn("read_call_data", &[I32, I32], &[I32]),
+    host_fn("write_return_data", &[I32, I32], &[I32]),
+    host_fn("get_random_beacon", &[I64, I32], &[I32]),
+    host_fn("get_code_hash", &[I32], &[]),
+    host_fn("set_code", &[I32], &[I32]),
+    host_fn("delegate_call", &[I32; 5], &[I32]),
+    host_fn("call_contract", &[I32; 6], &[I32]),
+    host_fn("read_call_return", &[I32, I32], &[I32]),
+    host_fn("vrf_verify", &[I32; 5], &[I32]),
+    host_fn("pedersen_add", &[I32; 3], &[I32]),
+    host_fn("pedersen_sub", &[I32; 3], &[I32]),
+    host_fn("pedersen_verify_opening", &[I32, I64, I32], &[I32]),
+    host_fn("range_proof_verify", &[I32; 5], &[I32]),
+    host_fn("groth16_verify", &[I32; 6], &[I32]),
+];
+
+/// The declared signature of host function `name`, if the runtime has one.
+pub fn host_function(name: &str) -> Option<&'static HostFunction> {
+    HOST_FUNCTIONS.iter().find(|function| function.name == name)
+}
+
 #[cfg(target_arch = "wasm32")]
 mod host {
-    use super::{ContractError, ContractResult, String, Vec, vec};
+    use super::{Amount, ContractError, ContractResult, String, Vec, invalid_commitment, vec};
 
     // Host function imports from the runtime
     #[link(wasm_import_module = "env")]
@@ -38,20 +111,76 @@ mod host {
 
         pub fn log(msg_ptr: i32, msg_len: i32);
 
+        pub fn log_record(record_ptr: i32, record_len: i32);
+
         pub fn emit_event(topic_ptr: i32, topic_len: i32, data_ptr: i32, data_len: i32);
 
-        pub fn transfer(to_ptr: i32, to_len: i32, amount: u64) -> i32;
+        pub fn transfer_u128(to_ptr: i32, to_len: i32, amount_ptr: i32) -> i32;
 
         pub fn get_block_height() -> u64;
         pub fn get_block_timestamp() -> u64;
         pub fn get_sender(buffer_ptr: i32) -> i32;
         pub fn get_contract_address(buffer_ptr: i32) -> i32;
-        pub fn get_value() -> u64;
+        pub fn get_value_u128(output_ptr: i32);
+        pub fn get_origin(buffer_ptr: i32) -> i32;
+        pub fn get_tx_hash(output_ptr: i32);
+        pub fn get_chain_id() -> u64;
+        pub fn get_shard_id() -> u32;
+        pub fn get_gas_price() -> u64;
+        pub fn get_caller_depth() -> u32;
 
         pub fn get_call_data_length() -> i32;
         pub fn read_call_data(buffer_ptr: i32, buffer_len: i32) -> i32;
         pub fn write_return_data(buffer_ptr: i32, buffer_len: i32) -> i32;
 
+        pub fn get_random_beacon(round: u64, output_ptr: i32) -> i32;
+
+        pub fn get_code_hash(output_ptr: i32);
+        pub fn set_code(code_hash_ptr: i32) -> i32;
+        pub fn delegate_call(
+            code_hash_ptr: i32,
+            entrypoint_ptr: i32,
+            entrypoint_len: i32,
+            input_ptr: i32,
+            input_len: i32,
+        ) -> i32;
+        pub fn call_contract(
+            target_ptr: i32,
+            target_len: i32,
+            entrypoint_ptr: i32,
+            entrypoint_len: i32,
+            input_ptr: i32,
+            input_len: i32,
+        ) -> i32;
+        pub fn read_call_return(buffer_ptr: i32, buffer_len: i32) -> i32;
+
+        pub fn vrf_verify(
+            pubkey_ptr: i32,
+            input_ptr: i32,
+            input_len: i32,
+            proof_ptr: i32,
+            output_ptr: i32,
+        ) -> i32;
+
+        pub fn pedersen_add(a_ptr: i32, b_ptr: i32, output_ptr: i32) -> i32;
+        pub fn pedersen_sub(a_ptr: i32, b_ptr: i32, output_ptr: i32) -> i32;
+        pub fn pedersen_verify_opening(commitment_ptr: i32, value: u64, blinding_ptr: i32) -> i32;
+        pub fn range_proof_verify(
+            commitments_ptr: i32,
+            commitments_count: i32,
+            proof_ptr: i32,
+            proof_len: i32,
+            bits: i32,
+        ) -> i32;
+
+        pub fn groth16_verify(
+            vk_ptr: i32,
+            vk_len: i32,
+            inputs_ptr: i32,
+            inputs_count: i32,
+            proof_ptr: i32,
+            proof_len: i32,
+        ) -> i32;
     }
 
     pub fn read_storage(account: &str, key: &str) -> ContractResult<Vec<u8>> {
@@ -103,6 +232,14 @@ mod host {
         }
     }
 
+    pub fn log_record(record: &crate::logging::LogRecord) {
+        if let Ok(encoded) = postcard::to_allocvec(record) {
+            unsafe {
+                log_record(encoded.as_ptr() as i32, encoded.len() as i32);
+            }
+        }
+    }
+
     pub fn emit_event_internal(topic: &str, data: &[u8]) {
         unsafe {
             emit_event(
@@ -114,8 +251,11 @@ mod host {
         }
     }
 
-    pub fn transfer_tokens(to: &str, amount: u64) -> ContractResult<()> {
-        let result = unsafe { transfer(to.as_ptr() as i32, to.len() as i32, amount) };
+    pub fn transfer_tokens(to: &str, amount: Amount) -> ContractResult<()> {
+        // Amounts cross the ABI as 16 little-endian bytes.
+        let amount = amount.to_le_bytes();
+        let result =
+            unsafe { transfer_u128(to.as_ptr() as i32, to.len() as i32, amount.as_ptr() as i32) };
         if result == 0 {
             Ok(())
         } else {
@@ -145,8 +285,39 @@ mod host {
         String::from_utf8_lossy(&buffer).into_owned()
     }
 
-    pub fn value() -> u64 {
-        unsafe { get_value() }
+    pub fn value() -> Amount {
+        let mut buffer = [0_u8; 16];
+        unsafe { get_value_u128(buffer.as_mut_ptr() as i32) };
+        Amount::from_le_bytes(buffer)
+    }
+
+    pub fn origin() -> String {
+        let mut buffer = vec![0_u8; 128];
+        let len = unsafe { get_origin(buffer.as_mut_ptr() as i32) };
+        buffer.truncate(len as usize);
+        String::from_utf8_lossy(&buffer).into_owned()
+    }
+
+    pub fn tx_hash() -> [u8; 32] {
+        let mut output = [0_u8; 32];
+        unsafe { get_tx_hash(output.as_mut_ptr() as i32) };
+        output
+    }
+
+    pub fn chain_id() -> u64 {
+        unsafe { get_chain_id() }
+    }
+
+    pub fn shard_id() -> u32 {
+        unsafe { get_shard_id() }
+    }
+
+    pub fn gas_price() -> u64 {
+        unsafe { get_gas_price() }
+    }
+
+    pub fn caller_depth() -> u32 {
+        unsafe { get_caller_depth() }
     }
 
     pub fn read_call_data_internal() -> ContractResult<Vec<u8>> {
@@ -176,6 +347,179 @@ mod host {
         }
     }
 
+    pub fn random_beacon(round: u64) -> ContractResult<[u8; 32]> {
+        let mut output = [0_u8; 32];
+        let result = unsafe { get_random_beacon(round, output.as_mut_ptr() as i32) };
+        if result == 0 {
+            Ok(output)
+        } else {
+            Err(ContractError::RandomnessUnavailable)
+        }
+    }
+
+    pub fn code_hash() -> [u8; 32] {
+        let mut output = [0_u8; 32];
+        unsafe { get_code_hash(output.as_mut_ptr() as i32) };
+        output
+    }
+
+    pub fn set_code_internal(code_hash: &[u8; 32]) -> ContractResult<()> {
+        // The host only ever replaces the code of the calling contract.
+        let result = unsafe { set_code(code_hash.as_ptr() as i32) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(ContractError::CodeUpgradeFailed)
+        }
+    }
+
+    pub fn delegate_call_internal(
+        code_hash: &[u8; 32],
+        entrypoint: &str,
+        input: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        let len = unsafe {
+            delegate_call(
+                code_hash.as_ptr() as i32,
+                entrypoint.as_ptr() as i32,
+                entrypoint.len() as i32,
+                input.as_ptr() as i32,
+                input.len() as i32,
+            )
+        };
+        read_call_return_data(len, entrypoint)
+    }
+
+    pub fn call_contract_internal(
+        target: &str,
+        entrypoint: &str,
+        input: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        let len = unsafe {
+            call_contract(
+                target.as_ptr() as i32,
+                target.len() as i32,
+                entrypoint.as_ptr() as i32,
+                entrypoint.len() as i32,
+                input.as_ptr() as i32,
+                input.len() as i32,
+            )
+        };
+        read_call_return_data(len, entrypoint)
+    }
+
+    fn read_call_return_data(len: i32, entrypoint: &str) -> ContractResult<Vec<u8>> {
+        if len < 0 {
+            return Err(ContractError::ContractCallFailed(String::from(entrypoint)));
+        }
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut buffer = vec![0_u8; len as usize];
+        let result = unsafe { read_call_return(buffer.as_mut_ptr() as i32, len) };
+        if result == 0 {
+            Ok(buffer)
+        } else {
+            Err(ContractError::ContractCallFailed(String::from(entrypoint)))
+        }
+    }
+
+    /// Trap: the runtime fails the call, reverts its state and reports the
+    /// failure to the caller.
+    pub fn fail_call(_error: &ContractError) {
+        core::arch::wasm32::unreachable()
+    }
+
+    pub fn vrf_verify_internal(
+        pubkey: &[u8; 32],
+        input: &[u8],
+        proof: &[u8; 80],
+    ) -> ContractResult<[u8; 32]> {
+        let mut output = [0_u8; 32];
+        let result = unsafe {
+            vrf_verify(
+                pubkey.as_ptr() as i32,
+                input.as_ptr() as i32,
+                input.len() as i32,
+                proof.as_ptr() as i32,
+                output.as_mut_ptr() as i32,
+            )
+        };
+        if result == 0 {
+            Ok(output)
+        } else {
+            Err(ContractError::InvalidProof)
+        }
+    }
+
+    pub fn pedersen_add_internal(a: &[u8; 32], b: &[u8; 32]) -> ContractResult<[u8; 32]> {
+        let mut output = [0_u8; 32];
+        let result = unsafe {
+            pedersen_add(
+                a.as_ptr() as i32,
+                b.as_ptr() as i32,
+                output.as_mut_ptr() as i32,
+            )
+        };
+        if result == 0 {
+            Ok(output)
+        } else {
+            Err(invalid_commitment())
+        }
+    }
+
+    pub fn pedersen_sub_internal(a: &[u8; 32], b: &[u8; 32]) -> ContractResult<[u8; 32]> {
+        let mut output = [0_u8; 32];
+        let result = unsafe {
+            pedersen_sub(
+                a.as_ptr() as i32,
+                b.as_ptr() as i32,
+                output.as_mut_ptr() as i32,
+            )
+        };
+        if result == 0 {
+            Ok(output)
+        } else {
+            Err(invalid_commitment())
+        }
+    }
+
+    pub fn pedersen_verify_opening_internal(
+        commitment: &[u8; 32],
+        value: u64,
+        blinding: &[u8; 32],
+    ) -> i32 {
+        unsafe {
+            pedersen_verify_opening(commitment.as_ptr() as i32, value, blinding.as_ptr() as i32)
+        }
+    }
+
+    pub fn range_proof_verify_internal(commitments: &[[u8; 32]], proof: &[u8], bits: u32) -> i32 {
+        unsafe {
+            range_proof_verify(
+                commitments.as_ptr() as i32,
+                commitments.len() as i32,
+                proof.as_ptr() as i32,
+                proof.len() as i32,
+                bits as i32,
+            )
+        }
+    }
+
+    pub fn groth16_verify_internal(vk: &[u8], public_inputs: &[[u8; 32]], proof: &[u8]) -> i32 {
+        unsafe {
+            groth16_verify(
+                vk.as_ptr() as i32,
+                vk.len() as i32,
+                public_inputs.as_ptr() as i32,
+                public_inputs.len() as i32,
+                proof.as_ptr() as i32,
+                proof.len() as i32,
+            )
+        }
+    }
+
     fn hash_blake3_bytes(data: &[u8]) -> [u8; 32] {
         let digest = blake3::hash(data);
         *digest.as_bytes()
@@ -198,37 +542,19 @@ mod host {
         }
     }
 
-    pub fn hash_blake3_internal(data_ptr: i32, data_len: i32, output_ptr: i32) {
-        unsafe {
-            let data = core::slice::from_raw_parts(data_ptr as *const u8, data_len as usize);
-            let digest = hash_blake3_bytes(data);
-            let output_slice = core::slice::from_raw_parts_mut(output_ptr as *mut u8, 32);
-            output_slice.copy_from_slice(&digest);
-        }
+    pub fn hash_blake3_internal(data: &[u8]) -> [u8; 32] {
+        hash_blake3_bytes(data)
     }
 
     pub fn verify_signature_internal(
-        pubkey_ptr: i32,
-        message_ptr: i32,
-        message_len: i32,
-        signature_ptr: i32,
+        pubkey: &[u8; 32],
+        message: &[u8],
+        signature: &[u8; 64],
     ) -> i32 {
-        unsafe {
-            let pubkey_slice = core::slice::from_raw_parts(pubkey_ptr as *const u8, 32);
-            let message =
-                core::slice::from_raw_parts(message_ptr as *const u8, message_len as usize);
-            let signature_slice = core::slice::from_raw_parts(signature_ptr as *const u8, 64);
-
-            let mut pubkey = [0_u8; 32];
-            pubkey.copy_from_slice(pubkey_slice);
-            let mut signature = [0_u8; 64];
-            signature.copy_from_slice(signature_slice);
-
-            match verify_signature_slice(&pubkey, message, &signature) {
-                Ok(true) => 1,
-                Ok(false) => 0,
-                Err(_) => -1,
-            }
+        match verify_signature_slice(pubkey, message, signature) {
+            Ok(true) => 1,
+            Ok(false) => 0,
+            Err(_) => -1,
         }
     }
 
@@ -265,7 +591,7 @@ mod host {
 
 #[cfg(not(target_arch = "wasm32"))]
 mod host {
-    use super::{ContractError, ContractResult, String, Vec};
+    use super::{Amount, ContractError, ContractResult, String, Vec, invalid_commitment};
     use alloc::string::ToString;
     use spin::Mutex;
 
@@ -279,14 +605,61 @@ mod host {
     pub struct MockRuntime {
         storage: alloc::collections::BTreeMap<(String, String), Vec<u8>>,
         sender: String,
+        origin: String,
         contract_address: String,
         block_height: u64,
         block_timestamp: u64,
-        value: u64,
+        tx_hash: [u8; 32],
+        chain_id: u64,
+        shard_id: u32,
+        gas_price: u64,
+        caller_depth: u32,
+        value: Amount,
         call_data: Vec<u8>,
         return_data: Vec<u8>,
         events: Vec<EventRecord>,
         logs: Vec<String>,
+        log_records: Vec<crate::logging::LogRecord>,
+        random_seed: Option<[u8; 32]>,
+        random_beacons: alloc::collections::BTreeMap<u64, [u8; 32]>,
+        code_hashes: alloc::collections::BTreeMap<String, [u8; 32]>,
+        codes: alloc::collections::BTreeMap<[u8; 32], Code>,
+        failure: Option<ContractError>,
+    }
+
+    /// Native stand-in for an uploaded wasm module: handles `entrypoint` with
+    /// `input` and returns the data the module would write back.
+    pub type CodeHandler = fn(entrypoint: &str, input: &[u8]) -> ContractResult<Vec<u8>>;
+
+    /// Entrypoints a contract exports, as `Contract::EXPORTS` lists them.
+    pub type Exports = &'static [(&'static str, extern "C" fn())];
+
+    /// Uploaded code: a native handler, or a contract's own exports.
+    #[derive(Clone, Copy)]
+    pub enum Code {
+        Handler(CodeHandler),
+        Exports(Exports),
+    }
+
+    impl Code {
+        /// Handle `entrypoint` with `input` as call data, returning the data
+        /// written back.
+        fn run(self, entrypoint: &str, input: &[u8]) -> ContractResult<Vec<u8>> {
+            let exports = match self {
+                Code::Handler(handler) => return handler(entrypoint, input),
+                Code::Exports(exports) => exports,
+            };
+            let (_, export) = exports
+                .iter()
+                .find(|(name, _)| *name == entrypoint)
+                .ok_or_else(|| ContractError::ContractCallFailed(entrypoint.to_string()))?;
+            let call_data = with_runtime(|rt| core::mem::replace(&mut rt.call_data, input.to_vec()));
+            export();
+            Ok(with_runtime(|rt| {
+                rt.call_data = call_data;
+                core::mem::take(&mut rt.return_data)
+            }))
+        }
     }
 
     impl MockRuntime {
@@ -294,11 +667,23 @@ mod host {
             self.storage.clear();
             self.events.clear();
             self.logs.clear();
+            self.log_records.clear();
             self.call_data.clear();
             self.return_data.clear();
+            self.origin.clear();
             self.block_height = 0;
             self.block_timestamp = 0;
+            self.tx_hash = [0; 32];
+            self.chain_id = MOCK_CHAIN_ID;
+            self.shard_id = 0;
+            self.gas_price = 0;
+            self.caller_depth = 0;
             self.value = 0;
+            self.random_seed = None;
+            self.random_beacons.clear();
+            self.code_hashes.clear();
+            self.codes.clear();
+            self.failure = None;
         }
 
         fn storage_key(account: &str, key: &str) -> (String, String) {
@@ -330,17 +715,43 @@ mod host {
                 data: data.to_vec(),
             });
         }
+
+        /// Rounds pinned with `set_random_beacon`, then, once seeded, rounds up
+        /// to the current block height derived from the seed; later rounds are
+        /// not published yet.
+        fn random_beacon(&self, round: u64) -> ContractResult<[u8; 32]> {
+            if let Some(output) = self.random_beacons.get(&round) {
+                return Ok(*output);
+            }
+            match self.random_seed {
+                Some(seed) if round <= self.block_height => {
+                    Ok(*blake3::keyed_hash(&seed, &round.to_le_bytes()).as_bytes())
+                }
+                _ => Err(ContractError::RandomnessUnavailable),
+            }
+        }
     }
 
+    /// Chain id reported by a freshly reset mock runtime.
+    pub const MOCK_CHAIN_ID: u64 = 1;
+
     static MOCK_RUNTIME: Mutex<Option<MockRuntime>> = Mutex::new(None);
 
+    extern crate std;
+    std::thread_local! {
+        static HOST_CALLS: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+    }
+
     pub fn with_runtime<F, R>(f: F) -> R
     where
         F: FnOnce(&mut MockRuntime) -> R,
     {
+        HOST_CALLS.with(|calls| calls.set(calls.get() + 1));
         let mut guard = MOCK_RUNTIME.lock();
         if guard.is_none() {
-            *guard = Some(MockRuntime::default());
+            let mut runtime = MockRuntime::default();
+            runtime.reset();
+            *guard = Some(runtime);
         }
 
         // SAFETY: guard is initialized above and held until the closure completes.
@@ -348,6 +759,21 @@ mod host {
         f(runtime)
     }
 
+    /// Change the execution context, dropping the SDK's cached copy of it.
+    fn update_context<F, R>(f: F) -> R
+    where
+        F: FnOnce(&mut MockRuntime) -> R,
+    {
+        let result = with_runtime(f);
+        crate::context::invalidate_context_cache();
+        result
+    }
+
+    /// Number of host calls made by the current thread.
+    pub fn host_calls() -> u64 {
+        HOST_CALLS.with(|calls| calls.get())
+    }
+
     pub fn read_storage(account: &str, key: &str) -> ContractResult<Vec<u8>> {
         with_runtime(|rt| rt.read_storage(account, key))
     }
@@ -360,11 +786,15 @@ mod host {
         with_runtime(|rt| rt.log(message));
     }
 
+    pub fn log_record(record: &crate::logging::LogRecord) {
+        with_runtime(|rt| rt.log_records.push(record.clone()));
+    }
+
     pub fn emit_event_internal(topic: &str, data: &[u8]) {
         with_runtime(|rt| rt.emit_event_internal(topic, data));
     }
 
-    pub fn transfer_tokens(_to: &str, _amount: u64) -> ContractResult<()> {
+    pub fn transfer_tokens(_to: &str, _amount: Amount) -> ContractResult<()> {
         // Value transfers are no-ops in the mock runtime.
         Ok(())
     }
@@ -385,10 +815,41 @@ mod host {
         with_runtime(|rt| rt.contract_address.clone())
     }
 
-    pub fn value() -> u64 {
+    pub fn value() -> Amount {
         with_runtime(|rt| rt.value)
     }
 
+    pub fn origin() -> String {
+        // Without an explicit origin the sender is treated as a top-level caller.
+        with_runtime(|rt| {
+            if rt.origin.is_empty() {
+                rt.sender.clone()
+            } else {
+                rt.origin.clone()
+            }
+        })
+    }
+
+    pub fn tx_hash() -> [u8; 32] {
+        with_runtime(|rt| rt.tx_hash)
+    }
+
+    pub fn chain_id() -> u64 {
+        with_runtime(|rt| rt.chain_id)
+    }
+
+    pub fn shard_id() -> u32 {
+        with_runtime(|rt| rt.shard_id)
+    }
+
+    pub fn gas_price() -> u64 {
+        with_runtime(|rt| rt.gas_price)
+    }
+
+    pub fn caller_depth() -> u32 {
+        with_runtime(|rt| rt.caller_depth)
+    }
+
     pub fn read_call_data_internal() -> ContractResult<Vec<u8>> {
         with_runtime(|rt| Ok(rt.call_data.clone()))
     }
@@ -400,6 +861,194 @@ mod host {
         })
     }
 
+    pub fn random_beacon(round: u64) -> ContractResult<[u8; 32]> {
+        with_runtime(|rt| rt.random_beacon(round))
+    }
+
+    pub fn code_hash() -> [u8; 32] {
+        with_runtime(|rt| {
+            rt.code_hashes
+                .get(&rt.contract_address)
+                .copied()
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn set_code_internal(code_hash: &[u8; 32]) -> ContractResult<()> {
+        with_runtime(|rt| {
+            if !rt.codes.contains_key(code_hash) {
+                return Err(ContractError::CodeUpgradeFailed);
+            }
+            let contract = rt.contract_address.clone();
+            rt.code_hashes.insert(contract, *code_hash);
+            Ok(())
+        })
+    }
+
+    pub fn delegate_call_internal(
+        code_hash: &[u8; 32],
+        entrypoint: &str,
+        input: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        let code = with_runtime(|rt| rt.codes.get(code_hash).copied())
+            .ok_or_else(|| ContractError::ContractCallFailed(entrypoint.to_string()))?;
+        // Run outside the runtime lock: the code uses the host itself.
+        reverting_on_failure(entrypoint, || code.run(entrypoint, input))
+    }
+
+    pub fn call_contract_internal(
+        target: &str,
+        entrypoint: &str,
+        input: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        let code = with_runtime(|rt| {
+            rt.code_hashes
+                .get(target)
+                .and_then(|code_hash| rt.codes.get(code_hash))
+                .copied()
+        })
+        .ok_or_else(|| ContractError::ContractCallFailed(entrypoint.to_string()))?;
+
+        // The callee sees this contract as sender, and no attached value.
+        let caller = contract_address();
+        let frame = enter_frame(target, &caller, Some(input));
+        let result = reverting_on_failure(entrypoint, || code.run(entrypoint, input));
+        exit_frame(frame);
+        result
+    }
+
+    /// Run a called module, failing the call and dropping its storage writes
+    /// and events if it errs or reports a failure through [`fail_call`].
+    fn reverting_on_failure(
+        entrypoint: &str,
+        call: impl FnOnce() -> ContractResult<Vec<u8>>,
+    ) -> ContractResult<Vec<u8>> {
+        let (storage, events, outer_failure) =
+            with_runtime(|rt| (rt.storage.clone(), rt.events.len(), rt.failure.take()));
+        let result = call();
+        with_runtime(|rt| {
+            let result = match core::mem::replace(&mut rt.failure, outer_failure) {
+                Some(_) => Err(ContractError::ContractCallFailed(entrypoint.to_string())),
+                None => result,
+            };
+            if result.is_err() {
+                rt.storage = storage;
+                rt.events.truncate(events);
+            }
+            result
+        })
+    }
+
+    /// Record that the running module failed, as a trap would.
+    pub fn fail_call(error: &ContractError) {
+        with_runtime(|rt| rt.failure = Some(error.clone()));
+    }
+
+    pub fn take_failure() -> Option<ContractError> {
+        with_runtime(|rt| rt.failure.take())
+    }
+
+    /// Execution state of a caller, saved while a nested call runs.
+    pub struct CallFrame {
+        contract_address: String,
+        sender: String,
+        origin: String,
+        call_data: Option<Vec<u8>>,
+        value: Amount,
+        caller_depth: u32,
+    }
+
+    /// Switch to a nested call into `contract` from `sender`, keeping the
+    /// transaction origin. `call_data` replaces the caller's call data and
+    /// clears the attached value when given.
+    pub fn enter_frame(contract: &str, sender: &str, call_data: Option<&[u8]>) -> CallFrame {
+        update_context(|rt| {
+            let origin = if rt.origin.is_empty() {
+                rt.sender.clone()
+            } else {
+                rt.origin.clone()
+            };
+            let value = if call_data.is_some() {
+                core::mem::take(&mut rt.value)
+            } else {
+                rt.value
+            };
+            let frame = CallFrame {
+                contract_address: core::mem::replace(
+                    &mut rt.contract_address,
+                    contract.to_string(),
+                ),
+                sender: core::mem::replace(&mut rt.sender, sender.to_string()),
+                origin: core::mem::replace(&mut rt.origin, origin),
+                call_data: call_data
+                    .map(|data| core::mem::replace(&mut rt.call_data, data.to_vec())),
+                value,
+                caller_depth: rt.caller_depth,
+            };
+            rt.caller_depth += 1;
+            frame
+        })
+    }
+
+    /// Return to the caller saved by [`enter_frame`]. The SDK drops its cached
+    /// context once the call returns, so this leaves the cache alone.
+    pub fn exit_frame(frame: CallFrame) {
+        with_runtime(|rt| {
+            rt.contract_address = frame.contract_address;
+            rt.sender = frame.sender;
+            rt.origin = frame.origin;
+            if let Some(call_data) = frame.call_data {
+                rt.call_data = call_data;
+            }
+            rt.value = frame.value;
+            rt.caller_depth = frame.caller_depth;
+        });
+    }
+
+    pub fn vrf_verify_internal(
+        pubkey: &[u8; 32],
+        input: &[u8],
+        proof: &[u8; 80],
+    ) -> ContractResult<[u8; 32]> {
+        crate::crypto::vrf::ecvrf::verify(pubkey, input, proof).ok_or(ContractError::InvalidProof)
+    }
+
+    pub fn pedersen_add_internal(a: &[u8; 32], b: &[u8; 32]) -> ContractResult<[u8; 32]> {
+        crate::crypto::zk::backend::add(a, b).ok_or_else(invalid_commitment)
+    }
+
+    pub fn pedersen_sub_internal(a: &[u8; 32], b: &[u8; 32]) -> ContractResult<[u8; 32]> {
+        crate::crypto::zk::backend::sub(a, b).ok_or_else(invalid_commitment)
+    }
+
+    pub fn pedersen_verify_opening_internal(
+        commitment: &[u8; 32],
+        value: u64,
+        blinding: &[u8; 32],
+    ) -> i32 {
+        match crate::crypto::zk::backend::verify_opening(commitment, value, blinding) {
+            Some(true) => 1,
+            Some(false) => 0,
+            None => -1,
+        }
+    }
+
+    pub fn range_proof_verify_internal(commitments: &[[u8; 32]], proof: &[u8], bits: u32) -> i32 {
+        match crate::crypto::zk::backend::verify_range(commitments, proof, bits) {
+            Some(true) => 1,
+            Some(false) => 0,
+            None => -1,
+        }
+    }
+
+    pub fn groth16_verify_internal(vk: &[u8], public_inputs: &[[u8; 32]], proof: &[u8]) -> i32 {
+        match crate::crypto::groth16::backend::verify(vk, public_inputs, proof) {
+            Some(true) => 1,
+            Some(false) => 0,
+            None => -1,
+        }
+    }
+
     fn hash_blake3_bytes(data: &[u8]) -> [u8; 32] {
         let digest = blake3::hash(data);
         *digest.as_bytes()
@@ -422,37 +1071,19 @@ mod host {
         }
     }
 
-    pub fn hash_blake3_internal(data_ptr: i32, data_len: i32, output_ptr: i32) {
-        unsafe {
-            let data = core::slice::from_raw_parts(data_ptr as *const u8, data_len as usize);
-            let digest = hash_blake3_bytes(data);
-            let output_slice = core::slice::from_raw_parts_mut(output_ptr as *mut u8, 32);
-            output_slice.copy_from_slice(&digest);
-        }
+    pub fn hash_blake3_internal(data: &[u8]) -> [u8; 32] {
+        hash_blake3_bytes(data)
     }
 
     pub fn verify_signature_internal(
-        pubkey_ptr: i32,
-        message_ptr: i32,
-        message_len: i32,
-        signature_ptr: i32,
+        pubkey: &[u8; 32],
+        message: &[u8],
+        signature: &[u8; 64],
     ) -> i32 {
-        unsafe {
-            let pubkey_slice = core::slice::from_raw_parts(pubkey_ptr as *const u8, 32);
-            let message =
-                core::slice::from_raw_parts(message_ptr as *const u8, message_len as usize);
-            let signature_slice = core::slice::from_raw_parts(signature_ptr as *const u8, 64);
-
-            let mut pubkey = [0_u8; 32];
-            pubkey.copy_from_slice(pubkey_slice);
-            let mut signature = [0_u8; 64];
-            signature.copy_from_slice(signature_slice);
-
-            match verify_signature_slice(&pubkey, message, &signature) {
-                Ok(true) => 1,
-                Ok(false) => 0,
-                Err(_) => -1,
-            }
+        match verify_signature_slice(pubkey, message, signature) {
+            Ok(true) => 1,
+            Ok(false) => 0,
+            Err(_) => -1,
         }
     }
 
@@ -487,33 +1118,79 @@ mod host {
     }
 
     pub fn reset() {
-        with_runtime(|rt| rt.reset());
+        update_context(|rt| rt.reset());
     }
 
     pub fn set_sender(sender: &str) {
-        with_runtime(|rt| rt.sender = sender.to_string());
+        update_context(|rt| rt.sender = sender.to_string());
     }
 
     pub fn set_contract_address(addr: &str) {
-        with_runtime(|rt| rt.contract_address = addr.to_string());
+        update_context(|rt| rt.contract_address = addr.to_string());
+    }
+
+    pub fn set_origin(origin: &str) {
+        update_context(|rt| rt.origin = origin.to_string());
+    }
+
+    pub fn set_tx_hash(tx_hash: [u8; 32]) {
+        update_context(|rt| rt.tx_hash = tx_hash);
+    }
+
+    pub fn set_chain_id(chain_id: u64) {
+        update_context(|rt| rt.chain_id = chain_id);
+    }
+
+    pub fn set_shard_id(shard_id: u32) {
+        update_context(|rt| rt.shard_id = shard_id);
+    }
+
+    pub fn set_gas_price(gas_price: u64) {
+        update_context(|rt| rt.gas_price = gas_price);
+    }
+
+    pub fn set_caller_depth(depth: u32) {
+        update_context(|rt| rt.caller_depth = depth);
     }
 
     pub fn set_block_height(height: u64) {
-        with_runtime(|rt| rt.block_height = height);
+        update_context(|rt| rt.block_height = height);
     }
 
     pub fn set_block_timestamp(timestamp: u64) {
-        with_runtime(|rt| rt.block_timestamp = timestamp);
+        update_context(|rt| rt.block_timestamp = timestamp);
     }
 
-    pub fn set_value(amount: u64) {
-        with_runtime(|rt| rt.value = amount);
+    pub fn set_value(amount: Amount) {
+        update_context(|rt| rt.value = amount);
     }
 
     pub fn set_call_data(data: &[u8]) {
         with_runtime(|rt| rt.call_data = data.to_vec());
     }
 
+    pub fn set_random_seed(seed: [u8; 32]) {
+        with_runtime(|rt| rt.random_seed = Some(seed));
+    }
+
+    pub fn set_random_beacon(round: u64, output: [u8; 32]) {
+        with_runtime(|rt| {
+            rt.random_beacons.insert(round, output);
+        });
+    }
+
+    pub fn register_code(code_hash: [u8; 32], code: Code) {
+        with_runtime(|rt| {
+            rt.codes.insert(code_hash, code);
+        });
+    }
+
+    pub fn set_code_hash(contract: &str, code_hash: [u8; 32]) {
+        with_runtime(|rt| {
+            rt.code_hashes.insert(contract.to_string(), code_hash);
+        });
+    }
+
     pub fn take_events() -> Vec<EventRecord> {
         with_runtime(|rt| {
             let mut drained = Vec::new();
@@ -530,6 +1207,10 @@ mod host {
         })
     }
 
+    pub fn take_log_records() -> Vec<crate::logging::LogRecord> {
+        with_runtime(|rt| core::mem::take(&mut rt.log_records))
+    }
+
     pub fn take_return_data() -> Vec<u8> {
         with_runtime(|rt| {
             let mut drained = Vec::new();
@@ -548,6 +1229,10 @@ mod host {
     pub use EventRecord as MockEventRecord;
 }
 
+fn invalid_commitment() -> ContractError {
+    ContractError::InvalidArgument(String::from("Invalid commitment encoding"))
+}
+
 pub(crate) fn read_storage(account: &str, key: &str) -> ContractResult<Vec<u8>> {
     host::read_storage(account, key)
 }
@@ -560,11 +1245,15 @@ pub(crate) fn log_message(message: &str) {
     host::log_message(message);
 }
 
+pub(crate) fn log_record(record: &crate::logging::LogRecord) {
+    host::log_record(record);
+}
+
 pub(crate) fn emit_event_internal(topic: &str, data: &[u8]) {
     host::emit_event_internal(topic, data);
 }
 
-pub fn transfer_tokens(to: &str, amount: u64) -> ContractResult<()> {
+pub fn transfer_tokens(to: &str, amount: Amount) -> ContractResult<()> {
     host::transfer_tokens(to, amount)
 }
 
@@ -584,10 +1273,34 @@ pub(crate) fn get_contract_addr() -> String {
     host::contract_address()
 }
 
-pub(crate) fn get_value() -> u64 {
+pub(crate) fn get_value() -> Amount {
     host::value()
 }
 
+pub(crate) fn get_origin() -> String {
+    host::origin()
+}
+
+pub(crate) fn get_tx_hash() -> [u8; 32] {
+    host::tx_hash()
+}
+
+pub(crate) fn get_chain_id() -> u64 {
+    host::chain_id()
+}
+
+pub(crate) fn get_shard_id() -> u32 {
+    host::shard_id()
+}
+
+pub(crate) fn get_gas_price() -> u64 {
+    host::gas_price()
+}
+
+pub(crate) fn get_caller_depth() -> u32 {
+    host::caller_depth()
+}
+
 pub(crate) fn read_call_data() -> ContractResult<Vec<u8>> {
     host::read_call_data_internal()
 }
@@ -596,15 +1309,103 @@ pub(crate) fn write_return_data(data: &[u8]) -> ContractResult<()> {
     host::write_return_data_internal(data)
 }
 
+pub(crate) fn get_random_beacon(round: u64) -> ContractResult<[u8; 32]> {
+    host::random_beacon(round)
+}
+
+pub(crate) fn get_code_hash() -> [u8; 32] {
+    host::code_hash()
+}
+
+pub(crate) fn set_code(code_hash: &[u8; 32]) -> ContractResult<()> {
+    host::set_code_internal(code_hash)
+}
+
+pub(crate) fn delegate_call(
+    code_hash: &[u8; 32],
+    entrypoint: &str,
+    input: &[u8],
+) -> ContractResult<Vec<u8>> {
+    let result = host::delegate_call_internal(code_hash, entrypoint, input);
+    // The called code may have re-entered this contract and cached the
+    // context of its own frame.
+    crate::context::invalidate_context_cache();
+    result
+}
+
+pub(crate) fn call_contract(target: &str, entrypoint: &str, input: &[u8]) -> ContractResult<Vec<u8>> {
+    let result = host::call_contract_internal(target, entrypoint, input);
+    crate::context::invalidate_context_cache();
+    result
+}
+
+/// Fail the running call: the host reverts its state and the caller sees an
+/// error. Under wasm this traps and does not return.
+pub(crate) fn fail_call(error: &ContractError) {
+    host::fail_call(error)
+}
+
+/// Verify a VRF proof (public wrapper for crypto module)
+pub fn call_vrf_verify(
+    pubkey: &[u8; 32],
+    input: &[u8],
+    proof: &[u8; 80],
+) -> ContractResult<[u8; 32]> {
+    host::vrf_verify_internal(pubkey, input, proof)
+}
+
+/// Add two Pedersen commitments (public wrapper for crypto module)
+pub fn call_pedersen_add(a: &[u8; 32], b: &[u8; 32]) -> ContractResult<[u8; 32]> {
+    host::pedersen_add_internal(a, b)
+}
+
+/// Subtract two Pedersen commitments (public wrapper for crypto module)
+pub fn call_pedersen_sub(a: &[u8; 32], b: &[u8; 32]) -> ContractResult<[u8; 32]> {
+    host::pedersen_sub_internal(a, b)
+}
+
+/// Check a Pedersen commitment opening (public wrapper for crypto module)
+pub fn call_pedersen_verify_opening(
+    commitment: &[u8; 32],
+    value: u64,
+    blinding: &[u8; 32],
+) -> ContractResult<bool> {
+    match host::pedersen_verify_opening_internal(commitment, value, blinding) {
+        1 => Ok(true),
+        0 => Ok(false),
+        _ => Err(invalid_commitment()),
+    }
+}
+
+/// Verify a Groth16 proof over BN254 (public wrapper for crypto module)
+pub fn call_groth16_verify(
+    vk: &[u8],
+    public_inputs: &[[u8; 32]],
+    proof: &[u8],
+) -> ContractResult<bool> {
+    match host::groth16_verify_internal(vk, public_inputs, proof) {
+        1 => Ok(true),
+        0 => Ok(false),
+        _ => Err(ContractError::InvalidProof),
+    }
+}
+
+/// Verify an (aggregated) Bulletproof range proof (public wrapper for crypto module)
+pub fn call_range_proof_verify(
+    commitments: &[[u8; 32]],
+    proof: &[u8],
+    bits: u32,
+) -> ContractResult<bool> {
+    match host::range_proof_verify_internal(commitments, proof, bits) {
+        1 => Ok(true),
+        0 => Ok(false),
+        _ => Err(ContractError::InvalidProof),
+    }
+}
+
 /// Hash data with BLAKE3 (public wrapper for crypto module)
 pub fn call_hash_blake3(data: &[u8]) -> [u8; 32] {
-    let mut output = [0u8; 32];
-    host::hash_blake3_internal(
-        data.as_ptr() as i32,
-        data.len() as i32,
-        output.as_mut_ptr() as i32,
-    );
-    output
+    host::hash_blake3_internal(data)
 }
 
 /// Verify signature (public wrapper for crypto module)
@@ -613,12 +1414,7 @@ pub fn call_verify_signature(
     message: &[u8],
     signature: &[u8; 64],
 ) -> ContractResult<bool> {
-    let result = host::verify_signature_internal(
-        pubkey.as_ptr() as i32,
-        message.as_ptr() as i32,
-        message.len() as i32,
-        signature.as_ptr() as i32,
-    );
+    let result = host::verify_signature_internal(pubkey, message, signature);
 
     match result {
         1 => Ok(true),
@@ -643,10 +1439,11 @@ pub fn batch_verify_signatures(
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod mock {
-    use super::host;
+    use super::{Amount, ContractError, host};
     use alloc::string::String;
     use alloc::vec::Vec;
 
+    pub use host::{CodeHandler, Exports};
     pub use host::MockEventRecord as EventRecord;
 
     pub fn reset() {
@@ -661,6 +1458,32 @@ pub mod mock {
         host::set_contract_address(addr);
     }
 
+    /// Set the transaction origin; unset, it follows the sender.
+    pub fn set_origin(origin: &str) {
+        host::set_origin(origin);
+    }
+
+    pub fn set_tx_hash(tx_hash: [u8; 32]) {
+        host::set_tx_hash(tx_hash);
+    }
+
+    /// Set the chain id; a reset runtime reports [`host::MOCK_CHAIN_ID`].
+    pub fn set_chain_id(chain_id: u64) {
+        host::set_chain_id(chain_id);
+    }
+
+    pub fn set_shard_id(shard_id: u32) {
+        host::set_shard_id(shard_id);
+    }
+
+    pub fn set_gas_price(gas_price: u64) {
+        host::set_gas_price(gas_price);
+    }
+
+    pub fn set_caller_depth(depth: u32) {
+        host::set_caller_depth(depth);
+    }
+
     pub fn set_block_height(height: u64) {
         host::set_block_height(height);
     }
@@ -669,7 +1492,7 @@ pub mod mock {
         host::set_block_timestamp(timestamp);
     }
 
-    pub fn set_value(amount: u64) {
+    pub fn set_value(amount: Amount) {
         host::set_value(amount);
     }
 
@@ -677,19 +1500,136 @@ pub mod mock {
         host::set_call_data(data);
     }
 
+    /// Seed the randomness beacon: every round up to the current block height
+    /// derives from this seed, and later rounds are unavailable.
+    pub fn set_random_seed(seed: [u8; 32]) {
+        host::set_random_seed(seed);
+    }
+
+    /// Pin the beacon output for a single round, published or not.
+    pub fn set_random_beacon(round: u64, output: [u8; 32]) {
+        host::set_random_beacon(round, output);
+    }
+
+    /// Make `handler` available as uploaded code under `code_hash`, for
+    /// [`crate::upgrade`], delegate calls and, once a contract is deployed
+    /// with it via [`set_code_hash`], cross-contract calls.
+    pub fn register_code(code_hash: [u8; 32], handler: CodeHandler) {
+        host::register_code(code_hash, host::Code::Handler(handler));
+    }
+
+    /// Make a contract's exported entrypoints, `Contract::EXPORTS`, available
+    /// as uploaded code under `code_hash`, and deploy `contract` with it. Calls
+    /// into `contract` then run its entrypoints as the host would.
+    pub fn deploy_exports(code_hash: [u8; 32], contract: &str, exports: Exports) {
+        host::register_code(code_hash, host::Code::Exports(exports));
+        host::set_code_hash(contract, code_hash);
+    }
+
+    /// Set the code hash `contract` is deployed with.
+    pub fn set_code_hash(contract: &str, code_hash: [u8; 32]) {
+        host::set_code_hash(contract, code_hash);
+    }
+
+    /// Produce a VRF proof and output with an Ed25519 secret key.
+    pub fn vrf_prove(secret: &[u8; 32], input: &[u8]) -> ([u8; 80], [u8; 32]) {
+        crate::crypto::vrf::ecvrf::prove(secret, input)
+    }
+
+    /// Commit to `value` with `blinding` using the runtime's Pedersen generators.
+    pub fn pedersen_commit(value: u64, blinding: &[u8; 32]) -> [u8; 32] {
+        crate::crypto::zk::backend::commit(value, blinding)
+    }
+
+    /// Produce an aggregated range proof and the matching commitments.
+    ///
+    /// Panics if the inputs cannot be proven (mismatched lengths, a value out of
+    /// range, or an unsupported aggregation size).
+    pub fn prove_range(
+        values: &[u64],
+        blindings: &[[u8; 32]],
+        bits: u32,
+    ) -> (Vec<u8>, Vec<[u8; 32]>) {
+        crate::crypto::zk::backend::prove_range(values, blindings, bits)
+            .expect("range proof inputs must be provable")
+    }
+
     pub fn take_events() -> Vec<EventRecord> {
         host::take_events()
     }
 
+    /// Running count of host calls made by the current thread; diff two
+    /// readings to measure an operation. Each test runs on its own thread, so
+    /// other tests do not affect the count.
+    pub fn host_calls() -> u64 {
+        host::host_calls()
+    }
+
     pub fn take_logs() -> Vec<String> {
         host::take_logs()
     }
 
+    /// Drain the records written by the leveled log macros.
+    pub fn take_log_records() -> Vec<crate::logging::LogRecord> {
+        host::take_log_records()
+    }
+
     pub fn take_return_data() -> Vec<u8> {
         host::take_return_data()
     }
 
+    /// Take the error an exported entrypoint failed with, if it did. Calls
+    /// made with `call_contract` report it to the caller instead.
+    pub fn take_failure() -> Option<ContractError> {
+        host::take_failure()
+    }
+
     pub fn inspect_storage(account: &str, key: &str) -> Vec<u8> {
         host::inspect_storage(account, key)
     }
+
+    /// Run `f` as a nested call into `contract` made by `sender`, restoring the
+    /// calling frame afterwards. Storage is keyed by contract address, so `f`
+    /// sees the callee's state, as a cross-contract call would.
+    pub fn invoke<R>(contract: &str, sender: &str, f: impl FnOnce() -> R) -> R {
+        let frame = host::enter_frame(contract, sender, None);
+        let result = f();
+        host::exit_frame(frame);
+        crate::context::invalidate_context_cache();
+        result
+    }
+
+    /// Serialise unit tests that share the process-wide mock runtime.
+    #[cfg(test)]
+    pub(crate) fn serial() -> std::sync::MutexGuard<'static, ()> {
+        static SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        SERIAL
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Sender installed by [`test_env`].
+    #[cfg(test)]
+    pub(crate) fn test_sender() -> crate::address::Address {
+        crate::address::Address::new(crate::address::AddressKind::Account, [0x5e; 32])
+    }
+
+    /// Contract address installed by [`test_env`].
+    #[cfg(test)]
+    pub(crate) fn test_contract() -> crate::address::Address {
+        crate::address::Address::contract([0xc0; 32])
+    }
+
+    /// Lock the mock runtime and install a valid default execution context.
+    #[cfg(test)]
+    pub(crate) fn test_env() -> std::sync::MutexGuard<'static, ()> {
+        let guard = serial();
+        reset();
+        set_sender(test_sender().as_str());
+        set_origin(test_sender().as_str());
+        set_contract_address(test_contract().as_str());
+        set_block_height(1);
+        set_block_timestamp(1_700_000_000);
+        guard
+    }
 }
@@ -0,0 +1,332 @@
+//! Meta-transactions through a trusted forwarder
+//!
+//! A user signs a [`ForwardRequest`] off-chain and any relayer submits it to a
+//! forwarder contract, which pays for execution. The forwarder checks the
+//! request's deadline, signature and nonce with [`execute`] and then calls the
+//! target, appending the signer's address to the call data.
+//!
+//! A target opts in by trusting the forwarder with [`set_trusted_forwarder`]
+//! and reading the acting account from [`Context::msg_sender`] instead of
+//! [`Context::sender`]. For calls from a trusted forwarder it returns the
+//! appended signer; for every other caller it is just the sender, so
+//! entrypoints work the same whether they are called directly or relayed.
+
+use crate::address::Address;
+use crate::context::{Context, try_context};
+use crate::crypto::typed_data::{Domain, TypedData};
+use crate::error::{ContractError, ContractResult};
+use crate::events::emit;
+use crate::security::access_control::{AccessControl, DEFAULT_ADMIN_ROLE};
+use crate::security::nonces::{Nonced, Nonces};
+use crate::storage::Map;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+const TRUSTED_FORWARDERS_BUCKET: &str = "__trusted_forwarders";
+
+/// Signing domain name of forward requests
+pub const DOMAIN_NAME: &str = "SilicaForwarder";
+/// Signing domain version of forward requests
+pub const DOMAIN_VERSION: &str = "1";
+
+/// A call signed by `signer` for a forwarder to relay to `target`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardRequest {
+    pub signer: Address,
+    pub target: Address,
+    pub entrypoint: String,
+    pub data: Vec<u8>,
+    /// Sequential nonce of `signer` at the forwarder.
+    pub nonce: u64,
+    /// Last block timestamp at which the request may be relayed.
+    pub deadline: u64,
+}
+
+impl TypedData for ForwardRequest {
+    const TYPE_NAME: &'static str = "ForwardRequest(signer,target,entrypoint,data,nonce,deadline)";
+}
+
+impl Nonced for ForwardRequest {
+    fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    fn deadline(&self) -> u64 {
+        self.deadline
+    }
+}
+
+/// Emitted by the forwarder for every relayed request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestForwarded {
+    pub signer: Address,
+    pub target: Address,
+    pub entrypoint: String,
+    pub nonce: u64,
+    pub relayer: Address,
+}
+
+/// Emitted by a target when it starts or stops trusting a forwarder.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedForwarderSet {
+    pub forwarder: Address,
+    pub trusted: bool,
+}
+
+fn trusted_forwarders() -> Map<Address, bool> {
+    Map::new(TRUSTED_FORWARDERS_BUCKET)
+}
+
+#[cfg(feature = "abi")]
+impl crate::abi::AbiBuilder {
+    /// Declare the event and storage of [`set_trusted_forwarder`].
+    pub fn trusted_forwarders(self) -> Self {
+        self.event::<TrustedForwarderSet>("TrustedForwarderSet")
+            .map::<Address, bool>(TRUSTED_FORWARDERS_BUCKET)
+    }
+}
+
+/// Verify `request` against `signer_key` under `domain`, consume its nonce and
+/// call the target on the signer's behalf, returning the target's return data.
+///
+/// Intended for the forwarder contract itself.
+pub fn execute(
+    domain: &Domain,
+    signer_key: &[u8; 32],
+    request: &ForwardRequest,
+    signature: &[u8; 64],
+) -> ContractResult<Vec<u8>> {
+    if Address::from_public_key(signer_key) != request.signer {
+        return Err(ContractError::InvalidSignature);
+    }
+    Nonces::consume_signed(domain, signer_key, request, signature)?;
+
+    let ctx = try_context()?;
+    let data = append_signer(&request.data, &request.signer);
+    let result = ctx.call_contract(&request.target, &request.entrypoint, &data)?;
+    emit(
+        "RequestForwarded",
+        &RequestForwarded {
+            signer: request.signer.clone(),
+            target: request.target.clone(),
+            entrypoint: request.entrypoint.clone(),
+            nonce: request.nonce,
+            relayer: ctx.sender().clone(),
+        },
+    );
+    Ok(result)
+}
+
+/// Call data as a forwarder delivers it: `data || signer || len(signer)`.
+pub fn append_signer(data: &[u8], signer: &Address) -> Vec<u8> {
+    let signer = signer.as_str().as_bytes();
+    let mut forwarded = Vec::with_capacity(data.len() + signer.len() + 1);
+    forwarded.extend_from_slice(data);
+    forwarded.extend_from_slice(signer);
+    // Addresses are far shorter than 256 bytes, so the length fits one byte.
+    forwarded.push(signer.len() as u8);
+    forwarded
+}
+
+/// Split forwarded call data into the original data and the signer.
+pub fn split_signer(forwarded: &[u8]) -> ContractResult<(&[u8], Address)> {
+    let malformed =
+        || ContractError::InvalidArgument(String::from("Malformed forwarded call data"));
+    let (&len, rest) = forwarded.split_last().ok_or_else(malformed)?;
+    let split = rest.len().checked_sub(len as usize).ok_or_else(malformed)?;
+    let (data, signer) = rest.split_at(split);
+    let signer = core::str::from_utf8(signer).map_err(|_| malformed())?;
+    Ok((data, Address::parse(signer)?))
+}
+
+/// Start or stop accepting calls relayed by `forwarder` as coming from the
+/// signer. Requires [`DEFAULT_ADMIN_ROLE`].
+pub fn set_trusted_forwarder(
+    caller: &Address,
+    forwarder: &Address,
+    trusted: bool,
+) -> ContractResult<()> {
+    AccessControl::authorize(caller, Some(DEFAULT_ADMIN_ROLE))?;
+    trust_forwarder(forwarder, trusted)
+}
+
+/// Like [`set_trusted_forwarder`], for contracts that authorize the change
+/// themselves, such as a multisig that only accepts it from its own approved
+/// transactions. A trusted forwarder can act for any signer, so callers must
+/// check that the change was agreed.
+pub fn trust_forwarder(forwarder: &Address, trusted: bool) -> ContractResult<()> {
+    let mut forwarders = trusted_forwarders();
+    if trusted {
+        forwarders.set(forwarder, &true)?;
+    } else {
+        forwarders.remove(forwarder)?;
+    }
+    emit(
+        "TrustedForwarderSet",
+        &TrustedForwarderSet {
+            forwarder: forwarder.clone(),
+            trusted,
+        },
+    );
+    Ok(())
+}
+
+/// Whether this contract trusts `forwarder`.
+pub fn is_trusted_forwarder(forwarder: &Address) -> ContractResult<bool> {
+    trusted_forwarders().contains_key(forwarder)
+}
+
+/// Signer and original call data if `ctx` is a call from a trusted forwarder.
+pub(crate) fn forwarded(ctx: &Context) -> ContractResult<Option<(Vec<u8>, Address)>> {
+    if !is_trusted_forwarder(ctx.sender())? {
+        return Ok(None);
+    }
+    let call_data = ctx.call_data()?;
+    let (data, signer) = split_signer(&call_data)?;
+    Ok(Some((data.to_vec(), signer)))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+    use crate::storage::storage;
+    use crate::testing::Keyring;
+
+    const NOW: u64 = 1_700_000_000;
+    const GOVERNOR_CODE: [u8; 32] = [0x60; 32];
+
+    fn forwarder() -> Address {
+        Address::contract([0xf0; 32])
+    }
+
+    fn governor() -> Address {
+        Address::contract([0x60; 32])
+    }
+
+    fn domain() -> Domain {
+        Domain::new("Forwarder", "1", 7, &forwarder())
+    }
+
+    /// Records who it thinks is voting, as a governor would.
+    fn governor_code(entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+        assert_eq!(entrypoint, "vote");
+        let ctx = try_context()?;
+        let voter = ctx.msg_sender()?;
+        let support: bool = postcard::from_bytes(&ctx.msg_data()?)
+            .map_err(|_| ContractError::DeserializationFailed)?;
+        storage().set("last_vote", &(voter, support))?;
+        Ok(Vec::new())
+    }
+
+    fn setup() {
+        mock::register_code(GOVERNOR_CODE, governor_code);
+        mock::set_code_hash(governor().as_str(), GOVERNOR_CODE);
+
+        let admin = mock::test_sender();
+        mock::invoke(governor().as_str(), admin.as_str(), || {
+            AccessControl::initialize(&admin).expect("init governor");
+            set_trusted_forwarder(&admin, &forwarder(), true).expect("trust forwarder");
+        });
+        mock::set_contract_address(forwarder().as_str());
+        mock::take_events();
+    }
+
+    fn last_vote() -> Option<(Address, bool)> {
+        mock::invoke(governor().as_str(), forwarder().as_str(), || {
+            storage().get("last_vote").expect("read")
+        })
+    }
+
+    #[test]
+    fn forwarded_calls_act_for_the_signer() {
+        let _guard = mock::test_env();
+        setup();
+        let alice = Keyring::new().alice();
+        let relayer = mock::test_sender();
+
+        let request = ForwardRequest {
+            signer: alice.address(),
+            target: governor(),
+            entrypoint: String::from("vote"),
+            data: postcard::to_allocvec(&true).unwrap(),
+            nonce: 0,
+            deadline: NOW + 60,
+        };
+        let signature = alice.sign_typed(&domain(), &request).unwrap();
+
+        execute(&domain(), &alice.public_key(), &request, &signature).expect("relay");
+        assert_eq!(last_vote(), Some((alice.address(), true)));
+
+        let events = mock::take_events();
+        let forwarded: RequestForwarded = postcard::from_bytes(&events[0].data).unwrap();
+        assert_eq!(forwarded.relayer, relayer);
+        assert_eq!(forwarded.signer, alice.address());
+
+        // The same signed request cannot be relayed twice.
+        assert!(matches!(
+            execute(&domain(), &alice.public_key(), &request, &signature),
+            Err(ContractError::InvalidNonce { .. })
+        ));
+    }
+
+    #[test]
+    fn forged_or_late_requests_are_rejected() {
+        let _guard = mock::test_env();
+        setup();
+        let keyring = Keyring::new();
+        let (alice, bob) = (keyring.alice(), keyring.bob());
+
+        let request = ForwardRequest {
+            signer: alice.address(),
+            target: governor(),
+            entrypoint: String::from("vote"),
+            data: postcard::to_allocvec(&false).unwrap(),
+            nonce: 0,
+            deadline: NOW + 60,
+        };
+        let forged = bob.sign_typed(&domain(), &request).unwrap();
+        assert!(matches!(
+            execute(&domain(), &bob.public_key(), &request, &forged),
+            Err(ContractError::InvalidSignature)
+        ));
+        assert!(matches!(
+            execute(&domain(), &alice.public_key(), &request, &forged),
+            Err(ContractError::InvalidSignature)
+        ));
+
+        mock::set_block_timestamp(NOW + 61);
+        let signature = alice.sign_typed(&domain(), &request).unwrap();
+        assert!(matches!(
+            execute(&domain(), &alice.public_key(), &request, &signature),
+            Err(ContractError::Expired { .. })
+        ));
+        assert_eq!(last_vote(), None);
+    }
+
+    #[test]
+    fn untrusted_callers_are_themselves_the_msg_sender() {
+        let _guard = mock::test_env();
+        setup();
+        let alice = Keyring::new().alice();
+        let data = append_signer(b"payload", &alice.address());
+
+        // Direct call: suffix-looking data is not interpreted.
+        mock::set_contract_address(governor().as_str());
+        mock::set_call_data(&data);
+        let ctx = try_context().unwrap();
+        assert_eq!(&ctx.msg_sender().unwrap(), ctx.sender());
+        assert_eq!(ctx.msg_data().unwrap(), data);
+
+        // Same data from the trusted forwarder is unwrapped.
+        mock::set_sender(forwarder().as_str());
+        let ctx = try_context().unwrap();
+        assert_eq!(ctx.msg_sender().unwrap(), alice.address());
+        assert_eq!(ctx.msg_data().unwrap(), b"payload");
+
+        let (payload, signer) = split_signer(&data).unwrap();
+        assert_eq!((payload, signer), (&b"payload"[..], alice.address()));
+        assert!(split_signer(&[200]).is_err());
+    }
+}
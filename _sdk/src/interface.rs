@@ -0,0 +1,353 @@
+//! Typed cross-contract interfaces
+//!
+//! [`contract_interface`] turns a trait into both sides of a call: a
+//! `<Trait>Ref` that other contracts use to call a deployed implementation,
+//! and a `dispatch` method that lets a contract implementing the trait serve
+//! those calls.
+//!
+//! ```rust,ignore
+//! #[contract_interface]
+//! pub trait Counter {
+//!     fn increment(&mut self, by: u64) -> ContractResult<u64>;
+//!     fn get(&self) -> ContractResult<u64>;
+//! }
+//!
+//! // Caller side:
+//! let value = CounterRef::new(counter_address).increment(5)?;
+//!
+//! // Implementation side; exports `increment` and `get` as entrypoints:
+//! pub struct MyCounter;
+//!
+//! #[export_interface]
+//! impl Counter for MyCounter {
+//!     fn increment(&mut self, by: u64) -> ContractResult<u64> { /* ... */ }
+//!     fn get(&self) -> ContractResult<u64> { /* ... */ }
+//! }
+//! ```
+//!
+//! Each export serves its call with [`serve`]; a method that returns an error
+//! fails the call, so the host reverts it and the caller's `Ref` method
+//! returns an error too. `CounterRef::describe` reports
+//! the same entrypoints to an [`InterfaceVisitor`]; the ABI builder uses it so
+//! a contract's ABI is generated from the types it actually decodes.
+//!
+//! Arguments travel as the postcard encoding of the argument tuple, so a
+//! single argument is encoded on its own and no arguments as empty call data;
+//! results are the postcard encoding of the return value.
+
+use crate::context::try_context;
+use crate::error::{ContractError, ContractResult};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+pub use silica_contract_macros::{contract_interface, export_interface};
+
+/// Receives the entrypoints of a [`contract_interface`], as reported by the
+/// generated `<Trait>Ref::describe`.
+pub trait InterfaceVisitor {
+    /// Start an entrypoint; `mutates` is false for `&self` methods.
+    fn visit_entrypoint(&mut self, name: &str, mutates: bool);
+
+    /// Add an argument to the current entrypoint.
+    fn visit_arg<T: DeserializeOwned>(&mut self, name: &str);
+
+    /// Set the current entrypoint's return type; not called for `()`.
+    fn visit_returns<T: DeserializeOwned>(&mut self);
+}
+
+/// Serve the current call by running `entrypoint` through `dispatch` on the
+/// call data, without any trusted forwarder's signer suffix, and writing the
+/// result as return data.
+///
+/// A failure is logged and fails the call: the host reverts the call's state
+/// and the caller gets an error. Under wasm the module traps, so `serve`
+/// returns only on success.
+pub fn serve(entrypoint: &str, dispatch: impl FnOnce(&str, &[u8]) -> ContractResult<Vec<u8>>) {
+    // A new invocation: never trust context cached by an earlier one.
+    crate::context::invalidate_context_cache();
+    let result = try_context().and_then(|ctx| {
+        let output = dispatch(entrypoint, &ctx.msg_data()?)?;
+        ctx.return_bytes(&output)
+    });
+    if let Err(error) = result {
+        crate::error!(entrypoint = entrypoint, error = error; "Entrypoint failed");
+        crate::ffi::fail_call(&error);
+    }
+}
+
+/// Encode a call to `entrypoint` with `args` as one byte string, for
+/// contracts that store calls to make later, such as multisig transactions.
+///
+/// The bytes are the postcard encoding of the entrypoint name and the call
+/// data, so [`decode_call`] returns exactly what a `Ref` method would send.
+pub fn encode_call<A: Serialize + ?Sized>(entrypoint: &str, args: &A) -> ContractResult<Vec<u8>> {
+    __encode(&(entrypoint, __encode(args)?))
+}
+
+/// Split bytes from [`encode_call`] into the entrypoint and its call data.
+pub fn decode_call(data: &[u8]) -> ContractResult<(String, Vec<u8>)> {
+    __decode(data)
+}
+
+#[doc(hidden)]
+pub fn __encode<T: Serialize + ?Sized>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+#[doc(hidden)]
+pub fn __decode<T: DeserializeOwned>(bytes: &[u8]) -> ContractResult<T> {
+    postcard::from_bytes(bytes).map_err(|_| ContractError::DeserializationFailed)
+}
+
+#[doc(hidden)]
+pub fn __unknown_entrypoint(entrypoint: &str) -> ContractError {
+    ContractError::InvalidArgument(format!("Unknown entrypoint: {}", entrypoint))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::ffi::mock;
+    use crate::storage::storage;
+    use alloc::string::String;
+
+    #[contract_interface]
+    pub trait Registry {
+        /// Store `name` under `id`, returning the previous name.
+        fn register(&mut self, id: u64, name: String) -> ContractResult<Option<String>>;
+        fn lookup(&self, id: u64) -> ContractResult<Option<String>>;
+        fn clear(&mut self) -> ContractResult<()>;
+    }
+
+    /// Keeps names in the callee's storage.
+    struct StoredRegistry;
+
+    impl Registry for StoredRegistry {
+        fn register(&mut self, id: u64, name: String) -> ContractResult<Option<String>> {
+            if name.is_empty() {
+                return Err(ContractError::InvalidArgument(String::from("empty name")));
+            }
+            let previous = self.lookup(id)?;
+            storage().set(&format!("name:{}", id), &name)?;
+            Ok(previous)
+        }
+
+        fn lookup(&self, id: u64) -> ContractResult<Option<String>> {
+            storage().get(&format!("name:{}", id))
+        }
+
+        fn clear(&mut self) -> ContractResult<()> {
+            storage().remove("name:1")
+        }
+    }
+
+    const REGISTRY_CODE: [u8; 32] = [0x7e; 32];
+
+    fn registry_code(entrypoint: &str, input: &[u8]) -> ContractResult<Vec<u8>> {
+        StoredRegistry.dispatch(entrypoint, input)
+    }
+
+    fn registry() -> RegistryRef {
+        let address = Address::contract([0x7e; 32]);
+        mock::register_code(REGISTRY_CODE, registry_code);
+        mock::set_code_hash(address.as_str(), REGISTRY_CODE);
+        RegistryRef::new(address)
+    }
+
+    #[test]
+    fn callers_reach_implementations_through_dispatch() {
+        let _guard = mock::test_env();
+        let registry = registry();
+
+        assert_eq!(registry.register(1, String::from("alice")).unwrap(), None);
+        assert_eq!(
+            registry.register(1, String::from("bob")).unwrap(),
+            Some(String::from("alice"))
+        );
+        assert_eq!(registry.lookup(1).unwrap(), Some(String::from("bob")));
+        registry.clear().unwrap();
+        assert_eq!(registry.lookup(1).unwrap(), None);
+
+        // State lives with the callee, not the caller.
+        assert!(!storage().has("name:1"));
+        assert_eq!(RegistryRef::ENTRYPOINTS, ["register", "lookup", "clear"]);
+    }
+
+    /// Writes and emits, then rejects the call.
+    fn rejecting_code(_entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+        storage().set("written", &true)?;
+        crate::events::emit("Written", &true);
+        Err(ContractError::Unauthorized)
+    }
+
+    #[test]
+    fn rejected_calls_fail_and_revert() {
+        let _guard = mock::test_env();
+        let address = Address::contract([0x7f; 32]);
+        mock::register_code([0x7f; 32], rejecting_code);
+        mock::set_code_hash(address.as_str(), [0x7f; 32]);
+
+        assert!(RegistryRef::new(address.clone()).clear().is_err());
+        let caller = mock::test_contract();
+        assert!(!mock::invoke(address.as_str(), caller.as_str(), || {
+            storage().has("written")
+        }));
+        assert!(mock::take_events().is_empty());
+
+        // Errors returned by the implementation reach the caller.
+        assert!(registry().register(2, String::new()).is_err());
+    }
+
+    #[test]
+    fn encoded_calls_reach_dispatch() {
+        let _guard = mock::test_env();
+        let data = encode_call("register", &(3u64, String::from("carol"))).unwrap();
+        let (entrypoint, input) = decode_call(&data).unwrap();
+        assert_eq!(entrypoint, "register");
+        StoredRegistry.dispatch(&entrypoint, &input).unwrap();
+        assert_eq!(
+            StoredRegistry.lookup(3).unwrap(),
+            Some(String::from("carol"))
+        );
+        assert!(decode_call(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn dispatch_rejects_unknown_entrypoints_and_bad_input() {
+        let _guard = mock::test_env();
+        assert!(matches!(
+            StoredRegistry.dispatch("transfer", &[]),
+            Err(ContractError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            StoredRegistry.dispatch("lookup", &[]),
+            Err(ContractError::DeserializationFailed)
+        ));
+    }
+
+    #[test]
+    fn serve_answers_with_return_data() {
+        let _guard = mock::test_env();
+        mock::set_call_data(&__encode(&(7u64, String::from("carol"))).unwrap());
+        serve("register", |entrypoint, input| {
+            StoredRegistry.dispatch(entrypoint, input)
+        });
+        assert!(mock::take_failure().is_none());
+        assert_eq!(mock::take_return_data(), __encode(&None::<String>).unwrap());
+        assert_eq!(
+            StoredRegistry.lookup(7).unwrap(),
+            Some(String::from("carol"))
+        );
+    }
+    #[contract_interface]
+    pub trait Tally {
+        fn tally_add(&mut self, by: u64) -> ContractResult<u64>;
+        fn tally_reset(&mut self) -> ContractResult<()>;
+    }
+
+    struct StoredTally;
+
+    #[export_interface]
+    impl Tally for StoredTally {
+        fn tally_add(&mut self, by: u64) -> ContractResult<u64> {
+            let total = storage().get::<u64>("tally")?.unwrap_or(0) + by;
+            storage().set("tally", &total)?;
+            Ok(total)
+        }
+
+        fn tally_reset(&mut self) -> ContractResult<()> {
+            storage().remove("tally")
+        }
+    }
+
+    #[test]
+    fn exported_entrypoints_decode_call_data() {
+        let _guard = mock::test_env();
+        mock::set_call_data(&__encode(&5u64).unwrap());
+        tally_add();
+        tally_add();
+        assert_eq!(mock::take_return_data(), __encode(&10u64).unwrap());
+
+        assert!(mock::take_failure().is_none());
+
+        // Undecodable input fails the call without running the method.
+        mock::set_call_data(&[]);
+        tally_add();
+        assert!(matches!(
+            mock::take_failure(),
+            Some(ContractError::DeserializationFailed)
+        ));
+        assert_eq!(storage().get::<u64>("tally").unwrap(), Some(10));
+
+        tally_reset();
+        assert!(!storage().has("tally"));
+
+        let exported: Vec<_> = StoredTally::EXPORTS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(exported, TallyRef::ENTRYPOINTS);
+    }
+
+    #[test]
+    fn deployed_exports_answer_calls() {
+        let _guard = mock::test_env();
+        let address = Address::contract([0x7a; 32]);
+        mock::deploy_exports([0x7a; 32], address.as_str(), StoredTally::EXPORTS);
+        mock::set_call_data(&[0xff]);
+
+        let tally = TallyRef::new(address.clone());
+        assert_eq!(tally.tally_add(2).unwrap(), 2);
+        assert_eq!(tally.tally_add(3).unwrap(), 5);
+        // The callee's storage changed, and the caller's call data did not.
+        let caller = mock::test_contract();
+        assert_eq!(
+            mock::invoke(address.as_str(), caller.as_str(), || storage()
+                .get::<u64>("tally"))
+            .unwrap(),
+            Some(5)
+        );
+        let ctx = crate::context::context();
+        assert_eq!(ctx.call_data().unwrap(), [0xff]);
+
+        assert!(ctx.call_contract(&address, "tally_sub", &[]).is_err());
+    }
+
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl InterfaceVisitor for Recorder {
+        fn visit_entrypoint(&mut self, name: &str, mutates: bool) {
+            self.0.push(format!("{} mutates={}", name, mutates));
+        }
+
+        fn visit_arg<T: DeserializeOwned>(&mut self, name: &str) {
+            self.0
+                .push(format!("  {}: {}", name, core::any::type_name::<T>()));
+        }
+
+        fn visit_returns<T: DeserializeOwned>(&mut self) {
+            self.0.push(format!("  -> {}", core::any::type_name::<T>()));
+        }
+    }
+
+    #[test]
+    fn describe_reports_every_entrypoint() {
+        let mut recorder = Recorder::default();
+        RegistryRef::describe(&mut recorder);
+        assert_eq!(
+            recorder.0,
+            [
+                "register mutates=true",
+                "  id: u64",
+                "  name: alloc::string::String",
+                "  -> core::option::Option<alloc::string::String>",
+                "lookup mutates=false",
+                "  id: u64",
+                "  -> core::option::Option<alloc::string::String>",
+                "clear mutates=true",
+            ]
+        );
+    }
+}
@@ -18,24 +18,41 @@
 #![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
+// Lets code generated by `silica-contract-macros` name this crate from inside it.
+extern crate self as silica_contract_sdk;
 
+#[cfg(feature = "abi")]
+pub mod abi;
+pub mod address;
+pub mod codec;
 pub mod context;
 pub mod crypto;
 pub mod error;
 pub mod events;
 pub mod ffi;
+pub mod forwarder;
+pub mod interface;
+pub mod logging;
 pub mod security;
 pub mod storage;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod timelock;
+pub mod upgrade;
 
 /// Common imports for contract development
 pub mod prelude {
+    pub use crate::address::{Address, AddressKind};
     pub use crate::context::{Context, context, try_context};
     pub use crate::crypto;
     pub use crate::error::{ContractError, ContractResult};
-    pub use crate::events::{emit, log};
+    pub use crate::events::{emit, emit_with, log};
+    pub use crate::interface::{contract_interface, export_interface};
+    pub use crate::{debug, error, info, warn};
     pub use crate::security::safe_math;
+    pub use crate::security::safe_math::Amount;
     pub use crate::security::validation;
-    pub use crate::security::{AccessControl, ReentrancyGuard};
+    pub use crate::security::{AccessControl, Pausable, ReentrancyGuard};
     pub use crate::storage::{Map, Storage, storage};
 
     pub use alloc::format;
@@ -0,0 +1,164 @@
+//! Leveled, structured logging
+//!
+//! [`debug!`](crate::debug), [`info!`](crate::info), [`warn!`](crate::warn)
+//! and [`error!`](crate::error) take an optional list of `key = value` fields
+//! followed by a format string:
+//!
+//! ```rust,ignore
+//! info!(from = sender, amount = amount; "transfer to {}", recipient);
+//! warn!("allowance nearly exhausted");
+//! ```
+//!
+//! Logs are for development only. Unless the SDK's `debug-logs` feature is
+//! enabled the macros expand to dead code: arguments are still type-checked,
+//! but nothing is formatted, allocated or sent to the host, so release wasm
+//! pays nothing for them. Contracts usually forward the feature:
+//!
+//! ```toml
+//! [features]
+//! debug-logs = ["silica-contract-sdk/debug-logs"]
+//! ```
+
+use crate::ffi;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Severity of a log record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        })
+    }
+}
+
+/// A structured log record as handed to the host.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub level: Level,
+    pub message: String,
+    /// `key = value` fields in the order they were written.
+    pub fields: Vec<(String, String)>,
+}
+
+impl LogRecord {
+    /// Value of the first field named `key`.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[doc(hidden)]
+pub fn __log(level: Level, message: fmt::Arguments<'_>, fields: &[(&str, &dyn fmt::Display)]) {
+    ffi::log_record(&LogRecord {
+        level,
+        message: message.to_string(),
+        fields: fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+    });
+}
+
+#[doc(hidden)]
+#[cfg(any(test, feature = "debug-logs"))]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {
+        $crate::logging::__log(
+            $level,
+            ::core::format_args!($($arg)+),
+            &[$((::core::stringify!($key), &$value as &dyn ::core::fmt::Display)),+],
+        )
+    };
+    ($level:expr, $($arg:tt)+) => {
+        $crate::logging::__log($level, ::core::format_args!($($arg)+), &[])
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(any(test, feature = "debug-logs")))]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {
+        if false {
+            let _ = $level;
+            let _ = ::core::format_args!($($arg)+);
+            $(let _ = &$value as &dyn ::core::fmt::Display;)+
+        }
+    };
+    ($level:expr, $($arg:tt)+) => {
+        if false {
+            let _ = $level;
+            let _ = ::core::format_args!($($arg)+);
+        }
+    };
+}
+
+/// Log at [`Level::Debug`]. See the [`logging`](crate::logging) module.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::__log!($crate::logging::Level::Debug, $($arg)+) };
+}
+
+/// Log at [`Level::Info`]. See the [`logging`](crate::logging) module.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::__log!($crate::logging::Level::Info, $($arg)+) };
+}
+
+/// Log at [`Level::Warn`]. See the [`logging`](crate::logging) module.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::__log!($crate::logging::Level::Warn, $($arg)+) };
+}
+
+/// Log at [`Level::Error`]. See the [`logging`](crate::logging) module.
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::__log!($crate::logging::Level::Error, $($arg)+) };
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    #[test]
+    fn records_capture_level_message_and_fields() {
+        let _guard = mock::test_env();
+        let sender = mock::test_sender();
+
+        crate::info!(from = sender, amount = 250u64; "transfer of {} units", 250);
+        crate::warn!("low balance");
+        crate::debug!(attempt = 2; "retrying");
+
+        let records = mock::take_log_records();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].level, Level::Info);
+        assert_eq!(records[0].message, "transfer of 250 units");
+        assert_eq!(records[0].field("from"), Some(sender.as_str()));
+        assert_eq!(records[0].field("amount"), Some("250"));
+        assert_eq!(records[1].level, Level::Warn);
+        assert!(records[1].fields.is_empty());
+        assert_eq!(records[2].field("attempt"), Some("2"));
+        assert!(Level::Error > Level::Warn && Level::Info > Level::Debug);
+        assert!(mock::take_log_records().is_empty());
+    }
+}
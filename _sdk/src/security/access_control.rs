@@ -0,0 +1,983 @@
+//! Role-based access control with a role hierarchy and two-step ownership
+//!
+//! Every role has an admin role whose holders may grant and revoke it; unless
+//! changed with [`AccessControl::set_role_admin`] that is
+//! [`DEFAULT_ADMIN_ROLE`]. The owner passes every check. Ownership moves in
+//! two steps, so a mistyped address cannot take over the contract: the owner
+//! nominates a pending owner, who must then call
+//! [`AccessControl::accept_ownership`].
+//!
+//! Role members are kept in an enumerable list next to a membership index, so
+//! contracts can count and list holders without a separate registry.
+//!
+//! Roles may be granted until a block timestamp with
+//! [`AccessControl::grant_role_until`]; expired holders fail [`AccessControl::has_role`]
+//! and [`AccessControl::authorize`] but stay listed until revoked. Once an admin
+//! delay is configured, sensitive changes (ownership transfers, granting or
+//! revoking [`DEFAULT_ADMIN_ROLE`], changing role admins or the delay itself)
+//! must go through [`AccessControl::schedule_admin_change`] and
+//! [`AccessControl::apply_admin_change`], giving role holders time to react.
+
+use crate::address::Address;
+use crate::context::try_context;
+use crate::error::{ContractError, ContractResult};
+use crate::events::emit;
+use crate::storage::{Map, Vector, storage};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Role granted to the owner; by default it administers every other role.
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+
+const OWNER_KEY: &str = "__ac_owner";
+const PENDING_OWNER_KEY: &str = "__ac_pending_owner";
+const MEMBER_INDEX_BUCKET: &str = "__ac_roles";
+const ROLE_ADMIN_BUCKET: &str = "__ac_role_admin";
+const MEMBERS_PREFIX: &str = "__ac_members";
+const ROLE_EXPIRY_BUCKET: &str = "__ac_role_expiry";
+const ADMIN_DELAY_KEY: &str = "__ac_admin_delay";
+const ADMIN_CHANGE_NONCE_KEY: &str = "__ac_admin_change_nonce";
+const ADMIN_CHANGE_BUCKET: &str = "__ac_admin_changes";
+
+/// An admin action that can be queued behind the admin delay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdminChange {
+    /// Nominate a new owner, as [`AccessControl::transfer_ownership`].
+    TransferOwnership { new_owner: Address },
+    /// Grant `role`, optionally until `expires_at`.
+    GrantRole {
+        role: String,
+        account: Address,
+        expires_at: Option<u64>,
+    },
+    /// Revoke `role` from `account`.
+    RevokeRole { role: String, account: Address },
+    /// Make `admin_role` the admin of `role`.
+    SetRoleAdmin { role: String, admin_role: String },
+    /// Change the admin delay, in seconds.
+    SetAdminDelay { delay: u64 },
+}
+
+/// A scheduled [`AdminChange`] and when it may be applied.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingAdminChange {
+    pub change: AdminChange,
+    pub proposer: Address,
+    pub ready_at: u64,
+}
+
+/// Emitted when `account` is granted `role`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleGranted {
+    pub role: String,
+    pub account: Address,
+    pub sender: Address,
+    pub expires_at: Option<u64>,
+}
+
+/// Emitted when `account` loses `role`, including by renouncing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleRevoked {
+    pub role: String,
+    pub account: Address,
+    pub sender: Address,
+}
+
+/// Emitted when the admin role of `role` changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleAdminChanged {
+    pub role: String,
+    pub previous_admin_role: String,
+    pub new_admin_role: String,
+}
+
+/// Emitted when the owner nominates a new owner.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipTransferStarted {
+    pub previous_owner: Address,
+    pub new_owner: Address,
+}
+
+/// Emitted when an admin change is queued.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminChangeScheduled {
+    pub id: u64,
+    pub change: AdminChange,
+    pub ready_at: u64,
+}
+
+/// Emitted when a queued admin change is applied or cancelled.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminChangeResolved {
+    pub id: u64,
+    pub applied: bool,
+}
+
+/// Emitted when ownership changes hands (or is first assigned).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipTransferred {
+    pub previous_owner: Option<Address>,
+    pub new_owner: Address,
+}
+
+fn member_index() -> Map<(String, Address), u64> {
+    Map::new(MEMBER_INDEX_BUCKET)
+}
+
+fn role_admins() -> Map<String, String> {
+    Map::new(ROLE_ADMIN_BUCKET)
+}
+
+fn role_expiries() -> Map<(String, Address), u64> {
+    Map::new(ROLE_EXPIRY_BUCKET)
+}
+
+fn admin_changes() -> Map<u64, PendingAdminChange> {
+    Map::new(ADMIN_CHANGE_BUCKET)
+}
+
+fn now() -> ContractResult<u64> {
+    Ok(try_context()?.block_timestamp())
+}
+
+fn invalid_argument(message: &str) -> ContractError {
+    ContractError::InvalidArgument(message.to_string())
+}
+
+fn members_key(role: &str) -> String {
+    // Hash the role so arbitrary role names cannot collide with the vector's
+    // own `::len` / `::item::` suffixes.
+    let role_hash = blake3::hash(role.as_bytes());
+    format!(
+        "{}:{}",
+        MEMBERS_PREFIX,
+        hex::encode(&role_hash.as_bytes()[..16])
+    )
+}
+
+fn members(role: &str) -> Vector<Address> {
+    Vector::new(&members_key(role))
+}
+
+#[cfg(feature = "abi")]
+impl crate::abi::AbiBuilder {
+    /// Declare the events [`AccessControl`] emits and the storage it keeps,
+    /// including the member list of each role in `roles`.
+    pub fn access_control(self, roles: &[&str]) -> Self {
+        let builder = self
+            .ty::<AdminChange>()
+            .event::<OwnershipTransferred>("OwnershipTransferred")
+            .event::<OwnershipTransferStarted>("OwnershipTransferStarted")
+            .event::<RoleAdminChanged>("RoleAdminChanged")
+            .event::<RoleGranted>("RoleGranted")
+            .event::<RoleRevoked>("RoleRevoked")
+            .event::<AdminChangeScheduled>("AdminChangeScheduled")
+            .event::<AdminChangeResolved>("AdminChangeApplied")
+            .event::<AdminChangeResolved>("AdminChangeCancelled")
+            .value::<Address>(OWNER_KEY)
+            .value::<Address>(PENDING_OWNER_KEY)
+            .map::<(String, Address), u64>(MEMBER_INDEX_BUCKET)
+            .map::<String, String>(ROLE_ADMIN_BUCKET)
+            .map::<(String, Address), u64>(ROLE_EXPIRY_BUCKET)
+            .value::<u64>(ADMIN_DELAY_KEY)
+            .value::<u64>(ADMIN_CHANGE_NONCE_KEY)
+            .map::<u64, PendingAdminChange>(ADMIN_CHANGE_BUCKET);
+        roles.iter().fold(builder, |builder, role| {
+            builder.vector::<Address>(&members_key(role))
+        })
+    }
+}
+
+/// Role-based access control manager.
+pub struct AccessControl;
+
+impl AccessControl {
+    /// Initialise access control with the given owner. Fails once an owner is
+    /// stored; ownership then only moves through
+    /// [`AccessControl::transfer_ownership`].
+    pub fn initialize(owner: &Address) -> ContractResult<()> {
+        if AccessControl::read_owner()?.is_some() {
+            return Err(invalid_argument("Access control already initialized"));
+        }
+        let mut store = storage();
+        store.set(OWNER_KEY, owner)?;
+        emit(
+            "OwnershipTransferred",
+            &OwnershipTransferred {
+                previous_owner: None,
+                new_owner: owner.clone(),
+            },
+        );
+        AccessControl::grant_role_internal(owner, DEFAULT_ADMIN_ROLE, owner, None)
+    }
+
+    /// Retrieve the stored contract owner (if any).
+    pub fn owner() -> Option<Address> {
+        AccessControl::read_owner().ok().flatten()
+    }
+
+    /// Owner nominated by [`AccessControl::transfer_ownership`], if any.
+    pub fn pending_owner() -> Option<Address> {
+        storage().get(PENDING_OWNER_KEY).ok().flatten()
+    }
+
+    /// Check if an address currently holds a role that has not expired.
+    pub fn has_role(address: &Address, role: &str) -> bool {
+        AccessControl::has_role_internal(address, role).unwrap_or(false)
+    }
+
+    /// Role whose holders may grant and revoke `role`.
+    pub fn role_admin(role: &str) -> ContractResult<String> {
+        Ok(role_admins()
+            .get(&role.to_string())?
+            .unwrap_or_else(|| DEFAULT_ADMIN_ROLE.to_string()))
+    }
+
+    /// Make `admin_role` the admin of `role`. The caller must be the owner or
+    /// hold the current admin role of `role`.
+    pub fn set_role_admin(caller: &Address, role: &str, admin_role: &str) -> ContractResult<()> {
+        AccessControl::ensure_can_administer(caller, &AccessControl::role_admin(role)?)?;
+        AccessControl::ensure_undelayed()?;
+        AccessControl::set_role_admin_internal(role, admin_role)
+    }
+
+    fn set_role_admin_internal(role: &str, admin_role: &str) -> ContractResult<()> {
+        let previous_admin_role = AccessControl::role_admin(role)?;
+        role_admins().set(&role.to_string(), &admin_role.to_string())?;
+        emit(
+            "RoleAdminChanged",
+            &RoleAdminChanged {
+                role: role.to_string(),
+                previous_admin_role,
+                new_admin_role: admin_role.to_string(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Grant a role to an address. The granter must be the owner or hold the
+    /// role's admin role. Re-granting a time-bounded role makes it permanent.
+    pub fn grant_role(granter: &Address, address: &Address, role: &str) -> ContractResult<()> {
+        AccessControl::ensure_can_administer(granter, &AccessControl::role_admin(role)?)?;
+        if role == DEFAULT_ADMIN_ROLE {
+            AccessControl::ensure_undelayed()?;
+        }
+        AccessControl::grant_role_internal(address, role, granter, None)
+    }
+
+    /// Grant a role that lapses once the block timestamp reaches `expires_at`.
+    pub fn grant_role_until(
+        granter: &Address,
+        address: &Address,
+        role: &str,
+        expires_at: u64,
+    ) -> ContractResult<()> {
+        AccessControl::ensure_can_administer(granter, &AccessControl::role_admin(role)?)?;
+        if role == DEFAULT_ADMIN_ROLE {
+            AccessControl::ensure_undelayed()?;
+        }
+        if expires_at <= now()? {
+            return Err(invalid_argument("Role expiry must be in the future"));
+        }
+        AccessControl::grant_role_internal(address, role, granter, Some(expires_at))
+    }
+
+    /// Timestamp at which `address` loses `role`, if the grant is time-bounded.
+    pub fn role_expiry(address: &Address, role: &str) -> ContractResult<Option<u64>> {
+        role_expiries().get(&(role.to_string(), address.clone()))
+    }
+
+    /// Revoke a role from an address. The revoker must be the owner or hold
+    /// the role's admin role.
+    pub fn revoke_role(revoker: &Address, address: &Address, role: &str) -> ContractResult<()> {
+        AccessControl::ensure_can_administer(revoker, &AccessControl::role_admin(role)?)?;
+        if role == DEFAULT_ADMIN_ROLE {
+            AccessControl::ensure_undelayed()?;
+        }
+        AccessControl::revoke_role_internal(address, role, revoker)
+    }
+
+    /// Give up a role held by the caller.
+    pub fn renounce_role(caller: &Address, role: &str) -> ContractResult<()> {
+        AccessControl::revoke_role_internal(caller, role, caller)
+    }
+
+    /// Number of addresses holding `role`, including expired grants.
+    pub fn role_member_count(role: &str) -> ContractResult<u64> {
+        members(role).len()
+    }
+
+    /// Number of addresses currently holding `role`; expired grants are not
+    /// counted.
+    pub fn active_role_member_count(role: &str) -> ContractResult<u64> {
+        let mut count = 0;
+        for member in AccessControl::role_members(role)? {
+            if AccessControl::has_role_internal(&member, role)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Holder of `role` at `index`. Order is not preserved across revocations.
+    pub fn role_member(role: &str, index: u64) -> ContractResult<Option<Address>> {
+        members(role).get(index)
+    }
+
+    /// All holders of `role`.
+    pub fn role_members(role: &str) -> ContractResult<Vec<Address>> {
+        let list = members(role);
+        let len = list.len()?;
+        let mut result = Vec::with_capacity(len as usize);
+        for index in 0..len {
+            if let Some(member) = list.get(index)? {
+                result.push(member);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Ensure the caller is authorised, optionally requiring a specific role.
+    pub fn authorize(caller: &Address, required_role: Option<&str>) -> ContractResult<()> {
+        if AccessControl::is_owner(caller)? {
+            return Ok(());
+        }
+
+        if let Some(role) = required_role
+            && AccessControl::has_role_internal(caller, role)?
+        {
+            return Ok(());
+        }
+
+        Err(ContractError::Unauthorized)
+    }
+
+    /// Nominate `new_owner`; ownership moves once they call
+    /// [`AccessControl::accept_ownership`]. Nominating again replaces the
+    /// pending owner.
+    pub fn transfer_ownership(current_owner: &Address, new_owner: &Address) -> ContractResult<()> {
+        if !AccessControl::is_owner(current_owner)? {
+            return Err(ContractError::Unauthorized);
+        }
+        AccessControl::ensure_undelayed()?;
+        AccessControl::start_ownership_transfer(current_owner, new_owner)
+    }
+
+    fn start_ownership_transfer(
+        current_owner: &Address,
+        new_owner: &Address,
+    ) -> ContractResult<()> {
+        storage().set(PENDING_OWNER_KEY, new_owner)?;
+        emit(
+            "OwnershipTransferStarted",
+            &OwnershipTransferStarted {
+                previous_owner: current_owner.clone(),
+                new_owner: new_owner.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Complete a transfer started with [`AccessControl::transfer_ownership`].
+    ///
+    /// The admin role moves from the previous owner to the caller.
+    pub fn accept_ownership(caller: &Address) -> ContractResult<()> {
+        match AccessControl::pending_owner() {
+            Some(pending) if pending == *caller => {}
+            _ => return Err(ContractError::Unauthorized),
+        }
+
+        let previous_owner = AccessControl::read_owner()?;
+        let mut store = storage();
+        store.set(OWNER_KEY, caller)?;
+        store.remove(PENDING_OWNER_KEY)?;
+
+        AccessControl::grant_role_internal(caller, DEFAULT_ADMIN_ROLE, caller, None)?;
+        if let Some(previous) = &previous_owner
+            && previous != caller
+        {
+            AccessControl::revoke_role_internal(previous, DEFAULT_ADMIN_ROLE, caller)?;
+        }
+
+        emit(
+            "OwnershipTransferred",
+            &OwnershipTransferred {
+                previous_owner,
+                new_owner: caller.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Delay, in seconds, between scheduling and applying an admin change.
+    pub fn admin_delay() -> ContractResult<u64> {
+        Ok(storage().get(ADMIN_DELAY_KEY)?.unwrap_or(0))
+    }
+
+    /// Set the initial admin delay. Only the owner may call this, and only
+    /// while no delay is configured; afterwards schedule
+    /// [`AdminChange::SetAdminDelay`] instead.
+    pub fn set_admin_delay(caller: &Address, delay: u64) -> ContractResult<()> {
+        if !AccessControl::is_owner(caller)? {
+            return Err(ContractError::Unauthorized);
+        }
+        AccessControl::ensure_undelayed()?;
+        storage().set(ADMIN_DELAY_KEY, &delay)
+    }
+
+    /// Queue `change` to be applied after the admin delay, returning its id.
+    ///
+    /// The caller must be allowed to make the change now and again when it is
+    /// applied.
+    pub fn schedule_admin_change(caller: &Address, change: AdminChange) -> ContractResult<u64> {
+        AccessControl::ensure_can_apply(caller, &change)?;
+
+        let ready_at = now()?
+            .checked_add(AccessControl::admin_delay()?)
+            .ok_or(ContractError::Overflow)?;
+        let mut store = storage();
+        let id = store.get::<u64>(ADMIN_CHANGE_NONCE_KEY)?.unwrap_or(0);
+        store.set(ADMIN_CHANGE_NONCE_KEY, &(id + 1))?;
+
+        admin_changes().set(
+            &id,
+            &PendingAdminChange {
+                change: change.clone(),
+                proposer: caller.clone(),
+                ready_at,
+            },
+        )?;
+        emit(
+            "AdminChangeScheduled",
+            &AdminChangeScheduled {
+                id,
+                change,
+                ready_at,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Look up a queued admin change.
+    pub fn pending_admin_change(id: u64) -> ContractResult<Option<PendingAdminChange>> {
+        admin_changes().get(&id)
+    }
+
+    /// Apply a queued admin change whose delay has elapsed.
+    pub fn apply_admin_change(caller: &Address, id: u64) -> ContractResult<()> {
+        let pending = AccessControl::pending_admin_change(id)?
+            .ok_or_else(|| invalid_argument("Unknown admin change"))?;
+        if now()? < pending.ready_at {
+            return Err(invalid_argument("Admin change is not ready"));
+        }
+        AccessControl::ensure_can_apply(caller, &pending.change)?;
+        admin_changes().remove(&id)?;
+
+        match pending.change {
+            AdminChange::TransferOwnership { new_owner } => {
+                AccessControl::start_ownership_transfer(caller, &new_owner)?
+            }
+            AdminChange::GrantRole {
+                role,
+                account,
+                expires_at,
+            } => AccessControl::grant_role_internal(&account, &role, caller, expires_at)?,
+            AdminChange::RevokeRole { role, account } => {
+                AccessControl::revoke_role_internal(&account, &role, caller)?
+            }
+            AdminChange::SetRoleAdmin { role, admin_role } => {
+                AccessControl::set_role_admin_internal(&role, &admin_role)?
+            }
+            AdminChange::SetAdminDelay { delay } => storage().set(ADMIN_DELAY_KEY, &delay)?,
+        }
+
+        emit(
+            "AdminChangeApplied",
+            &AdminChangeResolved { id, applied: true },
+        );
+        Ok(())
+    }
+
+    /// Drop a queued admin change. Only its proposer or the owner may cancel.
+    pub fn cancel_admin_change(caller: &Address, id: u64) -> ContractResult<()> {
+        let pending = AccessControl::pending_admin_change(id)?
+            .ok_or_else(|| invalid_argument("Unknown admin change"))?;
+        if pending.proposer != *caller && !AccessControl::is_owner(caller)? {
+            return Err(ContractError::Unauthorized);
+        }
+        admin_changes().remove(&id)?;
+        emit(
+            "AdminChangeCancelled",
+            &AdminChangeResolved { id, applied: false },
+        );
+        Ok(())
+    }
+
+    fn ensure_undelayed() -> ContractResult<()> {
+        if AccessControl::admin_delay()? > 0 {
+            return Err(invalid_argument("Admin change must be scheduled"));
+        }
+        Ok(())
+    }
+
+    fn ensure_can_apply(caller: &Address, change: &AdminChange) -> ContractResult<()> {
+        match change {
+            AdminChange::TransferOwnership { .. } | AdminChange::SetAdminDelay { .. } => {
+                if AccessControl::is_owner(caller)? {
+                    Ok(())
+                } else {
+                    Err(ContractError::Unauthorized)
+                }
+            }
+            AdminChange::GrantRole { role, .. }
+            | AdminChange::RevokeRole { role, .. }
+            | AdminChange::SetRoleAdmin { role, .. } => {
+                AccessControl::ensure_can_administer(caller, &AccessControl::role_admin(role)?)
+            }
+        }
+    }
+
+    fn read_owner() -> ContractResult<Option<Address>> {
+        storage().get::<Address>(OWNER_KEY)
+    }
+
+    fn is_owner(address: &Address) -> ContractResult<bool> {
+        Ok(AccessControl::read_owner()?.is_some_and(|owner| owner == *address))
+    }
+
+    fn ensure_can_administer(caller: &Address, admin_role: &str) -> ContractResult<()> {
+        if AccessControl::is_owner(caller)? || AccessControl::has_role_internal(caller, admin_role)?
+        {
+            return Ok(());
+        }
+        Err(ContractError::Unauthorized)
+    }
+
+    fn grant_role_internal(
+        address: &Address,
+        role: &str,
+        sender: &Address,
+        expires_at: Option<u64>,
+    ) -> ContractResult<()> {
+        let key = (role.to_string(), address.clone());
+        let mut index = member_index();
+        let mut expiries = role_expiries();
+        if index.contains_key(&key)? {
+            if expiries.get(&key)? == expires_at {
+                return Ok(());
+            }
+        } else {
+            let mut list = members(role);
+            let position = list.len()?;
+            list.push(address)?;
+            index.set(&key, &position)?;
+        }
+
+        match expires_at {
+            Some(expiry) => expiries.set(&key, &expiry)?,
+            None => expiries.remove(&key)?,
+        }
+
+        emit(
+            "RoleGranted",
+            &RoleGranted {
+                role: key.0,
+                account: key.1,
+                sender: sender.clone(),
+                expires_at,
+            },
+        );
+        Ok(())
+    }
+
+    fn revoke_role_internal(address: &Address, role: &str, sender: &Address) -> ContractResult<()> {
+        let key = (role.to_string(), address.clone());
+        let mut index = member_index();
+        let Some(position) = index.get(&key)? else {
+            return Ok(());
+        };
+
+        // Swap-remove: move the last member into the vacated slot.
+        let mut list = members(role);
+        let last = list
+            .pop()?
+            .ok_or_else(|| ContractError::Custom("Role member list is corrupted".to_string()))?;
+        if last != *address {
+            list.set(position, &last)?;
+            index.set(&(role.to_string(), last), &position)?;
+        }
+        index.remove(&key)?;
+        role_expiries().remove(&key)?;
+
+        emit(
+            "RoleRevoked",
+            &RoleRevoked {
+                role: key.0,
+                account: key.1,
+                sender: sender.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    fn has_role_internal(address: &Address, role: &str) -> ContractResult<bool> {
+        let key = (role.to_string(), address.clone());
+        if !member_index().contains_key(&key)? {
+            return Ok(false);
+        }
+        match role_expiries().get(&key)? {
+            Some(expiry) => Ok(now()? < expiry),
+            None => Ok(true),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    fn account(seed: u8) -> Address {
+        Address::from_public_key(&[seed; 32])
+    }
+
+    #[test]
+    fn access_control_basics() {
+        let _guard = mock::test_env();
+
+        let owner = account(1);
+        AccessControl::initialize(&owner).expect("init owner");
+
+        assert_eq!(AccessControl::owner(), Some(owner.clone()));
+        assert!(AccessControl::authorize(&owner, None).is_ok());
+        assert!(AccessControl::has_role(&owner, DEFAULT_ADMIN_ROLE));
+
+        let user = account(2);
+        assert!(!AccessControl::has_role(&user, "admin"));
+        assert!(AccessControl::authorize(&user, Some("admin")).is_err());
+
+        AccessControl::grant_role(&owner, &user, "admin").expect("grant admin");
+        assert!(AccessControl::has_role(&user, "admin"));
+        AccessControl::authorize(&user, Some("admin")).expect("user authorised");
+
+        AccessControl::revoke_role(&owner, &user, "admin").expect("revoke admin");
+        assert!(!AccessControl::has_role(&user, "admin"));
+    }
+
+    #[test]
+    fn initialize_cannot_replace_the_owner() {
+        let _guard = mock::test_env();
+
+        let owner = account(1);
+        let intruder = account(2);
+        AccessControl::initialize(&owner).expect("init owner");
+        mock::take_events();
+
+        assert!(AccessControl::initialize(&intruder).is_err());
+        assert_eq!(AccessControl::owner(), Some(owner.clone()));
+        assert!(AccessControl::has_role(&owner, DEFAULT_ADMIN_ROLE));
+        assert!(!AccessControl::has_role(&intruder, DEFAULT_ADMIN_ROLE));
+        assert!(mock::take_events().is_empty());
+    }
+
+    #[test]
+    fn ownership_transfer_takes_two_steps() {
+        let _guard = mock::test_env();
+
+        let owner = account(1);
+        let new_owner = account(3);
+        let stranger = account(4);
+        AccessControl::initialize(&owner).expect("init owner");
+
+        assert!(AccessControl::transfer_ownership(&stranger, &new_owner).is_err());
+        AccessControl::transfer_ownership(&owner, &new_owner).expect("start transfer");
+        assert_eq!(AccessControl::owner(), Some(owner.clone()));
+        assert_eq!(AccessControl::pending_owner(), Some(new_owner.clone()));
+
+        assert!(matches!(
+            AccessControl::accept_ownership(&stranger),
+            Err(ContractError::Unauthorized)
+        ));
+        AccessControl::accept_ownership(&new_owner).expect("accept");
+
+        assert_eq!(AccessControl::owner(), Some(new_owner.clone()));
+        assert_eq!(AccessControl::pending_owner(), None);
+        assert!(AccessControl::authorize(&owner, Some("admin")).is_err());
+        AccessControl::authorize(&new_owner, Some("admin")).expect("new owner admin");
+        assert!(AccessControl::accept_ownership(&new_owner).is_err());
+    }
+
+    #[test]
+    fn role_admins_form_a_hierarchy() {
+        let _guard = mock::test_env();
+
+        let owner = account(1);
+        let manager = account(2);
+        let minter = account(3);
+        AccessControl::initialize(&owner).expect("init owner");
+
+        AccessControl::set_role_admin(&owner, "minter", "minter_admin").expect("set admin");
+        assert_eq!(AccessControl::role_admin("minter").unwrap(), "minter_admin");
+        assert_eq!(
+            AccessControl::role_admin("burner").unwrap(),
+            DEFAULT_ADMIN_ROLE
+        );
+
+        // Without the admin role the manager cannot grant minters.
+        assert!(AccessControl::grant_role(&manager, &minter, "minter").is_err());
+
+        AccessControl::grant_role(&owner, &manager, "minter_admin").expect("grant manager");
+        AccessControl::grant_role(&manager, &minter, "minter").expect("grant minter");
+        assert!(AccessControl::has_role(&minter, "minter"));
+
+        // ...but is not an admin of unrelated roles.
+        assert!(AccessControl::grant_role(&manager, &minter, "burner").is_err());
+        assert!(AccessControl::set_role_admin(&manager, "burner", "minter_admin").is_err());
+
+        AccessControl::renounce_role(&minter, "minter").expect("renounce");
+        assert!(!AccessControl::has_role(&minter, "minter"));
+    }
+
+    #[test]
+    fn role_members_are_enumerable() {
+        let _guard = mock::test_env();
+
+        let owner = account(1);
+        AccessControl::initialize(&owner).expect("init owner");
+        let holders: Vec<Address> = (10..14).map(account).collect();
+        for holder in &holders {
+            AccessControl::grant_role(&owner, holder, "keeper").expect("grant");
+        }
+        // Granting twice does not duplicate the member.
+        AccessControl::grant_role(&owner, &holders[0], "keeper").expect("grant again");
+
+        assert_eq!(AccessControl::role_member_count("keeper").unwrap(), 4);
+        assert_eq!(AccessControl::role_members("keeper").unwrap(), holders);
+
+        AccessControl::revoke_role(&owner, &holders[1], "keeper").expect("revoke");
+        let mut remaining = AccessControl::role_members("keeper").unwrap();
+        remaining.sort();
+        let mut expected = vec![holders[0].clone(), holders[2].clone(), holders[3].clone()];
+        expected.sort();
+        assert_eq!(remaining, expected);
+        assert_eq!(AccessControl::role_member_count("keeper").unwrap(), 3);
+        assert!(AccessControl::has_role(&holders[3], "keeper"));
+
+        for holder in &expected {
+            AccessControl::revoke_role(&owner, holder, "keeper").expect("revoke");
+        }
+        assert_eq!(AccessControl::role_member_count("keeper").unwrap(), 0);
+        assert_eq!(AccessControl::role_member("keeper", 0).unwrap(), None);
+    }
+
+    #[test]
+    fn time_bounded_roles_expire() {
+        let _guard = mock::test_env();
+        let start = 1_700_000_000;
+
+        let owner = account(1);
+        let operator = account(2);
+        AccessControl::initialize(&owner).expect("init owner");
+
+        assert!(AccessControl::grant_role_until(&owner, &operator, "emergency", start).is_err());
+        AccessControl::grant_role_until(&owner, &operator, "emergency", start + 100)
+            .expect("grant until");
+        assert!(AccessControl::has_role(&operator, "emergency"));
+        assert_eq!(
+            AccessControl::role_expiry(&operator, "emergency").unwrap(),
+            Some(start + 100)
+        );
+        AccessControl::authorize(&operator, Some("emergency")).expect("authorised");
+
+        mock::set_block_timestamp(start + 100);
+        assert!(!AccessControl::has_role(&operator, "emergency"));
+        assert!(AccessControl::authorize(&operator, Some("emergency")).is_err());
+        // Expired holders stay listed until revoked, but are not active.
+        assert_eq!(AccessControl::role_member_count("emergency").unwrap(), 1);
+        assert_eq!(
+            AccessControl::active_role_member_count("emergency").unwrap(),
+            0
+        );
+
+        AccessControl::grant_role(&owner, &operator, "emergency").expect("make permanent");
+        assert!(AccessControl::has_role(&operator, "emergency"));
+        assert_eq!(
+            AccessControl::role_expiry(&operator, "emergency").unwrap(),
+            None
+        );
+        assert_eq!(AccessControl::role_member_count("emergency").unwrap(), 1);
+        assert_eq!(
+            AccessControl::active_role_member_count("emergency").unwrap(),
+            1
+        );
+
+        AccessControl::grant_role_until(&owner, &operator, "emergency", start + 200)
+            .expect("bound again");
+        AccessControl::revoke_role(&owner, &operator, "emergency").expect("revoke");
+        assert_eq!(
+            AccessControl::role_expiry(&operator, "emergency").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn sensitive_changes_wait_for_the_admin_delay() {
+        let _guard = mock::test_env();
+        let start = 1_700_000_000;
+
+        let owner = account(1);
+        let new_owner = account(2);
+        let stranger = account(3);
+        AccessControl::initialize(&owner).expect("init owner");
+        AccessControl::set_admin_delay(&owner, 3_600).expect("set delay");
+
+        // Sensitive changes can no longer be made directly; others still can.
+        assert!(AccessControl::transfer_ownership(&owner, &new_owner).is_err());
+        assert!(AccessControl::grant_role(&owner, &stranger, DEFAULT_ADMIN_ROLE).is_err());
+        assert!(AccessControl::set_role_admin(&owner, "keeper", "ops").is_err());
+        assert!(AccessControl::set_admin_delay(&owner, 0).is_err());
+        AccessControl::grant_role(&owner, &stranger, "keeper").expect("plain grant");
+
+        let transfer = AdminChange::TransferOwnership {
+            new_owner: new_owner.clone(),
+        };
+        assert!(matches!(
+            AccessControl::schedule_admin_change(&stranger, transfer.clone()),
+            Err(ContractError::Unauthorized)
+        ));
+        let id = AccessControl::schedule_admin_change(&owner, transfer).expect("schedule");
+        let pending = AccessControl::pending_admin_change(id).unwrap().unwrap();
+        assert_eq!(pending.ready_at, start + 3_600);
+        assert!(AccessControl::apply_admin_change(&owner, id).is_err());
+
+        mock::set_block_timestamp(start + 3_600);
+        assert!(AccessControl::apply_admin_change(&stranger, id).is_err());
+        AccessControl::apply_admin_change(&owner, id).expect("apply");
+        assert_eq!(AccessControl::pending_admin_change(id).unwrap(), None);
+        assert_eq!(AccessControl::pending_owner(), Some(new_owner.clone()));
+        AccessControl::accept_ownership(&new_owner).expect("accept");
+
+        let grant = AdminChange::GrantRole {
+            role: DEFAULT_ADMIN_ROLE.to_string(),
+            account: stranger.clone(),
+            expires_at: None,
+        };
+        let id = AccessControl::schedule_admin_change(&new_owner, grant).expect("schedule");
+        assert!(AccessControl::cancel_admin_change(&stranger, id).is_err());
+        AccessControl::cancel_admin_change(&new_owner, id).expect("cancel");
+        mock::set_block_timestamp(start + 7_200);
+        assert!(AccessControl::apply_admin_change(&new_owner, id).is_err());
+        assert!(!AccessControl::has_role(&stranger, DEFAULT_ADMIN_ROLE));
+
+        let id = AccessControl::schedule_admin_change(
+            &new_owner,
+            AdminChange::SetAdminDelay { delay: 0 },
+        )
+        .expect("schedule");
+        mock::set_block_timestamp(start + 10_800);
+        AccessControl::apply_admin_change(&new_owner, id).expect("apply");
+        assert_eq!(AccessControl::admin_delay().unwrap(), 0);
+        AccessControl::set_role_admin(&new_owner, "keeper", "ops").expect("direct again");
+    }
+
+    #[test]
+    fn admins_cannot_be_stripped_without_the_delay() {
+        let _guard = mock::test_env();
+        let start = 1_700_000_000;
+
+        let owner = account(1);
+        let timelock = account(2);
+        AccessControl::initialize(&owner).expect("init owner");
+        AccessControl::grant_role(&owner, &timelock, DEFAULT_ADMIN_ROLE).expect("grant admin");
+        AccessControl::set_admin_delay(&owner, 3_600).expect("set delay");
+
+        assert!(AccessControl::revoke_role(&owner, &timelock, DEFAULT_ADMIN_ROLE).is_err());
+        assert!(AccessControl::revoke_role(&timelock, &owner, DEFAULT_ADMIN_ROLE).is_err());
+        assert!(AccessControl::has_role(&timelock, DEFAULT_ADMIN_ROLE));
+
+        let revoke = AdminChange::RevokeRole {
+            role: DEFAULT_ADMIN_ROLE.to_string(),
+            account: timelock.clone(),
+        };
+        let id = AccessControl::schedule_admin_change(&owner, revoke).expect("schedule");
+        assert!(AccessControl::apply_admin_change(&owner, id).is_err());
+        assert!(AccessControl::has_role(&timelock, DEFAULT_ADMIN_ROLE));
+
+        mock::set_block_timestamp(start + 3_600);
+        AccessControl::apply_admin_change(&owner, id).expect("apply");
+        assert!(!AccessControl::has_role(&timelock, DEFAULT_ADMIN_ROLE));
+    }
+
+    #[test]
+    fn changes_emit_events() {
+        let _guard = mock::test_env();
+
+        let owner = account(1);
+        let user = account(2);
+        AccessControl::initialize(&owner).expect("init owner");
+        AccessControl::grant_role(&owner, &user, "keeper").expect("grant");
+        AccessControl::grant_role(&owner, &user, "keeper").expect("grant again");
+        AccessControl::renounce_role(&user, "keeper").expect("renounce");
+        AccessControl::transfer_ownership(&owner, &user).expect("start");
+        AccessControl::accept_ownership(&user).expect("accept");
+
+        let events = mock::take_events();
+        let granted: RoleGranted = postcard::from_bytes(&events[2].data).expect("decode");
+        assert_eq!(
+            granted,
+            RoleGranted {
+                role: "keeper".to_string(),
+                account: user.clone(),
+                sender: owner.clone(),
+                expires_at: None,
+            }
+        );
+
+        let transferred: OwnershipTransferred =
+            postcard::from_bytes(&events.last().unwrap().data).expect("decode");
+        assert_eq!(transferred.previous_owner, Some(owner));
+        assert_eq!(transferred.new_owner, user);
+
+        let topics: Vec<String> = events.into_iter().map(|event| event.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                "OwnershipTransferred",
+                "RoleGranted",
+                "RoleGranted",
+                "RoleRevoked",
+                "OwnershipTransferStarted",
+                "RoleGranted",
+                "RoleRevoked",
+                "OwnershipTransferred",
+            ]
+        );
+    }
+
+    #[cfg(feature = "abi")]
+    #[test]
+    fn abi_declares_role_member_lists() {
+        use crate::abi::{AbiBuilder, StorageKind};
+
+        let _guard = mock::test_env();
+        let owner = account(1);
+        AccessControl::initialize(&owner).unwrap();
+        AccessControl::grant_role(&owner, &account(2), "minter").unwrap();
+
+        let abi = AbiBuilder::new("roles", "1.0.0")
+            .access_control(&["minter"])
+            .build()
+            .unwrap();
+        let lists: Vec<&str> = abi
+            .storage
+            .iter()
+            .filter(|entry| matches!(entry.kind, StorageKind::Vector { .. }))
+            .map(|entry| entry.key.as_str())
+            .collect();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(Vector::<Address>::new(lists[0]).len().unwrap(), 1);
+        assert!(abi.events.iter().any(|event| event.topic == "RoleGranted"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Nonces and replay protection for signed requests
+//!
+//! - [`Nonces`] are sequential: each signer's requests must be used in order,
+//!   which suits permits and meta-transactions relayed one at a time.
+//! - [`UnorderedNonces`] are tracked in a per-signer bitmap, so relayers may
+//!   submit requests in any order or in parallel; a nonce is simply marked
+//!   used, and whole words of nonces can be cancelled at once.
+//!
+//! Both plug into [`crate::crypto::typed_data`]: a message implementing
+//! [`Nonced`] is checked against its deadline, verified against the signer's
+//! key and only then has its nonce consumed.
+//!
+//! ```rust,ignore
+//! let owner = Nonces::consume_signed(&domain, &signer, &permit, &signature)?;
+//! ```
+
+use crate::address::Address;
+use crate::context::try_context;
+use crate::crypto::typed_data::{Domain, TypedData};
+use crate::error::{ContractError, ContractResult};
+use crate::storage::Map;
+
+const SEQUENTIAL_BUCKET: &str = "__nonces";
+const UNORDERED_BUCKET: &str = "__nonce_bitmap";
+
+/// A signed message carrying its own nonce and deadline.
+pub trait Nonced: TypedData {
+    fn nonce(&self) -> u64;
+
+    /// Last block timestamp at which the message may be used.
+    fn deadline(&self) -> u64;
+}
+
+/// Fail with [`ContractError::Expired`] once the block timestamp is past
+/// `deadline`.
+pub fn check_deadline(deadline: u64) -> ContractResult<()> {
+    if try_context()?.block_timestamp() > deadline {
+        return Err(ContractError::Expired { deadline });
+    }
+    Ok(())
+}
+
+/// Check the deadline and signature of `message`, returning the signer's
+/// address.
+fn verify_signed<T: Nonced>(
+    domain: &Domain,
+    signer: &[u8; 32],
+    message: &T,
+    signature: &[u8; 64],
+) -> ContractResult<Address> {
+    check_deadline(message.deadline())?;
+    if !domain.verify_typed(signer, message, signature)? {
+        return Err(ContractError::InvalidSignature);
+    }
+    Ok(Address::from_public_key(signer))
+}
+
+/// Sequential per-signer nonces.
+pub struct Nonces;
+
+impl Nonces {
+    fn counters() -> Map<Address, u64> {
+        Map::new(SEQUENTIAL_BUCKET)
+    }
+
+    /// The next nonce `owner` must use.
+    pub fn current(owner: &Address) -> ContractResult<u64> {
+        Ok(Nonces::counters().get(owner)?.unwrap_or(0))
+    }
+
+    /// Consume and return `owner`'s next nonce.
+    pub fn use_nonce(owner: &Address) -> ContractResult<u64> {
+        let nonce = Nonces::current(owner)?;
+        let next = nonce.checked_add(1).ok_or(ContractError::Overflow)?;
+        Nonces::counters().set(owner, &next)?;
+        Ok(nonce)
+    }
+
+    /// Consume `nonce`, failing with [`ContractError::InvalidNonce`] unless it
+    /// is `owner`'s next nonce.
+    pub fn use_checked_nonce(owner: &Address, nonce: u64) -> ContractResult<()> {
+        let expected = Nonces::current(owner)?;
+        if nonce != expected {
+            return Err(ContractError::InvalidNonce {
+                expected,
+                provided: nonce,
+            });
+        }
+        Nonces::use_nonce(owner).map(|_| ())
+    }
+
+    /// Verify a signed `message` and consume its nonce in sequence.
+    pub fn consume_signed<T: Nonced>(
+        domain: &Domain,
+        signer: &[u8; 32],
+        message: &T,
+        signature: &[u8; 64],
+    ) -> ContractResult<Address> {
+        let owner = verify_signed(domain, signer, message, signature)?;
+        Nonces::use_checked_nonce(&owner, message.nonce())?;
+        Ok(owner)
+    }
+}
+
+/// Unordered per-signer nonces stored as 64-bit bitmap words.
+///
+/// Nonce `n` lives in word `n / 64`, bit `n % 64`.
+pub struct UnorderedNonces;
+
+impl UnorderedNonces {
+    fn words() -> Map<(Address, u64), u64> {
+        Map::new(UNORDERED_BUCKET)
+    }
+
+    fn word(owner: &Address, index: u64) -> ContractResult<u64> {
+        Ok(UnorderedNonces::words()
+            .get(&(owner.clone(), index))?
+            .unwrap_or(0))
+    }
+
+    /// Whether `nonce` has been used or invalidated for `owner`.
+    pub fn is_used(owner: &Address, nonce: u64) -> ContractResult<bool> {
+        let word = UnorderedNonces::word(owner, nonce / 64)?;
+        Ok(word & (1 << (nonce % 64)) != 0)
+    }
+
+    /// Mark `nonce` used, failing with [`ContractError::NonceAlreadyUsed`] if
+    /// it already was.
+    pub fn use_nonce(owner: &Address, nonce: u64) -> ContractResult<()> {
+        let index = nonce / 64;
+        let bit = 1 << (nonce % 64);
+        let word = UnorderedNonces::word(owner, index)?;
+        if word & bit != 0 {
+            return Err(ContractError::NonceAlreadyUsed { nonce });
+        }
+        UnorderedNonces::words().set(&(owner.clone(), index), &(word | bit))
+    }
+
+    /// Invalidate every nonce of word `index` whose bit is set in `mask`, so
+    /// outstanding signed requests using them can no longer be relayed.
+    pub fn invalidate(owner: &Address, index: u64, mask: u64) -> ContractResult<()> {
+        let word = UnorderedNonces::word(owner, index)?;
+        UnorderedNonces::words().set(&(owner.clone(), index), &(word | mask))
+    }
+
+    /// Verify a signed `message` and mark its nonce used.
+    pub fn consume_signed<T: Nonced>(
+        domain: &Domain,
+        signer: &[u8; 32],
+        message: &T,
+        signature: &[u8; 64],
+    ) -> ContractResult<Address> {
+        let owner = verify_signed(domain, signer, message, signature)?;
+        UnorderedNonces::use_nonce(&owner, message.nonce())?;
+        Ok(owner)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+    use crate::testing::Keyring;
+    use serde::Serialize;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[derive(Serialize)]
+    struct Permit {
+        spender: Address,
+        value: u64,
+        nonce: u64,
+        deadline: u64,
+    }
+
+    impl TypedData for Permit {
+        const TYPE_NAME: &'static str = "Permit(spender,value,nonce,deadline)";
+    }
+
+    impl Nonced for Permit {
+        fn nonce(&self) -> u64 {
+            self.nonce
+        }
+
+        fn deadline(&self) -> u64 {
+            self.deadline
+        }
+    }
+
+    fn permit(nonce: u64, deadline: u64) -> Permit {
+        Permit {
+            spender: Address::from_public_key(&[7; 32]),
+            value: 100,
+            nonce,
+            deadline,
+        }
+    }
+
+    #[test]
+    fn sequential_nonces_must_be_used_in_order() {
+        let _guard = mock::test_env();
+        let owner = Address::from_public_key(&[1; 32]);
+
+        assert_eq!(Nonces::current(&owner).unwrap(), 0);
+        assert_eq!(Nonces::use_nonce(&owner).unwrap(), 0);
+        Nonces::use_checked_nonce(&owner, 1).expect("next nonce");
+
+        assert!(matches!(
+            Nonces::use_checked_nonce(&owner, 1),
+            Err(ContractError::InvalidNonce {
+                expected: 2,
+                provided: 1
+            })
+        ));
+        assert!(matches!(
+            Nonces::use_checked_nonce(&owner, 5),
+            Err(ContractError::InvalidNonce { expected: 2, .. })
+        ));
+        assert_eq!(Nonces::current(&owner).unwrap(), 2);
+    }
+
+    #[test]
+    fn unordered_nonces_use_a_bitmap() {
+        let _guard = mock::test_env();
+        let owner = Address::from_public_key(&[1; 32]);
+        let other = Address::from_public_key(&[2; 32]);
+
+        UnorderedNonces::use_nonce(&owner, 70).expect("out of order");
+        UnorderedNonces::use_nonce(&owner, 3).expect("earlier nonce");
+        assert!(matches!(
+            UnorderedNonces::use_nonce(&owner, 70),
+            Err(ContractError::NonceAlreadyUsed { nonce: 70 })
+        ));
+        assert!(!UnorderedNonces::is_used(&owner, 71).unwrap());
+        assert!(!UnorderedNonces::is_used(&other, 70).unwrap());
+
+        UnorderedNonces::invalidate(&owner, 1, u64::MAX).expect("cancel word");
+        assert!(UnorderedNonces::is_used(&owner, 127).unwrap());
+        assert!(!UnorderedNonces::is_used(&owner, 128).unwrap());
+    }
+
+    #[test]
+    fn signed_requests_cannot_be_replayed_or_used_late() {
+        let _guard = mock::test_env();
+        let alice = Keyring::new().alice();
+        let domain = Domain::for_current_contract("Token", "1", 7).expect("domain");
+        let sign = |message: &Permit| alice.sign_typed(&domain, message).expect("sign");
+
+        let first = permit(0, NOW + 60);
+        let owner = Nonces::consume_signed(&domain, &alice.public_key(), &first, &sign(&first))
+            .expect("first permit");
+        assert_eq!(owner, alice.address());
+        assert!(matches!(
+            Nonces::consume_signed(&domain, &alice.public_key(), &first, &sign(&first)),
+            Err(ContractError::InvalidNonce { expected: 1, .. })
+        ));
+
+        let forged = permit(1, NOW + 60);
+        assert!(matches!(
+            Nonces::consume_signed(&domain, &alice.public_key(), &forged, &sign(&first)),
+            Err(ContractError::InvalidSignature)
+        ));
+
+        let late = permit(9, NOW + 60);
+        mock::set_block_timestamp(NOW + 61);
+        assert!(matches!(
+            UnorderedNonces::consume_signed(&domain, &alice.public_key(), &late, &sign(&late)),
+            Err(ContractError::Expired { deadline }) if deadline == NOW + 60
+        ));
+        assert!(!UnorderedNonces::is_used(&alice.address(), 9).unwrap());
+    }
+}
@@ -0,0 +1,358 @@
+//! Emergency pause (circuit breaker)
+//!
+//! A contract can be paused globally or per function. Holders of
+//! [`GUARDIAN_ROLE`] (and the owner or [`DEFAULT_ADMIN_ROLE`] holders) may
+//! pause; only the owner or admins may unpause, so a compromised guardian key
+//! can halt the contract but not resume it. A pause may carry a timeout after
+//! which it lapses on its own.
+//!
+//! Entry points opt in with [`Pausable::when_not_paused`] or
+//! [`Pausable::when_function_not_paused`]:
+//!
+//! ```rust,ignore
+//! Pausable::when_function_not_paused("stake")?;
+//! ```
+
+use super::access_control::{AccessControl, DEFAULT_ADMIN_ROLE};
+use crate::address::Address;
+use crate::context::try_context;
+use crate::error::{ContractError, ContractResult};
+use crate::events::emit;
+use crate::storage::{Map, storage};
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Serialize};
+
+/// Role allowed to pause the contract.
+pub const GUARDIAN_ROLE: &str = "guardian";
+
+const GLOBAL_PAUSE_KEY: &str = "__pause_global";
+const FUNCTION_PAUSE_BUCKET: &str = "__pause_functions";
+
+/// Why and until when something is paused.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PauseState {
+    pub reason: String,
+    pub paused_by: Address,
+    pub paused_at: u64,
+    /// Block timestamp at which the pause lapses automatically.
+    pub until: Option<u64>,
+}
+
+/// Emitted when the contract (`function: None`) or one function is paused.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Paused {
+    pub function: Option<String>,
+    pub reason: String,
+    pub paused_by: Address,
+    pub until: Option<u64>,
+}
+
+/// Emitted when a pause is lifted explicitly. Timed-out pauses lapse silently.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unpaused {
+    pub function: Option<String>,
+    pub unpaused_by: Address,
+}
+
+fn function_pauses() -> Map<String, PauseState> {
+    Map::new(FUNCTION_PAUSE_BUCKET)
+}
+
+fn now() -> ContractResult<u64> {
+    Ok(try_context()?.block_timestamp())
+}
+
+/// Circuit breaker backed by contract storage.
+pub struct Pausable;
+
+impl Pausable {
+    /// Pause the whole contract until unpaused.
+    pub fn pause(caller: &Address, reason: &str) -> ContractResult<()> {
+        Pausable::pause_scope(caller, None, reason, None)
+    }
+
+    /// Pause the whole contract for `duration` seconds.
+    pub fn pause_for(caller: &Address, reason: &str, duration: u64) -> ContractResult<()> {
+        Pausable::pause_scope(caller, None, reason, Some(duration))
+    }
+
+    /// Lift a global pause.
+    pub fn unpause(caller: &Address) -> ContractResult<()> {
+        Pausable::unpause_scope(caller, None)
+    }
+
+    /// Pause a single function until unpaused.
+    pub fn pause_function(caller: &Address, function: &str, reason: &str) -> ContractResult<()> {
+        Pausable::pause_scope(caller, Some(function), reason, None)
+    }
+
+    /// Pause a single function for `duration` seconds.
+    pub fn pause_function_for(
+        caller: &Address,
+        function: &str,
+        reason: &str,
+        duration: u64,
+    ) -> ContractResult<()> {
+        Pausable::pause_scope(caller, Some(function), reason, Some(duration))
+    }
+
+    /// Lift a pause on a single function.
+    pub fn unpause_function(caller: &Address, function: &str) -> ContractResult<()> {
+        Pausable::unpause_scope(caller, Some(function))
+    }
+
+    /// Whether the contract is globally paused. Unreadable pause state counts
+    /// as paused.
+    pub fn is_paused() -> bool {
+        !matches!(Pausable::active_state(None), Ok(None))
+    }
+
+    /// Whether `function` is paused, either directly or by a global pause.
+    /// Unreadable pause state counts as paused.
+    pub fn is_function_paused(function: &str) -> bool {
+        Pausable::is_paused() || !matches!(Pausable::active_state(Some(function)), Ok(None))
+    }
+
+    /// The active global pause, if any.
+    pub fn pause_state() -> ContractResult<Option<PauseState>> {
+        Pausable::active_state(None)
+    }
+
+    /// The active pause on `function` itself, if any.
+    pub fn function_pause_state(function: &str) -> ContractResult<Option<PauseState>> {
+        Pausable::active_state(Some(function))
+    }
+
+    /// Fail with [`ContractError::Paused`] while the contract is paused.
+    pub fn when_not_paused() -> ContractResult<()> {
+        if Pausable::active_state(None)?.is_some() {
+            return Err(ContractError::Paused);
+        }
+        Ok(())
+    }
+
+    /// Fail with [`ContractError::Paused`] while the contract or `function` is
+    /// paused.
+    pub fn when_function_not_paused(function: &str) -> ContractResult<()> {
+        Pausable::when_not_paused()?;
+        if Pausable::active_state(Some(function))?.is_some() {
+            return Err(ContractError::Paused);
+        }
+        Ok(())
+    }
+
+    /// Fail with [`ContractError::NotPaused`] unless the contract is paused,
+    /// e.g. for emergency withdrawals.
+    pub fn when_paused() -> ContractResult<()> {
+        if Pausable::active_state(None)?.is_none() {
+            return Err(ContractError::NotPaused);
+        }
+        Ok(())
+    }
+
+    fn pause_scope(
+        caller: &Address,
+        function: Option<&str>,
+        reason: &str,
+        duration: Option<u64>,
+    ) -> ContractResult<()> {
+        if !AccessControl::has_role(caller, GUARDIAN_ROLE) {
+            AccessControl::authorize(caller, Some(DEFAULT_ADMIN_ROLE))?;
+        }
+        if Pausable::active_state(function)?.is_some() {
+            return Err(ContractError::Paused);
+        }
+
+        let paused_at = now()?;
+        let until = match duration {
+            Some(duration) => Some(
+                paused_at
+                    .checked_add(duration)
+                    .ok_or(ContractError::Overflow)?,
+            ),
+            None => None,
+        };
+        let state = PauseState {
+            reason: reason.to_string(),
+            paused_by: caller.clone(),
+            paused_at,
+            until,
+        };
+        match function {
+            Some(function) => function_pauses().set(&function.to_string(), &state)?,
+            None => storage().set(GLOBAL_PAUSE_KEY, &state)?,
+        }
+
+        emit(
+            "Paused",
+            &Paused {
+                function: function.map(ToString::to_string),
+                reason: state.reason,
+                paused_by: state.paused_by,
+                until,
+            },
+        );
+        Ok(())
+    }
+
+    fn unpause_scope(caller: &Address, function: Option<&str>) -> ContractResult<()> {
+        AccessControl::authorize(caller, Some(DEFAULT_ADMIN_ROLE))?;
+        if Pausable::active_state(function)?.is_none() {
+            return Err(ContractError::NotPaused);
+        }
+
+        match function {
+            Some(function) => function_pauses().remove(&function.to_string())?,
+            None => storage().remove(GLOBAL_PAUSE_KEY)?,
+        }
+        emit(
+            "Unpaused",
+            &Unpaused {
+                function: function.map(ToString::to_string),
+                unpaused_by: caller.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    fn active_state(function: Option<&str>) -> ContractResult<Option<PauseState>> {
+        let state = match function {
+            Some(function) => function_pauses().get(&function.to_string())?,
+            None => storage().get::<PauseState>(GLOBAL_PAUSE_KEY)?,
+        };
+        if let Some(until) = state.as_ref().and_then(|state| state.until)
+            && until <= now()?
+        {
+            return Ok(None);
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    struct Fixture {
+        owner: Address,
+        guardian: Address,
+        user: Address,
+    }
+
+    fn setup() -> Fixture {
+        let fixture = Fixture {
+            owner: Address::from_public_key(&[1; 32]),
+            guardian: Address::from_public_key(&[2; 32]),
+            user: Address::from_public_key(&[3; 32]),
+        };
+        AccessControl::initialize(&fixture.owner).expect("init");
+        AccessControl::grant_role(&fixture.owner, &fixture.guardian, GUARDIAN_ROLE)
+            .expect("grant guardian");
+        mock::take_events();
+        fixture
+    }
+
+    #[test]
+    fn global_pause_blocks_guarded_calls() {
+        let _guard = mock::test_env();
+        let f = setup();
+
+        assert!(Pausable::when_not_paused().is_ok());
+        assert!(matches!(
+            Pausable::when_paused(),
+            Err(ContractError::NotPaused)
+        ));
+        assert!(matches!(
+            Pausable::pause(&f.user, "nope"),
+            Err(ContractError::Unauthorized)
+        ));
+
+        Pausable::pause(&f.guardian, "exploit in progress").expect("pause");
+        assert!(Pausable::is_paused());
+        assert!(matches!(
+            Pausable::when_not_paused(),
+            Err(ContractError::Paused)
+        ));
+        assert!(Pausable::when_paused().is_ok());
+        assert!(Pausable::is_function_paused("stake"));
+        assert!(Pausable::pause(&f.guardian, "again").is_err());
+
+        // Guardians can halt but not resume.
+        assert!(matches!(
+            Pausable::unpause(&f.guardian),
+            Err(ContractError::Unauthorized)
+        ));
+        Pausable::unpause(&f.owner).expect("unpause");
+        assert!(!Pausable::is_paused());
+        assert!(matches!(
+            Pausable::unpause(&f.owner),
+            Err(ContractError::NotPaused)
+        ));
+
+        let topics: alloc::vec::Vec<String> =
+            mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(topics, ["Paused", "Unpaused"]);
+    }
+
+    #[test]
+    fn function_pauses_are_independent() {
+        let _guard = mock::test_env();
+        let f = setup();
+
+        Pausable::pause_function(&f.guardian, "stake", "oracle outage").expect("pause stake");
+        assert!(matches!(
+            Pausable::when_function_not_paused("stake"),
+            Err(ContractError::Paused)
+        ));
+        assert!(Pausable::when_function_not_paused("unstake").is_ok());
+        assert!(Pausable::when_not_paused().is_ok());
+
+        let state = Pausable::function_pause_state("stake").unwrap().unwrap();
+        assert_eq!(state.reason, "oracle outage");
+        assert_eq!(state.paused_by, f.guardian);
+
+        Pausable::unpause_function(&f.owner, "stake").expect("unpause stake");
+        assert!(Pausable::when_function_not_paused("stake").is_ok());
+    }
+
+    #[test]
+    fn timed_pauses_lapse() {
+        let _guard = mock::test_env();
+        let f = setup();
+        let start = 1_700_000_000;
+
+        Pausable::pause_for(&f.guardian, "cool down", 600).expect("pause");
+        assert_eq!(
+            Pausable::pause_state().unwrap().unwrap().until,
+            Some(start + 600)
+        );
+        mock::set_block_timestamp(start + 599);
+        assert!(Pausable::is_paused());
+        mock::set_block_timestamp(start + 600);
+        assert!(!Pausable::is_paused());
+        assert!(Pausable::when_not_paused().is_ok());
+
+        // A lapsed pause does not block a new one.
+        Pausable::pause_function_for(&f.owner, "stake", "again", 60).expect("pause");
+        assert!(Pausable::is_function_paused("stake"));
+    }
+
+    #[test]
+    fn unreadable_pause_state_fails_closed() {
+        let _guard = mock::test_env();
+        setup();
+
+        Map::<String, u8>::new(FUNCTION_PAUSE_BUCKET)
+            .set(&"stake".to_string(), &7)
+            .unwrap();
+        assert!(Pausable::is_function_paused("stake"));
+        assert!(!Pausable::is_function_paused("unstake"));
+        assert!(Pausable::when_function_not_paused("stake").is_err());
+
+        storage().set(GLOBAL_PAUSE_KEY, &7u8).unwrap();
+        assert!(Pausable::is_paused());
+        assert!(Pausable::is_function_paused("unstake"));
+        assert!(Pausable::when_not_paused().is_err());
+    }
+}
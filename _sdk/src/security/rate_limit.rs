@@ -0,0 +1,379 @@
+//! Rate limiting and per-period spending caps
+//!
+//! Two limiters, both keyed by any `Serialize` value (an [`Address`], a
+//! `(Address, String)` pair, ...) and persisted through [`Map`]:
+//!
+//! - [`TokenBucket`] holds up to `capacity` units and refills `refill_amount`
+//!   units every `refill_period`, allowing short bursts.
+//! - [`FixedWindow`] allows at most `limit` units per aligned window, e.g. a
+//!   daily withdrawal cap.
+//!
+//! Time is read from the block timestamp by default, or from the block height
+//! with [`Clock::BlockHeight`]. When a limit is exhausted the limiter returns
+//! [`ContractError::RateLimited`] with the number of seconds (or blocks) until
+//! the request would succeed.
+//!
+//! ```rust,ignore
+//! let mut daily = FixedWindow::new("withdraw_cap", 10_000, 86_400);
+//! daily.consume(&sender, amount)?;
+//! ```
+//!
+//! [`Address`]: crate::address::Address
+
+use super::safe_math::Amount;
+use crate::context::try_context;
+use crate::error::{ContractError, ContractResult};
+use crate::storage::Map;
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+/// Source of time for a limiter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Clock {
+    /// Seconds, from the block timestamp.
+    #[default]
+    BlockTimestamp,
+    /// Blocks, from the block height.
+    BlockHeight,
+}
+
+impl Clock {
+    /// Current reading of this clock.
+    pub fn now(self) -> ContractResult<u64> {
+        let context = try_context()?;
+        Ok(match self {
+            Clock::BlockTimestamp => context.block_timestamp(),
+            Clock::BlockHeight => context.block_height(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BucketState {
+    tokens: Amount,
+    updated_at: u64,
+}
+
+/// Token-bucket limiter: bursts up to `capacity`, then `refill_amount` per
+/// `refill_period`.
+#[derive(Clone)]
+pub struct TokenBucket<K> {
+    buckets: Map<K, BucketState>,
+    capacity: Amount,
+    refill_amount: Amount,
+    refill_period: u64,
+    clock: Clock,
+}
+
+impl<K: Serialize> TokenBucket<K> {
+    /// Bucket stored under `prefix`. New keys start full.
+    pub fn new(
+        prefix: &str,
+        capacity: impl Into<Amount>,
+        refill_amount: impl Into<Amount>,
+        refill_period: u64,
+    ) -> Self {
+        Self {
+            buckets: Map::new(prefix),
+            capacity: capacity.into(),
+            refill_amount: refill_amount.into(),
+            refill_period,
+            clock: Clock::default(),
+        }
+    }
+
+    /// Measure `refill_period` with `clock` instead of the block timestamp.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Units `key` may consume right now.
+    pub fn available(&self, key: &K) -> ContractResult<Amount> {
+        let now = self.clock.now()?;
+        Ok(self.refilled(key, now)?.tokens)
+    }
+
+    /// Succeed if `amount` could be consumed now, without recording it.
+    pub fn check(&self, key: &K, amount: impl Into<Amount>) -> ContractResult<()> {
+        let now = self.clock.now()?;
+        let state = self.refilled(key, now)?;
+        self.ensure_allowance(&state, amount.into(), now)
+    }
+
+    /// Take `amount` units from `key`'s bucket.
+    pub fn consume(&mut self, key: &K, amount: impl Into<Amount>) -> ContractResult<()> {
+        let amount = amount.into();
+        let now = self.clock.now()?;
+        let mut state = self.refilled(key, now)?;
+        self.ensure_allowance(&state, amount, now)?;
+
+        state.tokens -= amount;
+        self.buckets.set(key, &state)
+    }
+
+    /// Refill `key`'s bucket to capacity.
+    pub fn reset(&mut self, key: &K) -> ContractResult<()> {
+        self.buckets.remove(key)
+    }
+
+    fn refilled(&self, key: &K, now: u64) -> ContractResult<BucketState> {
+        self.validate()?;
+        let Some(mut state) = self.buckets.get(key)? else {
+            return Ok(BucketState {
+                tokens: self.capacity,
+                updated_at: now,
+            });
+        };
+
+        // Refill in whole periods so partial progress towards the next refill
+        // is kept rather than rounded away.
+        let periods = now.saturating_sub(state.updated_at) / self.refill_period;
+        let refill = self.refill_amount.saturating_mul(Amount::from(periods));
+        state.tokens = state.tokens.saturating_add(refill).min(self.capacity);
+        state.updated_at = if state.tokens == self.capacity {
+            now
+        } else {
+            state.updated_at + periods * self.refill_period
+        };
+        Ok(state)
+    }
+
+    fn ensure_allowance(
+        &self,
+        state: &BucketState,
+        amount: Amount,
+        now: u64,
+    ) -> ContractResult<()> {
+        if amount > self.capacity {
+            return Err(exceeds_limit());
+        }
+        if amount <= state.tokens {
+            return Ok(());
+        }
+
+        let deficit = amount - state.tokens;
+        let periods = deficit.div_ceil(self.refill_amount);
+        let wait = u64::try_from(periods)
+            .ok()
+            .and_then(|periods| periods.checked_mul(self.refill_period))
+            .ok_or(ContractError::Overflow)?;
+        let elapsed = now.saturating_sub(state.updated_at);
+        Err(ContractError::RateLimited {
+            retry_after: wait - elapsed,
+        })
+    }
+
+    fn validate(&self) -> ContractResult<()> {
+        if self.refill_period == 0 || self.refill_amount == 0 {
+            return Err(ContractError::InvalidArgument(String::from(
+                "Token bucket refill must be positive",
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct WindowState {
+    window_start: u64,
+    used: Amount,
+}
+
+/// Fixed-window limiter: at most `limit` units per window. Windows are aligned
+/// to multiples of `window`, so a daily cap over timestamps resets at 00:00 UTC.
+#[derive(Clone)]
+pub struct FixedWindow<K> {
+    windows: Map<K, WindowState>,
+    limit: Amount,
+    window: u64,
+    clock: Clock,
+}
+
+impl<K: Serialize> FixedWindow<K> {
+    /// Limiter stored under `prefix` allowing `limit` units per `window`.
+    pub fn new(prefix: &str, limit: impl Into<Amount>, window: u64) -> Self {
+        Self {
+            windows: Map::new(prefix),
+            limit: limit.into(),
+            window,
+            clock: Clock::default(),
+        }
+    }
+
+    /// Measure `window` with `clock` instead of the block timestamp.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Units `key` may still consume in the current window.
+    pub fn remaining(&self, key: &K) -> ContractResult<Amount> {
+        let now = self.clock.now()?;
+        Ok(self.limit.saturating_sub(self.current(key, now)?.used))
+    }
+
+    /// Succeed if `amount` could be consumed now, without recording it.
+    pub fn check(&self, key: &K, amount: impl Into<Amount>) -> ContractResult<()> {
+        let now = self.clock.now()?;
+        let state = self.current(key, now)?;
+        self.ensure_allowance(&state, amount.into(), now)
+    }
+
+    /// Record `amount` units against `key` in the current window.
+    pub fn consume(&mut self, key: &K, amount: impl Into<Amount>) -> ContractResult<()> {
+        let amount = amount.into();
+        let now = self.clock.now()?;
+        let mut state = self.current(key, now)?;
+        self.ensure_allowance(&state, amount, now)?;
+
+        state.used += amount;
+        self.windows.set(key, &state)
+    }
+
+    /// Forget `key`'s usage in the current window.
+    pub fn reset(&mut self, key: &K) -> ContractResult<()> {
+        self.windows.remove(key)
+    }
+
+    fn current(&self, key: &K, now: u64) -> ContractResult<WindowState> {
+        if self.window == 0 {
+            return Err(ContractError::InvalidArgument(String::from(
+                "Rate limit window must be positive",
+            )));
+        }
+        let window_start = now - now % self.window;
+        Ok(match self.windows.get(key)? {
+            Some(state) if state.window_start == window_start => state,
+            _ => WindowState {
+                window_start,
+                used: 0,
+            },
+        })
+    }
+
+    fn ensure_allowance(
+        &self,
+        state: &WindowState,
+        amount: Amount,
+        now: u64,
+    ) -> ContractResult<()> {
+        if amount > self.limit {
+            return Err(exceeds_limit());
+        }
+        // A lowered limit may already be exceeded by the stored usage.
+        if amount <= self.limit.saturating_sub(state.used) {
+            return Ok(());
+        }
+        Err(ContractError::RateLimited {
+            retry_after: state.window_start.saturating_add(self.window) - now,
+        })
+    }
+}
+
+fn exceeds_limit() -> ContractError {
+    ContractError::InvalidArgument(String::from("Amount exceeds the rate limit"))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::ffi::mock;
+
+    const START: u64 = 1_700_000_000;
+
+    #[test]
+    fn token_bucket_bursts_then_refills() {
+        let _guard = mock::test_env();
+        let alice = Address::from_public_key(&[1; 32]);
+        let bob = Address::from_public_key(&[2; 32]);
+        let mut bucket = TokenBucket::new("calls", 3u64, 1u64, 10);
+
+        for _ in 0..3 {
+            bucket.consume(&alice, 1u64).expect("burst");
+        }
+        assert!(matches!(
+            bucket.consume(&alice, 1u64),
+            Err(ContractError::RateLimited { retry_after: 10 })
+        ));
+        assert_eq!(bucket.available(&bob).unwrap(), 3);
+
+        mock::set_block_timestamp(START + 14);
+        assert_eq!(bucket.available(&alice).unwrap(), 1);
+        assert!(matches!(
+            bucket.check(&alice, 2u64),
+            Err(ContractError::RateLimited { retry_after: 6 })
+        ));
+        bucket.consume(&alice, 1u64).expect("refilled");
+
+        // Progress towards the next refill survives the consume above.
+        mock::set_block_timestamp(START + 20);
+        assert_eq!(bucket.available(&alice).unwrap(), 1);
+
+        mock::set_block_timestamp(START + 1_000);
+        assert_eq!(bucket.available(&alice).unwrap(), 3);
+        assert!(matches!(
+            bucket.consume(&alice, 4u64),
+            Err(ContractError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn fixed_window_caps_spending_per_period() {
+        let _guard = mock::test_env();
+        let treasury = Address::from_public_key(&[3; 32]);
+        let mut daily = FixedWindow::new("withdraw_cap", 1_000u64, 86_400);
+        let window_end = START - START % 86_400 + 86_400;
+
+        daily.consume(&treasury, 600u64).expect("first");
+        assert_eq!(daily.remaining(&treasury).unwrap(), 400);
+        assert!(matches!(
+            daily.consume(&treasury, 500u64),
+            Err(ContractError::RateLimited { retry_after }) if retry_after == window_end - START
+        ));
+        daily.consume(&treasury, 400u64).expect("up to cap");
+
+        mock::set_block_timestamp(window_end);
+        assert_eq!(daily.remaining(&treasury).unwrap(), 1_000);
+        daily.consume(&treasury, 1_000u64).expect("next window");
+    }
+
+    #[test]
+    fn lowered_limits_apply_to_existing_usage() {
+        let _guard = mock::test_env();
+        let treasury = Address::from_public_key(&[3; 32]);
+        let window_end = START - START % 86_400 + 86_400;
+        FixedWindow::new("withdraw_cap", 1_000u64, 86_400)
+            .consume(&treasury, 800u64)
+            .expect("under the old limit");
+
+        // Redeployed with a limit below what this window already used.
+        let mut daily = FixedWindow::new("withdraw_cap", 500u64, 86_400);
+        assert_eq!(daily.remaining(&treasury).unwrap(), 0);
+        assert!(matches!(
+            daily.consume(&treasury, 1u64),
+            Err(ContractError::RateLimited { retry_after }) if retry_after == window_end - START
+        ));
+
+        mock::set_block_timestamp(window_end);
+        assert_eq!(daily.remaining(&treasury).unwrap(), 500);
+    }
+
+    #[test]
+    fn limiters_can_count_blocks() {
+        let _guard = mock::test_env();
+        mock::set_block_height(100);
+        let key = (Address::from_public_key(&[4; 32]), String::from("mint"));
+        let mut limiter = FixedWindow::new("per_block", 1u64, 5).with_clock(Clock::BlockHeight);
+
+        limiter.consume(&key, 1u64).expect("first");
+        mock::set_block_height(103);
+        assert!(matches!(
+            limiter.check(&key, 1u64),
+            Err(ContractError::RateLimited { retry_after: 2 })
+        ));
+        mock::set_block_height(105);
+        limiter.consume(&key, 1u64).expect("next window");
+    }
+}
@@ -0,0 +1,235 @@
+//! Reentrancy protection
+//!
+//! Locks live in the contract's own storage rather than in process memory, so
+//! they are seen by every wasm instance of the same contract: a callee that
+//! calls back into the contract during a cross-contract call finds the lock
+//! held, while other contracts keep their own, independent locks. Storage
+//! writes are rolled back with the rest of the call if it traps, so a failed
+//! call never leaves the contract locked.
+//!
+//! [`ReentrancyGuard::enter`] locks the whole contract. [`ReentrancyGuard::enter_scope`]
+//! locks a single scope (usually a function name), so unrelated entry points
+//! may still be re-entered; a contract-wide lock excludes every scope.
+
+use crate::error::{ContractError, ContractResult};
+use crate::storage::storage;
+use alloc::string::{String, ToString};
+
+const CONTRACT_LOCK_KEY: &str = "__reentrancy";
+const SCOPE_LOCK_PREFIX: &str = "__reentrancy:";
+const HELD_SCOPES_KEY: &str = "__reentrancy_scopes";
+
+fn scope_key(scope: &str) -> String {
+    let mut key = String::with_capacity(SCOPE_LOCK_PREFIX.len() + scope.len());
+    key.push_str(SCOPE_LOCK_PREFIX);
+    key.push_str(scope);
+    key
+}
+
+fn held_scopes() -> ContractResult<u64> {
+    Ok(storage().get(HELD_SCOPES_KEY)?.unwrap_or(0))
+}
+
+fn set_held_scopes(count: u64) -> ContractResult<()> {
+    if count == 0 {
+        storage().remove(HELD_SCOPES_KEY)
+    } else {
+        storage().set(HELD_SCOPES_KEY, &count)
+    }
+}
+
+/// Reentrancy guard API.
+pub struct ReentrancyGuard;
+
+/// RAII guard returned by [`ReentrancyGuard::enter`] and
+/// [`ReentrancyGuard::enter_scope`]; releases its lock when dropped.
+pub struct ReentrancyGuardGuard {
+    scope: Option<String>,
+}
+
+impl ReentrancyGuard {
+    /// Lock the whole contract, returning a guard on success.
+    pub fn enter() -> ContractResult<ReentrancyGuardGuard> {
+        if storage().has(CONTRACT_LOCK_KEY) || held_scopes()? > 0 {
+            return Err(ContractError::ReentrancyDetected);
+        }
+        storage().set(CONTRACT_LOCK_KEY, &true)?;
+        Ok(ReentrancyGuardGuard { scope: None })
+    }
+
+    /// Lock `scope`, returning a guard on success.
+    pub fn enter_scope(scope: &str) -> ContractResult<ReentrancyGuardGuard> {
+        let key = scope_key(scope);
+        if storage().has(CONTRACT_LOCK_KEY) || storage().has(&key) {
+            return Err(ContractError::ReentrancyDetected);
+        }
+        let held = held_scopes()?
+            .checked_add(1)
+            .ok_or(ContractError::Overflow)?;
+        storage().set(&key, &true)?;
+        set_held_scopes(held)?;
+        Ok(ReentrancyGuardGuard {
+            scope: Some(scope.to_string()),
+        })
+    }
+
+    /// Whether the whole contract or any scope is currently locked.
+    pub fn is_entered() -> bool {
+        storage().has(CONTRACT_LOCK_KEY) || storage().has(HELD_SCOPES_KEY)
+    }
+
+    /// Whether `scope` is currently locked, directly or by a contract-wide lock.
+    pub fn is_scope_entered(scope: &str) -> bool {
+        storage().has(CONTRACT_LOCK_KEY) || storage().has(&scope_key(scope))
+    }
+
+    /// Manually release the contract-wide lock. Prefer relying on the RAII drop
+    /// implementation.
+    pub fn exit() -> ContractResult<()> {
+        storage().remove(CONTRACT_LOCK_KEY)
+    }
+
+    /// Manually release the lock on `scope`. Prefer relying on the RAII drop
+    /// implementation.
+    pub fn exit_scope(scope: &str) -> ContractResult<()> {
+        let key = scope_key(scope);
+        if !storage().has(&key) {
+            return Ok(());
+        }
+        storage().remove(&key)?;
+        set_held_scopes(held_scopes()?.saturating_sub(1))
+    }
+
+    /// Execute a closure with the whole contract locked.
+    pub fn execute<F, R>(f: F) -> ContractResult<R>
+    where
+        F: FnOnce() -> ContractResult<R>,
+    {
+        let guard = Self::enter()?;
+        let result = f();
+        guard.release()?;
+        result
+    }
+
+    /// Execute a closure with `scope` locked.
+    pub fn execute_scoped<F, R>(scope: &str, f: F) -> ContractResult<R>
+    where
+        F: FnOnce() -> ContractResult<R>,
+    {
+        let guard = Self::enter_scope(scope)?;
+        let result = f();
+        guard.release()?;
+        result
+    }
+}
+
+impl ReentrancyGuardGuard {
+    /// Release the lock, surfacing storage errors that `drop` has to ignore.
+    pub fn release(mut self) -> ContractResult<()> {
+        let scope = self.scope.take();
+        core::mem::forget(self);
+        match scope {
+            Some(scope) => ReentrancyGuard::exit_scope(&scope),
+            None => ReentrancyGuard::exit(),
+        }
+    }
+}
+
+impl Drop for ReentrancyGuardGuard {
+    fn drop(&mut self) {
+        let _ = match &self.scope {
+            Some(scope) => ReentrancyGuard::exit_scope(scope),
+            None => ReentrancyGuard::exit(),
+        };
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::context::try_context;
+    use crate::ffi::mock;
+
+    /// A vault that pays out before updating its books, guarded by `scope`.
+    fn withdraw(scope: Option<&str>, attacker: &Address, depth: u32) -> ContractResult<u32> {
+        let body = || {
+            let vault = try_context()?.contract_address().clone();
+            // The payout hands control to the attacker contract, which
+            // immediately calls back into the vault.
+            mock::invoke(attacker.as_str(), vault.as_str(), || {
+                if depth == 0 {
+                    return Ok(0);
+                }
+                let sender = try_context()?.contract_address().clone();
+                mock::invoke(vault.as_str(), sender.as_str(), || {
+                    withdraw(scope, attacker, depth - 1)
+                })
+                .map(|reentries| reentries + 1)
+            })
+        };
+        match scope {
+            Some(scope) => ReentrancyGuard::execute_scoped(scope, body),
+            None => ReentrancyGuard::execute(body),
+        }
+    }
+
+    #[test]
+    fn malicious_contract_cannot_reenter() {
+        let _guard = mock::test_env();
+        let attacker = Address::contract([0xba; 32]);
+
+        assert!(matches!(
+            withdraw(None, &attacker, 1),
+            Err(ContractError::ReentrancyDetected)
+        ));
+        assert!(matches!(
+            withdraw(Some("withdraw"), &attacker, 1),
+            Err(ContractError::ReentrancyDetected)
+        ));
+
+        // The failed attempts left nothing locked behind.
+        assert!(!ReentrancyGuard::is_entered());
+        assert_eq!(withdraw(None, &attacker, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn locks_are_per_contract() {
+        let _guard = mock::test_env();
+        let other = Address::contract([0x0e; 32]);
+
+        let guard = ReentrancyGuard::enter().expect("lock vault");
+        mock::invoke(other.as_str(), mock::test_contract().as_str(), || {
+            assert!(!ReentrancyGuard::is_entered());
+            ReentrancyGuard::execute(|| Ok(())).expect("other contract is unlocked");
+        });
+        assert!(ReentrancyGuard::enter().is_err());
+        drop(guard);
+
+        let guard = ReentrancyGuard::enter().expect("re-entry after drop");
+        guard.release().expect("release");
+    }
+
+    #[test]
+    fn scopes_lock_independently() {
+        let _guard = mock::test_env();
+
+        let stake = ReentrancyGuard::enter_scope("stake").expect("stake");
+        assert!(ReentrancyGuard::is_scope_entered("stake"));
+        assert!(ReentrancyGuard::enter_scope("stake").is_err());
+        let claim = ReentrancyGuard::enter_scope("claim").expect("claim");
+        assert!(matches!(
+            ReentrancyGuard::enter(),
+            Err(ContractError::ReentrancyDetected)
+        ));
+
+        drop(stake);
+        assert!(ReentrancyGuard::enter().is_err());
+        drop(claim);
+        assert!(!ReentrancyGuard::is_entered());
+
+        let all = ReentrancyGuard::enter().expect("contract lock");
+        assert!(ReentrancyGuard::enter_scope("stake").is_err());
+        drop(all);
+    }
+}
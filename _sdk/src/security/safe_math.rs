@@ -0,0 +1,625 @@
+//! Safe arithmetic helpers with overflow checking
+//!
+//! Besides the basic checked operations on `u64` and [`Amount`] values this
+//! module provides the
+//! building blocks for pricing and accounting code: `a * b / c` with a
+//! double-width intermediate, integer square roots, basis-point helpers and
+//! the [`Fixed`] Q64.64 type. Every operation that can lose precision takes an
+//! explicit [`Rounding`] mode, so contracts decide which side absorbs the dust.
+
+use crate::error::{ContractError, ContractResult};
+use serde::{Deserialize, Serialize};
+
+/// Basis points in one whole (100%).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Token amount, wide enough for 18-decimal assets.
+///
+/// `u64` values convert losslessly with `Amount::from`, so APIs taking
+/// `impl Into<Amount>` accept both widths.
+pub type Amount = u128;
+
+const LOW_MASK: u128 = u64::MAX as u128;
+
+/// How to round a result that cannot be represented exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero.
+    Floor,
+    /// Away from zero.
+    Ceil,
+    /// To the nearest value, with ties rounded up.
+    Nearest,
+}
+
+mod sealed {
+    pub trait Sealed: Copy + Ord {
+        fn checked_add(self, other: Self) -> Option<Self>;
+        fn checked_sub(self, other: Self) -> Option<Self>;
+        fn checked_mul(self, other: Self) -> Option<Self>;
+        fn checked_div(self, other: Self) -> Option<Self>;
+        fn checked_pow(self, exp: u32) -> Option<Self>;
+    }
+
+    macro_rules! impl_sealed {
+        ($($ty:ty),*) => {$(
+            impl Sealed for $ty {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, other)
+                }
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_sub(self, other)
+                }
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_mul(self, other)
+                }
+                fn checked_div(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_div(self, other)
+                }
+                fn checked_pow(self, exp: u32) -> Option<Self> {
+                    <$ty>::checked_pow(self, exp)
+                }
+            }
+        )*};
+    }
+
+    impl_sealed!(u64, u128);
+}
+
+/// Unsigned integer widths supported by the checked helpers: `u64` and
+/// [`Amount`].
+pub trait Unsigned: sealed::Sealed {}
+
+impl Unsigned for u64 {}
+impl Unsigned for u128 {}
+
+#[inline(always)]
+pub fn add<T: Unsigned>(a: T, b: T) -> ContractResult<T> {
+    match a.checked_add(b) {
+        Some(result) => Ok(result),
+        None => Err(ContractError::Overflow),
+    }
+}
+
+#[inline(always)]
+pub fn sub<T: Unsigned>(a: T, b: T) -> ContractResult<T> {
+    match a.checked_sub(b) {
+        Some(result) => Ok(result),
+        None => Err(ContractError::Underflow),
+    }
+}
+
+#[inline(always)]
+pub fn mul<T: Unsigned>(a: T, b: T) -> ContractResult<T> {
+    match a.checked_mul(b) {
+        Some(result) => Ok(result),
+        None => Err(ContractError::Overflow),
+    }
+}
+
+#[inline(always)]
+pub fn div<T: Unsigned>(a: T, b: T) -> ContractResult<T> {
+    a.checked_div(b).ok_or_else(division_by_zero)
+}
+
+#[inline(always)]
+pub fn pow<T: Unsigned>(base: T, exp: u32) -> ContractResult<T> {
+    base.checked_pow(exp).ok_or(ContractError::Overflow)
+}
+
+/// Narrow an [`Amount`] to `u64`, failing with [`ContractError::Overflow`].
+#[inline(always)]
+pub fn to_u64(amount: Amount) -> ContractResult<u64> {
+    u64::try_from(amount).map_err(|_| ContractError::Overflow)
+}
+
+#[inline(always)]
+pub const fn saturating_add(a: u64, b: u64) -> u64 {
+    a.saturating_add(b)
+}
+
+#[inline(always)]
+pub const fn saturating_sub(a: u64, b: u64) -> u64 {
+    a.saturating_sub(b)
+}
+
+/// Compute `a * b / c` without intermediate overflow.
+///
+/// Fails with [`ContractError::Overflow`] only when the rounded result does
+/// not fit in a `u64`.
+pub fn mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> ContractResult<u64> {
+    if c == 0 {
+        return Err(division_by_zero());
+    }
+    let product = a as u128 * b as u128;
+    let divisor = c as u128;
+    let quotient = round_quotient(product / divisor, product % divisor, divisor, rounding)?;
+    u64::try_from(quotient).map_err(|_| ContractError::Overflow)
+}
+
+/// `a * b / c`, rounded down.
+#[inline(always)]
+pub fn mul_div_floor(a: u64, b: u64, c: u64) -> ContractResult<u64> {
+    mul_div(a, b, c, Rounding::Floor)
+}
+
+/// `a * b / c`, rounded up.
+#[inline(always)]
+pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> ContractResult<u64> {
+    mul_div(a, b, c, Rounding::Ceil)
+}
+
+/// Compute `a * b / c` for `u128` operands using a 256-bit intermediate.
+pub fn mul_div_u128(a: u128, b: u128, c: u128, rounding: Rounding) -> ContractResult<u128> {
+    if c == 0 {
+        return Err(division_by_zero());
+    }
+    let (high, low) = widening_mul(a, b);
+    if high >= c {
+        // The quotient needs more than 128 bits.
+        return Err(ContractError::Overflow);
+    }
+    let (quotient, remainder) = div_wide(high, low, c);
+    round_quotient(quotient, remainder, c, rounding)
+}
+
+/// Integer square root, rounded down.
+#[inline(always)]
+pub const fn isqrt(value: u64) -> u64 {
+    value.isqrt()
+}
+
+/// Integer square root of a `u128`, rounded down.
+///
+/// Useful for geometric means such as `sqrt(reserve_a * reserve_b)`, whose
+/// result always fits in a `u64`.
+#[inline(always)]
+pub const fn isqrt_u128(value: u128) -> u128 {
+    value.isqrt()
+}
+
+/// Apply a basis-point rate to `amount` (`amount * bps / 10_000`).
+///
+/// Rates above [`BPS_DENOMINATOR`] are allowed and scale the amount up.
+pub fn apply_bps(amount: u64, bps: u64, rounding: Rounding) -> ContractResult<u64> {
+    mul_div(amount, bps, BPS_DENOMINATOR, rounding)
+}
+
+/// Apply a basis-point rate to an [`Amount`].
+pub fn apply_bps_u128(amount: Amount, bps: u64, rounding: Rounding) -> ContractResult<Amount> {
+    mul_div_u128(amount, bps as u128, BPS_DENOMINATOR as u128, rounding)
+}
+
+/// Express `part` as basis points of `whole`.
+pub fn to_bps(part: u64, whole: u64, rounding: Rounding) -> ContractResult<u64> {
+    mul_div(part, BPS_DENOMINATOR, whole, rounding)
+}
+
+/// Check that `bps` is at most 100%.
+pub fn validate_bps(bps: u64) -> ContractResult<()> {
+    if bps > BPS_DENOMINATOR {
+        return Err(super::invalid_argument("Basis points cannot exceed 10000"));
+    }
+    Ok(())
+}
+
+/// Unsigned Q64.64 fixed-point number.
+///
+/// The upper 64 bits hold the integer part and the lower 64 bits the
+/// fraction. Arithmetic is checked, and multiplication and division take an
+/// explicit [`Rounding`] mode.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Fixed(u128);
+
+impl Fixed {
+    /// Number of fractional bits.
+    pub const FRACTIONAL_BITS: u32 = 64;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRACTIONAL_BITS);
+    pub const MAX: Fixed = Fixed(u128::MAX);
+
+    /// Wrap a raw Q64.64 bit pattern.
+    #[inline(always)]
+    pub const fn from_bits(bits: u128) -> Self {
+        Fixed(bits)
+    }
+
+    /// Raw Q64.64 bit pattern.
+    #[inline(always)]
+    pub const fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    /// Exact fixed-point representation of an integer.
+    #[inline(always)]
+    pub const fn from_int(value: u64) -> Self {
+        Fixed((value as u128) << Self::FRACTIONAL_BITS)
+    }
+
+    /// `numerator / denominator` as a fixed-point number.
+    pub fn from_ratio(
+        numerator: u64,
+        denominator: u64,
+        rounding: Rounding,
+    ) -> ContractResult<Self> {
+        mul_div_u128(
+            numerator as u128,
+            Self::ONE.0,
+            denominator as u128,
+            rounding,
+        )
+        .map(Fixed)
+    }
+
+    /// `bps / 10_000` as a fixed-point number.
+    pub fn from_bps(bps: u64, rounding: Rounding) -> ContractResult<Self> {
+        Self::from_ratio(bps, BPS_DENOMINATOR, rounding)
+    }
+
+    pub fn checked_add(self, other: Fixed) -> ContractResult<Self> {
+        self.0
+            .checked_add(other.0)
+            .map(Fixed)
+            .ok_or(ContractError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Fixed) -> ContractResult<Self> {
+        self.0
+            .checked_sub(other.0)
+            .map(Fixed)
+            .ok_or(ContractError::Underflow)
+    }
+
+    pub fn checked_mul(self, other: Fixed, rounding: Rounding) -> ContractResult<Self> {
+        mul_div_u128(self.0, other.0, Self::ONE.0, rounding).map(Fixed)
+    }
+
+    pub fn checked_div(self, other: Fixed, rounding: Rounding) -> ContractResult<Self> {
+        mul_div_u128(self.0, Self::ONE.0, other.0, rounding).map(Fixed)
+    }
+
+    /// Scale an integer amount by this value, e.g. shares by an exchange rate.
+    pub fn mul_int(self, amount: u64, rounding: Rounding) -> ContractResult<u64> {
+        let scaled = mul_div_u128(self.0, amount as u128, Self::ONE.0, rounding)?;
+        u64::try_from(scaled).map_err(|_| ContractError::Overflow)
+    }
+
+    /// Scale an [`Amount`] by this value.
+    pub fn mul_amount(self, amount: Amount, rounding: Rounding) -> ContractResult<Amount> {
+        let (high, low) = widening_mul(self.0, amount);
+        // Shifting out the fractional bits leaves `high:low >> 64`.
+        if high >> Self::FRACTIONAL_BITS != 0 {
+            return Err(ContractError::Overflow);
+        }
+        round_quotient(
+            (high << Self::FRACTIONAL_BITS) | (low >> Self::FRACTIONAL_BITS),
+            low & LOW_MASK,
+            Self::ONE.0,
+            rounding,
+        )
+    }
+
+    /// Round to an integer.
+    pub fn to_int(self, rounding: Rounding) -> ContractResult<u64> {
+        let integer = round_quotient(
+            self.0 >> Self::FRACTIONAL_BITS,
+            self.0 & LOW_MASK,
+            Self::ONE.0,
+            rounding,
+        )?;
+        u64::try_from(integer).map_err(|_| ContractError::Overflow)
+    }
+}
+
+fn division_by_zero() -> ContractError {
+    super::invalid_argument("Division by zero")
+}
+
+fn round_quotient(
+    quotient: u128,
+    remainder: u128,
+    divisor: u128,
+    rounding: Rounding,
+) -> ContractResult<u128> {
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => remainder != 0,
+        // `remainder >= divisor / 2` without overflowing `2 * remainder`.
+        Rounding::Nearest => remainder != 0 && remainder >= divisor - remainder,
+    };
+    if round_up {
+        quotient.checked_add(1).ok_or(ContractError::Overflow)
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// Full 256-bit product of two `u128`s as `(high, low)` halves.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    let (a_high, a_low) = (a >> 64, a & LOW_MASK);
+    let (b_high, b_low) = (b >> 64, b & LOW_MASK);
+
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (low_high & LOW_MASK) + (high_low & LOW_MASK);
+    let low = (low_low & LOW_MASK) | (middle << 64);
+    let high = high_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Divide the 256-bit value `(high, low)` by `divisor`, returning the quotient
+/// and remainder. Requires `high < divisor` so the quotient fits in 128 bits.
+fn div_wide(high: u128, low: u128, divisor: u128) -> (u128, u128) {
+    if high == 0 {
+        return (low / divisor, low % divisor);
+    }
+
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MODES: [Rounding; 3] = [Rounding::Floor, Rounding::Ceil, Rounding::Nearest];
+
+    fn reference(a: u128, b: u128, c: u128, rounding: Rounding) -> u128 {
+        let (quotient, remainder) = (a * b / c, a * b % c);
+        match rounding {
+            Rounding::Floor => quotient,
+            Rounding::Ceil => quotient + (remainder != 0) as u128,
+            Rounding::Nearest => quotient + (2 * remainder >= c && remainder != 0) as u128,
+        }
+    }
+
+    /// `a * b` compared with `q * c` as 256-bit values.
+    fn product_cmp(a: u128, b: u128, q: u128, c: u128) -> core::cmp::Ordering {
+        widening_mul(a, b).cmp(&widening_mul(q, c))
+    }
+
+    #[test]
+    fn mul_div_matches_reference_on_small_domain() {
+        for a in 0..=48u64 {
+            for b in 0..=48u64 {
+                for c in 1..=48u64 {
+                    for mode in MODES {
+                        let expected = reference(a as u128, b as u128, c as u128, mode);
+                        assert_eq!(mul_div(a, b, c, mode).unwrap() as u128, expected);
+                        assert_eq!(
+                            mul_div_u128(a as u128, b as u128, c as u128, mode).unwrap(),
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mul_div_edge_cases() {
+        assert!(mul_div(1, 1, 0, Rounding::Floor).is_err());
+        assert!(mul_div_u128(1, 1, 0, Rounding::Floor).is_err());
+        assert_eq!(
+            mul_div_floor(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+            u64::MAX
+        );
+        assert!(matches!(
+            mul_div_floor(u64::MAX, 2, 1),
+            Err(ContractError::Overflow)
+        ));
+        assert!(matches!(
+            mul_div_ceil(u64::MAX, 3, 3).map(|v| v == u64::MAX),
+            Ok(true)
+        ));
+        assert_eq!(
+            mul_div_u128(u128::MAX, u128::MAX, u128::MAX, Rounding::Ceil).unwrap(),
+            u128::MAX
+        );
+        assert!(matches!(
+            mul_div_u128(u128::MAX, 2, 1, Rounding::Floor),
+            Err(ContractError::Overflow)
+        ));
+        // A quotient of exactly u128::MAX with a remainder cannot round up.
+        assert!(matches!(
+            mul_div_u128(u128::MAX, 2, 2, Rounding::Ceil).map(|v| v == u128::MAX),
+            Ok(true)
+        ));
+        assert!(mul_div_u128(u128::MAX, 3, 2, Rounding::Floor).is_err());
+    }
+
+    #[test]
+    fn bps_helpers() {
+        assert_eq!(apply_bps(1_000, 30, Rounding::Floor).unwrap(), 3);
+        assert_eq!(apply_bps(999, 30, Rounding::Floor).unwrap(), 2);
+        assert_eq!(apply_bps(999, 30, Rounding::Ceil).unwrap(), 3);
+        assert_eq!(apply_bps(200, 15_000, Rounding::Floor).unwrap(), 300);
+        assert_eq!(to_bps(1, 3, Rounding::Floor).unwrap(), 3_333);
+        assert_eq!(to_bps(2, 3, Rounding::Nearest).unwrap(), 6_667);
+        assert!(to_bps(1, 0, Rounding::Floor).is_err());
+        assert!(validate_bps(BPS_DENOMINATOR).is_ok());
+        assert!(validate_bps(BPS_DENOMINATOR + 1).is_err());
+    }
+
+    #[test]
+    fn fixed_basics() {
+        let half = Fixed::from_ratio(1, 2, Rounding::Floor).unwrap();
+        assert_eq!(half.checked_add(half).unwrap(), Fixed::ONE);
+        assert_eq!(
+            Fixed::from_int(3)
+                .checked_mul(half, Rounding::Floor)
+                .unwrap()
+                .to_int(Rounding::Nearest)
+                .unwrap(),
+            2
+        );
+        assert_eq!(half.to_int(Rounding::Floor).unwrap(), 0);
+        assert_eq!(half.to_int(Rounding::Ceil).unwrap(), 1);
+        assert!(Fixed::ZERO.checked_sub(Fixed::ONE).is_err());
+        assert!(
+            Fixed::ONE
+                .checked_div(Fixed::ZERO, Rounding::Floor)
+                .is_err()
+        );
+        assert!(Fixed::MAX.checked_add(Fixed::from_bits(1)).is_err());
+        assert!(Fixed::MAX.to_int(Rounding::Ceil).is_err());
+        assert_eq!(Fixed::MAX.to_int(Rounding::Floor).unwrap(), u64::MAX);
+
+        let fee = Fixed::from_bps(25, Rounding::Floor).unwrap();
+        assert_eq!(fee.mul_int(10_000, Rounding::Ceil).unwrap(), 25);
+    }
+
+    #[test]
+    fn checked_ops_cover_amounts() {
+        let wei: Amount = 10u128.pow(18);
+        assert_eq!(mul(1_000_000, wei).unwrap(), 10u128.pow(24));
+        assert_eq!(add(u64::MAX as Amount, 1).unwrap(), 1u128 << 64);
+        assert!(matches!(sub(0 as Amount, 1), Err(ContractError::Underflow)));
+        assert!(matches!(
+            pow(10 as Amount, 39),
+            Err(ContractError::Overflow)
+        ));
+        assert!(div(wei, 0).is_err());
+
+        assert_eq!(to_u64(u64::MAX as Amount).unwrap(), u64::MAX);
+        assert!(matches!(to_u64(1u128 << 64), Err(ContractError::Overflow)));
+
+        assert_eq!(
+            apply_bps_u128(wei, 30, Rounding::Floor).unwrap(),
+            3 * 10u128.pow(15)
+        );
+        let rate = Fixed::from_ratio(3, 2, Rounding::Floor).unwrap();
+        assert_eq!(
+            rate.mul_amount(wei, Rounding::Floor).unwrap(),
+            15 * 10u128.pow(17)
+        );
+        assert!(
+            Fixed::from_int(2)
+                .mul_amount(u128::MAX, Rounding::Floor)
+                .is_err()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn fixed_mul_amount_matches_mul_int(bits: u128, amount: u64) {
+            let value = Fixed::from_bits(bits);
+            for mode in MODES {
+                let narrow = value.mul_int(amount, mode);
+                let wide = value.mul_amount(amount as Amount, mode);
+                match (narrow, wide) {
+                    (Ok(narrow), Ok(wide)) => prop_assert_eq!(narrow as Amount, wide),
+                    (Err(_), Ok(wide)) => prop_assert!(wide > u64::MAX as Amount),
+                    (_, Err(_)) => prop_assert!(false, "u64 amounts never overflow an Amount"),
+                }
+            }
+        }
+
+        #[test]
+        fn mul_div_u64_matches_u128(a: u64, b: u64, c in 1..=u64::MAX) {
+            for mode in MODES {
+                let expected = reference(a as u128, b as u128, c as u128, mode);
+                match mul_div(a, b, c, mode) {
+                    Ok(value) => prop_assert_eq!(value as u128, expected),
+                    Err(err) => {
+                        prop_assert!(matches!(err, ContractError::Overflow));
+                        prop_assert!(expected > u64::MAX as u128);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn mul_div_u128_brackets_exact_quotient(a: u128, b: u128, c in 1..=u128::MAX) {
+            use core::cmp::Ordering::*;
+
+            let floor = match mul_div_u128(a, b, c, Rounding::Floor) {
+                Ok(floor) => floor,
+                Err(_) => {
+                    // Overflow means the quotient needs more than 128 bits.
+                    prop_assert!(widening_mul(a, b).0 >= c);
+                    return Ok(());
+                }
+            };
+            prop_assert_ne!(product_cmp(a, b, floor, c), Less);
+            if let Some(next) = floor.checked_add(1) {
+                prop_assert_eq!(product_cmp(a, b, next, c), Less);
+            }
+
+            let exact = product_cmp(a, b, floor, c) == Equal;
+            match mul_div_u128(a, b, c, Rounding::Ceil) {
+                Ok(ceil) => prop_assert_eq!(ceil, floor + (!exact) as u128),
+                Err(_) => prop_assert!(!exact && floor == u128::MAX),
+            }
+            if let Ok(nearest) = mul_div_u128(a, b, c, Rounding::Nearest) {
+                prop_assert!(nearest == floor || (!exact && nearest == floor + 1));
+            }
+        }
+
+        #[test]
+        fn mul_div_rounding_is_ordered(a: u64, b: u64, c in 1..=u64::MAX) {
+            if let (Ok(floor), Ok(nearest), Ok(ceil)) = (
+                mul_div(a, b, c, Rounding::Floor),
+                mul_div(a, b, c, Rounding::Nearest),
+                mul_div(a, b, c, Rounding::Ceil),
+            ) {
+                prop_assert!(floor <= nearest && nearest <= ceil);
+                prop_assert!(ceil - floor <= 1);
+            }
+        }
+
+        #[test]
+        fn isqrt_is_floor_sqrt(value: u64, wide: u128) {
+            let root = isqrt(value) as u128;
+            prop_assert!(root * root <= value as u128);
+            prop_assert!((root + 1) * (root + 1) > value as u128);
+
+            let root = isqrt_u128(wide);
+            prop_assert!(root * root <= wide);
+            prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|next| next > wide));
+        }
+
+        #[test]
+        fn fixed_integers_roundtrip(a: u32, b: u32) {
+            let (fa, fb) = (Fixed::from_int(a as u64), Fixed::from_int(b as u64));
+            let product = fa.checked_mul(fb, Rounding::Floor).unwrap();
+            prop_assert_eq!(product, Fixed::from_int(a as u64 * b as u64));
+            prop_assert_eq!(fa.checked_add(fb).unwrap().checked_sub(fb).unwrap(), fa);
+            if b != 0 {
+                let quotient = product.checked_div(fb, Rounding::Floor).unwrap();
+                prop_assert_eq!(quotient, fa);
+            }
+        }
+
+        #[test]
+        fn fixed_ratio_rounds_in_requested_direction(numerator: u64, denominator in 1..=u64::MAX) {
+            let floor = Fixed::from_ratio(numerator, denominator, Rounding::Floor).unwrap();
+            let ceil = Fixed::from_ratio(numerator, denominator, Rounding::Ceil).unwrap();
+            prop_assert!(ceil.to_bits() - floor.to_bits() <= 1);
+
+            prop_assert!(floor.mul_int(denominator, Rounding::Floor).unwrap() <= numerator);
+            // Rounding up past u64::MAX is the only way this can fail.
+            prop_assert!(
+                ceil.mul_int(denominator, Rounding::Ceil)
+                    .map_or(true, |value| value >= numerator)
+            );
+            prop_assert_eq!(floor.to_int(Rounding::Floor).unwrap(), numerator / denominator);
+        }
+    }
+}
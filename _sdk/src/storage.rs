@@ -1,31 +1,35 @@
 //! Storage abstractions for smart contracts (memory pool optimized)
 
-use crate::context::try_context;
+use crate::codec::{Decode, Encode, Postcard};
+use crate::context::with_context;
 use crate::error::{ContractError, ContractResult};
 use crate::ffi;
 use alloc::string::{String, ToString};
 use core::marker::PhantomData;
 use core::str;
 use itoa::Buffer;
-use serde::{Deserialize, Serialize};
 
-/// Low-level storage access
-pub struct Storage;
+/// Low-level storage access, encoding values with codec `C`
+pub struct Storage<C = Postcard> {
+    _codec: PhantomData<C>,
+}
+
+impl<C> Storage<C> {
+    /// Storage access using codec `C`; [`storage`] uses the default codec.
+    pub fn new() -> Self {
+        Self {
+            _codec: PhantomData,
+        }
+    }
 
-impl Storage {
     /// Get a value from storage (optimized - single context call)
     pub fn get<T>(&self, key: &str) -> ContractResult<Option<T>>
     where
-        T: for<'de> Deserialize<'de>,
+        C: Decode<T>,
     {
-        let ctx = try_context()?;
-        match ffi::read_storage(ctx.contract_address(), key) {
+        match with_context(|ctx| ffi::read_storage(ctx.contract_address(), key))? {
             Ok(data) if data.is_empty() => Ok(None),
-            Ok(data) => {
-                let value = postcard::from_bytes(&data)
-                    .map_err(|_| ContractError::DeserializationFailed)?;
-                Ok(Some(value))
-            }
+            Ok(data) => Ok(Some(C::decode(&data)?)),
             Err(ContractError::StorageReadFailed) => Ok(None),
             Err(e) => Err(e),
         }
@@ -34,49 +38,51 @@ impl Storage {
     /// Set a value in storage (optimized - single context call)
     pub fn set<T>(&mut self, key: &str, value: &T) -> ContractResult<()>
     where
-        T: Serialize,
+        T: ?Sized,
+        C: Encode<T>,
     {
-        let ctx = try_context()?;
-        let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+        let data = C::encode(value)?;
 
-        ffi::write_storage(ctx.contract_address(), key, &data)
+        with_context(|ctx| ffi::write_storage(ctx.contract_address(), key, &data))?
     }
 
     /// Remove a value from storage (optimized - single context call)
     pub fn remove(&mut self, key: &str) -> ContractResult<()> {
-        let ctx = try_context()?;
-        ffi::write_storage(ctx.contract_address(), key, &[])
+        with_context(|ctx| ffi::write_storage(ctx.contract_address(), key, &[]))?
     }
 
     /// Check if a key exists (optimized - single context call)
     pub fn has(&self, key: &str) -> bool {
-        let ctx = match try_context() {
-            Ok(ctx) => ctx,
-            Err(_) => return false,
-        };
-        match ffi::read_storage(ctx.contract_address(), key) {
-            Ok(data) => !data.is_empty(),
-            Err(_) => false,
+        match with_context(|ctx| ffi::read_storage(ctx.contract_address(), key)) {
+            Ok(Ok(data)) => !data.is_empty(),
+            _ => false,
         }
     }
 }
 
+impl<C> Default for Storage<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Global storage instance
 pub fn storage() -> Storage {
-    Storage
+    Storage::new()
 }
 
 /// Type-safe key-value map in storage (optimized for minimal allocations)
+///
+/// Keys and values are encoded with codec `C`.
 #[derive(Clone)]
-pub struct Map<K, V> {
+pub struct Map<K, V, C = Postcard> {
     prefix: String,
-    _phantom: PhantomData<(K, V)>,
+    _phantom: PhantomData<(K, V, C)>,
 }
 
-impl<K, V> Map<K, V>
+impl<K, V, C> Map<K, V, C>
 where
-    K: Serialize,
-    V: Serialize + for<'de> Deserialize<'de>,
+    C: Encode<K> + Encode<V> + Decode<V>,
 {
     /// Create a new map with a unique prefix
     pub fn new(prefix: &str) -> Self {
@@ -88,8 +94,7 @@ where
 
     /// Generate storage key for a map entry (memory pool optimized)
     fn storage_key(&self, key: &K) -> ContractResult<String> {
-        let key_bytes =
-            postcard::to_allocvec(key).map_err(|_| ContractError::SerializationFailed)?;
+        let key_bytes = <C as Encode<K>>::encode(key)?;
         let key_hash = blake3::hash(&key_bytes);
 
         let mut storage_key = String::with_capacity(self.prefix.len() + 1 + 64);
@@ -108,38 +113,40 @@ where
     /// Get a value from the map (memory pool optimized)
     pub fn get(&self, key: &K) -> ContractResult<Option<V>> {
         let storage_key = self.storage_key(key)?;
-        storage().get(&storage_key)
+        Storage::<C>::new().get(&storage_key)
     }
 
     /// Set a value in the map (memory pool optimized)
     pub fn set(&mut self, key: &K, value: &V) -> ContractResult<()> {
         let storage_key = self.storage_key(key)?;
-        storage().set(&storage_key, value)
+        Storage::<C>::new().set(&storage_key, value)
     }
 
     /// Remove a value from the map (memory pool optimized)
     pub fn remove(&mut self, key: &K) -> ContractResult<()> {
         let storage_key = self.storage_key(key)?;
-        storage().remove(&storage_key)
+        Storage::<C>::new().remove(&storage_key)
     }
 
     /// Check if a key exists (memory pool optimized)
     pub fn contains_key(&self, key: &K) -> ContractResult<bool> {
         let storage_key = self.storage_key(key)?;
-        Ok(storage().has(&storage_key))
+        Ok(Storage::<C>::new().has(&storage_key))
     }
 }
 
 /// Type-safe vector in storage (optimized for sequential access)
+///
+/// Elements and the length are encoded with codec `C`.
 #[derive(Clone)]
-pub struct Vector<T> {
+pub struct Vector<T, C = Postcard> {
     prefix: String,
-    _phantom: PhantomData<T>,
+    _phantom: PhantomData<(T, C)>,
 }
 
-impl<T> Vector<T>
+impl<T, C> Vector<T, C>
 where
-    T: Serialize + for<'de> Deserialize<'de>,
+    C: Encode<T> + Decode<T> + Encode<u64> + Decode<u64>,
 {
     /// Create a new vector with a unique prefix
     pub fn new(prefix: &str) -> Self {
@@ -152,13 +159,13 @@ where
     /// Get the length of the vector (cached for performance)
     pub fn len(&self) -> ContractResult<u64> {
         let len_key = self.len_key();
-        Ok(storage().get::<u64>(&len_key)?.unwrap_or(0))
+        Ok(Storage::<C>::new().get::<u64>(&len_key)?.unwrap_or(0))
     }
 
     /// Check if vector is empty (optimized inline)
     pub fn is_empty(&self) -> ContractResult<bool> {
         let len_key = self.len_key();
-        Ok(storage().get::<u64>(&len_key)?.unwrap_or(0) == 0)
+        Ok(Storage::<C>::new().get::<u64>(&len_key)?.unwrap_or(0) == 0)
     }
 
     /// Get an element at index (bounds checked)
@@ -169,7 +176,7 @@ where
         }
 
         let item_key = self.item_key(index);
-        storage().get(&item_key)
+        Storage::<C>::new().get(&item_key)
     }
 
     /// Set an element at index (bounds checked)
@@ -182,17 +189,17 @@ where
         }
 
         let item_key = self.item_key(index);
-        storage().set(&item_key, value)
+        Storage::<C>::new().set(&item_key, value)
     }
 
     /// Push an element to the end (optimized - single length read)
     pub fn push(&mut self, value: &T) -> ContractResult<()> {
         let len = self.len()?;
         let item_key = self.item_key(len);
-        storage().set(&item_key, value)?;
+        Storage::<C>::new().set(&item_key, value)?;
 
         let len_key = self.len_key();
-        storage().set(&len_key, &(len + 1))
+        Storage::<C>::new().set(&len_key, &(len + 1))
     }
 
     /// Pop an element from the end (optimized - single length read)
@@ -203,11 +210,11 @@ where
         }
 
         let item_key = self.item_key(len - 1);
-        let value = storage().get(&item_key)?;
-        storage().remove(&item_key)?;
+        let value = Storage::<C>::new().get(&item_key)?;
+        Storage::<C>::new().remove(&item_key)?;
 
         let len_key = self.len_key();
-        storage().set(&len_key, &(len - 1))?;
+        Storage::<C>::new().set(&len_key, &(len - 1))?;
 
         Ok(value)
     }
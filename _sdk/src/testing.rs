@@ -0,0 +1,195 @@
+//! Deterministic test accounts (enabled with the `testing` feature)
+//!
+//! A [`Keyring`] derives named accounts ("alice", "bob", ...) from a seed, so
+//! tests can sign messages and typed payloads without ever shipping secret key
+//! material in contract builds.
+
+use crate::address::Address;
+use crate::crypto::typed_data::{Domain, TypedData, typed_data_hash};
+use crate::error::ContractResult;
+use alloc::string::{String, ToString};
+use ed25519_dalek::{Signer, SigningKey};
+
+const KEYRING_CONTEXT: &str = "silica-contract-sdk keyring v1 ed25519";
+const DEFAULT_SEED: &[u8] = b"silica-contract-sdk-keyring";
+
+/// Well-known account names, in the order conventional for test fixtures.
+pub const DEV_ACCOUNTS: [&str; 6] = ["alice", "bob", "charlie", "dave", "eve", "ferdie"];
+
+/// A named test account backed by an Ed25519 key.
+#[derive(Clone)]
+pub struct Account {
+    name: String,
+    signing_key: SigningKey,
+}
+
+impl Account {
+    /// The account name this key was derived from.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Ed25519 public key.
+    pub fn public_key(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// Chain address for this account.
+    pub fn address(&self) -> Address {
+        Address::from_public_key(&self.public_key())
+    }
+
+    /// Sign an arbitrary message.
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        self.signing_key.sign(message).to_bytes()
+    }
+
+    /// Sign `message` as typed data under `domain`.
+    pub fn sign_typed<T: TypedData>(
+        &self,
+        domain: &Domain,
+        message: &T,
+    ) -> ContractResult<[u8; 64]> {
+        let digest = typed_data_hash(domain, message)?;
+        Ok(self.sign(&digest))
+    }
+
+    /// Produce a VRF proof and output for `input` with this account's key.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn vrf_prove(&self, input: &[u8]) -> ([u8; 80], [u8; 32]) {
+        crate::ffi::mock::vrf_prove(&self.signing_key.to_bytes(), input)
+    }
+
+    /// Make this account the sender of the next mock invocation.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_as_sender(&self) {
+        crate::ffi::mock::set_sender(self.address().as_str());
+    }
+}
+
+/// Deterministic source of named test accounts.
+#[derive(Clone)]
+pub struct Keyring {
+    seed: [u8; 32],
+}
+
+impl Keyring {
+    /// Keyring with the SDK's default seed; accounts are stable across runs.
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Keyring whose accounts are derived from a custom seed.
+    pub fn with_seed(seed: &[u8]) -> Self {
+        Self {
+            seed: blake3::derive_key(KEYRING_CONTEXT, seed),
+        }
+    }
+
+    /// Derive the account called `name`.
+    pub fn account(&self, name: &str) -> Account {
+        let mut hasher = blake3::Hasher::new_keyed(&self.seed);
+        hasher.update(name.as_bytes());
+        let secret = *hasher.finalize().as_bytes();
+
+        Account {
+            name: name.to_string(),
+            signing_key: SigningKey::from_bytes(&secret),
+        }
+    }
+
+    /// The "alice" account.
+    pub fn alice(&self) -> Account {
+        self.account("alice")
+    }
+
+    /// The "bob" account.
+    pub fn bob(&self) -> Account {
+        self.account("bob")
+    }
+
+    /// All [`DEV_ACCOUNTS`], in order.
+    pub fn dev_accounts(&self) -> impl Iterator<Item = Account> + '_ {
+        DEV_ACCOUNTS.iter().map(|name| self.account(name))
+    }
+}
+
+impl Default for Keyring {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::context::try_context;
+    use crate::crypto;
+    use crate::ffi::mock;
+    use crate::security::validation;
+    use alloc::vec::Vec;
+    use serde::Serialize;
+
+    #[test]
+    fn accounts_are_deterministic_and_distinct() {
+        let keyring = Keyring::new();
+        assert_eq!(
+            keyring.alice().public_key(),
+            Keyring::new().alice().public_key()
+        );
+        assert_ne!(keyring.alice().public_key(), keyring.bob().public_key());
+
+        let reseeded = Keyring::with_seed(b"other suite");
+        assert_ne!(keyring.alice().public_key(), reseeded.alice().public_key());
+
+        let names: Vec<String> = keyring
+            .dev_accounts()
+            .map(|a| a.name().to_string())
+            .collect();
+        assert_eq!(names, DEV_ACCOUNTS);
+    }
+
+    #[test]
+    fn account_signatures_verify() {
+        let alice = Keyring::new().alice();
+        let signature = alice.sign(b"hello");
+
+        assert!(crypto::verify_signature(&alice.public_key(), b"hello", &signature).unwrap());
+        assert!(!crypto::verify_signature(&alice.public_key(), b"hullo", &signature).unwrap());
+    }
+
+    #[test]
+    fn account_signs_typed_data() {
+        #[derive(Serialize)]
+        struct Vote {
+            proposal: u64,
+            support: bool,
+        }
+
+        impl TypedData for Vote {
+            const TYPE_NAME: &'static str = "Vote(proposal,support)";
+        }
+
+        let bob = Keyring::new().bob();
+        let domain = Domain::new("Governor", "1", 1, &Address::contract([0x60; 32]));
+        let vote = Vote {
+            proposal: 3,
+            support: true,
+        };
+
+        let signature = bob.sign_typed(&domain, &vote).expect("sign");
+        assert!(crypto::verify_typed(&domain, &bob.public_key(), &vote, &signature).unwrap());
+    }
+
+    #[test]
+    fn account_addresses_work_with_mock_runtime() {
+        let _guard = mock::test_env();
+
+        let alice = Keyring::new().alice();
+        assert!(alice.address().starts_with("chert1"));
+        validation::validate_address(&alice.address()).expect("valid address");
+
+        alice.set_as_sender();
+        assert_eq!(try_context().expect("context").sender(), &alice.address());
+    }
+}
@@ -0,0 +1,215 @@
+//! Timelock interface and operation ids
+//!
+//! [`Timelock`] is the interface the timelock contract exports; governors and
+//! other contracts schedule operations through [`TimelockRef`].
+//!
+//! A timelock identifies a scheduled operation by the hash of its parameters,
+//! so proposers and executors can refer to it before it is stored. The
+//! timelock contract and off-chain clients both derive ids here, which keeps
+//! them in agreement.
+
+use crate::address::Address;
+use crate::error::ContractResult;
+use crate::interface::contract_interface;
+use alloc::vec::Vec;
+use blake3::Hasher;
+use serde::{Deserialize, Serialize};
+
+/// State of an operation; `get_operation_state` returns it `as u8`
+#[repr(u8)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationState {
+    /// Not scheduled
+    Unset = 0,
+    /// Scheduled, waiting for its delay
+    Pending = 1,
+    /// Delay passed, can be executed
+    Ready = 2,
+    /// Already executed
+    Executed = 3,
+    /// Cancelled before execution
+    Cancelled = 4,
+}
+
+/// [`OperationState`]s as the numbers `get_operation_state` returns
+pub mod state {
+    use super::OperationState;
+
+    pub const UNSET: u8 = OperationState::Unset as u8;
+    pub const PENDING: u8 = OperationState::Pending as u8;
+    pub const READY: u8 = OperationState::Ready as u8;
+    pub const EXECUTED: u8 = OperationState::Executed as u8;
+    pub const CANCELLED: u8 = OperationState::Cancelled as u8;
+}
+
+/// Entrypoints of the timelock contract
+///
+/// Roles are passed as ids: 0 proposer, 1 executor, 2 canceller, 3 admin.
+/// Operation states are [`OperationState`]s as `u8`, listed in [`state`].
+#[contract_interface]
+pub trait Timelock {
+    /// Set the minimum delay and grant the initial roles; no executors lets
+    /// anyone execute.
+    fn initialize(
+        &mut self,
+        min_delay: u64,
+        proposers: Vec<Address>,
+        executors: Vec<Address>,
+        admin: Address,
+    ) -> ContractResult<()>;
+    /// Schedule a call to run after `delay`, returning its operation id.
+    fn schedule(
+        &mut self,
+        target: Address,
+        value: u64,
+        data: Vec<u8>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+        delay: u64,
+    ) -> ContractResult<[u8; 32]>;
+    /// Schedule calls to run together after `delay`, returning the batch id.
+    fn schedule_batch(
+        &mut self,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        datas: Vec<Vec<u8>>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+        delay: u64,
+    ) -> ContractResult<[u8; 32]>;
+    /// Cancel a pending operation.
+    fn cancel(&mut self, id: [u8; 32]) -> ContractResult<()>;
+    /// Execute a ready operation.
+    fn execute(
+        &mut self,
+        target: Address,
+        value: u64,
+        data: Vec<u8>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<()>;
+    /// Execute a ready batch.
+    fn execute_batch(
+        &mut self,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        datas: Vec<Vec<u8>>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<()>;
+    /// Change the minimum delay.
+    fn update_delay(&mut self, new_delay: u64) -> ContractResult<()>;
+    /// Grant a role to `account`.
+    fn grant_role(&mut self, role: u8, account: Address) -> ContractResult<()>;
+    /// Revoke a role from `account`.
+    fn revoke_role(&mut self, role: u8, account: Address) -> ContractResult<()>;
+    /// State of an operation.
+    fn get_operation_state(&self, id: [u8; 32]) -> ContractResult<u8>;
+    /// Whether an operation is scheduled but not yet ready.
+    fn is_operation_pending(&self, id: [u8; 32]) -> ContractResult<bool>;
+    /// Whether an operation can be executed.
+    fn is_operation_ready(&self, id: [u8; 32]) -> ContractResult<bool>;
+    /// Whether an operation has been executed.
+    fn is_operation_done(&self, id: [u8; 32]) -> ContractResult<bool>;
+    /// When an operation becomes ready, or 0 if it does not exist.
+    fn get_timestamp_op(&self, id: [u8; 32]) -> ContractResult<u64>;
+    /// Minimum delay in seconds.
+    fn get_min_delay(&self) -> ContractResult<u64>;
+    /// Whether `account` holds a role.
+    fn has_role_check(&self, role: u8, account: Address) -> ContractResult<bool>;
+    /// Id of a single-call operation, as [`hash_operation`].
+    fn hash_operation_fn(
+        &self,
+        target: Address,
+        value: u64,
+        data: Vec<u8>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<[u8; 32]>;
+    /// Id of a batch, as [`hash_operation_batch`].
+    fn hash_operation_batch_fn(
+        &self,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        datas: Vec<Vec<u8>>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<[u8; 32]>;
+}
+
+/// Id of a single-call operation.
+///
+/// `blake3(target || value_le || data || predecessor || salt)`, with an
+/// all-zero predecessor when there is none.
+pub fn hash_operation(
+    target: &str,
+    value: u64,
+    data: &[u8],
+    predecessor: &Option<[u8; 32]>,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(target.as_bytes());
+    hasher.update(&value.to_le_bytes());
+    hasher.update(data);
+    hasher.update(predecessor.as_ref().unwrap_or(&[0u8; 32]));
+    hasher.update(salt);
+    *hasher.finalize().as_bytes()
+}
+
+/// Id of a batch operation.
+///
+/// Hashes all targets, then all values, then all call data, followed by the
+/// predecessor and salt as in [`hash_operation`]. Addresses hash as their
+/// bech32m string.
+pub fn hash_operation_batch<T: AsRef<str>>(
+    targets: &[T],
+    values: &[u64],
+    datas: &[Vec<u8>],
+    predecessor: &Option<[u8; 32]>,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    for target in targets {
+        hasher.update(target.as_ref().as_bytes());
+    }
+    for value in values {
+        hasher.update(&value.to_le_bytes());
+    }
+    for data in datas {
+        hasher.update(data);
+    }
+    hasher.update(predecessor.as_ref().unwrap_or(&[0u8; 32]));
+    hasher.update(salt);
+    *hasher.finalize().as_bytes()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+
+    #[test]
+    fn ids_cover_every_parameter() {
+        let salt = [7u8; 32];
+        let id = hash_operation("target", 5, &[1, 2], &None, &salt);
+
+        // A missing predecessor hashes as the zero id.
+        assert_eq!(
+            id,
+            hash_operation("target", 5, &[1, 2], &Some([0; 32]), &salt)
+        );
+        assert_ne!(
+            id,
+            hash_operation("target", 5, &[1, 2], &Some([1; 32]), &salt)
+        );
+        assert_ne!(id, hash_operation("target", 6, &[1, 2], &None, &salt));
+        assert_ne!(id, hash_operation("target", 5, &[1, 2], &None, &[8; 32]));
+
+        // A one-call batch has the same layout as the single call.
+        let batch =
+            hash_operation_batch(&[String::from("target")], &[5], &[vec![1, 2]], &None, &salt);
+        assert_eq!(id, batch);
+    }
+}
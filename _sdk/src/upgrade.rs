@@ -0,0 +1,507 @@
+//! Contract upgrades
+//!
+//! Two patterns are supported:
+//!
+//! - **In place.** [`Upgradeable::upgrade`] runs the new code's
+//!   [`MIGRATE_ENTRYPOINT`] against this contract's storage and, if the
+//!   migration succeeds, swaps the contract's code with the `set_code` host
+//!   import. The address and storage stay the same.
+//! - **Proxy.** A thin, never-upgraded contract keeps its address and storage
+//!   and forwards every call to an implementation module with `delegate_call`.
+//!   [`Proxy::upgrade_to`] points it at a new implementation.
+//!
+//! Both are gated on [`DEFAULT_ADMIN_ROLE`], so handing ownership to a timelock
+//! puts upgrades behind its delay. Use [`layout`] in tests to check that new
+//! code can still read the storage written by the old code.
+
+use crate::address::Address;
+use crate::error::{ContractError, ContractResult};
+use crate::events::emit;
+use crate::ffi;
+use crate::security::access_control::{AccessControl, DEFAULT_ADMIN_ROLE};
+use crate::storage::storage;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// BLAKE3 hash identifying an uploaded wasm module.
+pub type CodeHash = [u8; 32];
+
+/// Entrypoint the new code must export to migrate storage during
+/// [`Upgradeable::upgrade`]. It receives the postcard-encoded storage version
+/// the contract is migrating from.
+pub const MIGRATE_ENTRYPOINT: &str = "migrate";
+
+const VERSION_KEY: &str = "__upgrade_version";
+const MIGRATING_KEY: &str = "__upgrade_migrating";
+const IMPLEMENTATION_KEY: &str = "__proxy_implementation";
+
+/// Emitted when a contract's code or a proxy's implementation changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upgraded {
+    pub previous_code_hash: Option<CodeHash>,
+    pub code_hash: CodeHash,
+    pub upgraded_by: Address,
+}
+
+/// Hash of the code this contract is currently running.
+pub fn code_hash() -> CodeHash {
+    ffi::get_code_hash()
+}
+
+/// Run `entrypoint` of the uploaded module `code_hash` with this contract's
+/// storage and context, returning the data it writes back.
+pub fn delegate_call(
+    code_hash: &CodeHash,
+    entrypoint: &str,
+    input: &[u8],
+) -> ContractResult<Vec<u8>> {
+    ffi::delegate_call(code_hash, entrypoint, input)
+}
+
+/// In-place code upgrades with versioned storage migrations.
+pub struct Upgradeable;
+
+impl Upgradeable {
+    /// Storage version recorded by the last migration; 0 before any.
+    pub fn version() -> ContractResult<u32> {
+        Ok(storage().get(VERSION_KEY)?.unwrap_or(0))
+    }
+
+    /// Migrate storage with `code_hash`'s [`MIGRATE_ENTRYPOINT`], then switch
+    /// this contract to that code. Nothing changes if the migration fails.
+    pub fn upgrade(caller: &Address, code_hash: CodeHash) -> ContractResult<()> {
+        AccessControl::authorize(caller, Some(DEFAULT_ADMIN_ROLE))?;
+        let previous = self::code_hash();
+        if previous == code_hash {
+            return Err(ContractError::InvalidArgument(String::from(
+                "Code is already deployed",
+            )));
+        }
+
+        let from = postcard::to_allocvec(&Upgradeable::version()?)
+            .map_err(|_| ContractError::SerializationFailed)?;
+        storage().set(MIGRATING_KEY, &true)?;
+        let migrated = delegate_call(&code_hash, MIGRATE_ENTRYPOINT, &from);
+        storage().remove(MIGRATING_KEY)?;
+        migrated?;
+
+        ffi::set_code(&code_hash)?;
+        emit(
+            "Upgraded",
+            &Upgraded {
+                previous_code_hash: Some(previous),
+                code_hash,
+                upgraded_by: caller.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Body of the new code's [`MIGRATE_ENTRYPOINT`]: run `migration` with the
+    /// current storage version if it is older than `to_version`, then record
+    /// `to_version`. Fails with [`ContractError::Unauthorized`] outside of
+    /// [`Upgradeable::upgrade`].
+    pub fn migrate<F>(to_version: u32, migration: F) -> ContractResult<()>
+    where
+        F: FnOnce(u32) -> ContractResult<()>,
+    {
+        if !storage().has(MIGRATING_KEY) {
+            return Err(ContractError::Unauthorized);
+        }
+        let from = Upgradeable::version()?;
+        if from >= to_version {
+            return Ok(());
+        }
+        migration(from)?;
+        storage().set(VERSION_KEY, &to_version)
+    }
+}
+
+/// Delegate-call proxy: a fixed address whose calls run an upgradeable
+/// implementation against the proxy's own storage.
+///
+/// The proxy keeps its state under `__proxy_` keys so it cannot collide with
+/// the implementation's storage.
+pub struct Proxy;
+
+impl Proxy {
+    /// Set the first implementation. Like [`Proxy::upgrade_to`], the caller
+    /// must hold [`DEFAULT_ADMIN_ROLE`], so access control has to be
+    /// initialized first. Fails if an implementation is already set.
+    pub fn initialize(caller: &Address, implementation: CodeHash) -> ContractResult<()> {
+        AccessControl::authorize(caller, Some(DEFAULT_ADMIN_ROLE))?;
+        if storage().has(IMPLEMENTATION_KEY) {
+            return Err(ContractError::InvalidArgument(String::from(
+                "Proxy already initialized",
+            )));
+        }
+        storage().set(IMPLEMENTATION_KEY, &implementation)
+    }
+
+    /// Current implementation, if the proxy has been initialized.
+    pub fn implementation() -> ContractResult<Option<CodeHash>> {
+        storage().get(IMPLEMENTATION_KEY)
+    }
+
+    /// Point the proxy at `implementation`.
+    pub fn upgrade_to(caller: &Address, implementation: CodeHash) -> ContractResult<()> {
+        AccessControl::authorize(caller, Some(DEFAULT_ADMIN_ROLE))?;
+        let previous = Proxy::implementation()?;
+        storage().set(IMPLEMENTATION_KEY, &implementation)?;
+        emit(
+            "Upgraded",
+            &Upgraded {
+                previous_code_hash: previous,
+                code_hash: implementation,
+                upgraded_by: caller.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Point the proxy at `implementation` and immediately call `entrypoint`
+    /// on it, typically to migrate storage.
+    pub fn upgrade_to_and_call(
+        caller: &Address,
+        implementation: CodeHash,
+        entrypoint: &str,
+        input: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        Proxy::upgrade_to(caller, implementation)?;
+        Proxy::forward(entrypoint, input)
+    }
+
+    /// Forward a call to the current implementation.
+    pub fn forward(entrypoint: &str, input: &[u8]) -> ContractResult<Vec<u8>> {
+        let implementation = Proxy::implementation()?.ok_or_else(|| {
+            ContractError::ContractCallFailed(String::from("Proxy has no implementation"))
+        })?;
+        delegate_call(&implementation, entrypoint, input)
+    }
+}
+
+/// Storage layout descriptions for upgrade-compatibility tests.
+///
+/// Each entry is traced the way [`AbiBuilder`](crate::abi::AbiBuilder) traces
+/// storage, so renaming a type is fine as long as its encoding is unchanged.
+///
+/// ```rust,ignore
+/// let v1 = StorageLayout::new().entry::<u64>("total").entry::<Config>("config");
+/// let v2 = v1.clone().entry::<bool>("paused");
+/// v2.check_upgrade_from(&v1)?;
+/// layout::decode_as::<ConfigV1, Config>(&sample_v1_config)?;
+/// ```
+#[cfg(feature = "abi")]
+pub mod layout {
+    use crate::abi::same_encoding;
+    use crate::error::{ContractError, ContractResult};
+    use alloc::collections::BTreeSet;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+    use serde_reflection::{Format, FormatHolder, Registry, Tracer, TracerConfig};
+
+    /// One storage key (or `Map`/`Vector` prefix) and how the value stored
+    /// under it is encoded.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct LayoutEntry {
+        pub key: &'static str,
+        pub ty: &'static str,
+        pub format: Format,
+        /// Containers referenced by `format`.
+        pub types: Registry,
+    }
+
+    /// The keys a contract version reads and writes.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct StorageLayout {
+        entries: Vec<LayoutEntry>,
+        error: Option<String>,
+    }
+
+    fn trace<T: DeserializeOwned>() -> Result<(Format, Registry), serde_reflection::Error> {
+        let mut tracer = Tracer::new(TracerConfig::default());
+        let (mut format, _) = tracer.trace_simple_type::<T>()?;
+        format.normalize()?;
+        Ok((format, tracer.registry()?))
+    }
+
+    impl StorageLayout {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Declare that `key` holds a `T`.
+        pub fn entry<T: DeserializeOwned>(mut self, key: &'static str) -> Self {
+            let ty = core::any::type_name::<T>();
+            match trace::<T>() {
+                Ok((format, types)) => self.entries.push(LayoutEntry {
+                    key,
+                    ty,
+                    format,
+                    types,
+                }),
+                Err(error) => {
+                    self.error
+                        .get_or_insert_with(|| format!("cannot trace {}: {}", ty, error));
+                }
+            }
+            self
+        }
+
+        pub fn entries(&self) -> &[LayoutEntry] {
+            &self.entries
+        }
+
+        /// Check that every key of `previous` is still declared here with the
+        /// same encoding. New keys are allowed; dropped keys, or keys whose
+        /// values no longer decode the same way, are not.
+        pub fn check_upgrade_from(&self, previous: &StorageLayout) -> ContractResult<()> {
+            if let Some(error) = self.error.as_ref().or(previous.error.as_ref()) {
+                return Err(ContractError::InvalidArgument(format!(
+                    "Invalid storage layout: {}",
+                    error
+                )));
+            }
+            let conflicts: Vec<String> = previous
+                .entries
+                .iter()
+                .filter_map(
+                    |old| match self.entries.iter().find(|new| new.key == old.key) {
+                        None => Some(format!("`{}` was removed", old.key)),
+                        Some(new)
+                            if !same_encoding(
+                                &old.format,
+                                &old.types,
+                                &new.format,
+                                &new.types,
+                                &mut BTreeSet::new(),
+                            ) =>
+                        {
+                            Some(format!(
+                                "`{}` changed encoding from {} to {}",
+                                old.key, old.ty, new.ty
+                            ))
+                        }
+                        Some(_) => None,
+                    },
+                )
+                .collect();
+
+            if conflicts.is_empty() {
+                Ok(())
+            } else {
+                Err(ContractError::InvalidArgument(format!(
+                    "Incompatible storage layout: {}",
+                    conflicts.join(", ")
+                )))
+            }
+        }
+    }
+
+    /// Read a value stored by old code as the type new code uses for it.
+    pub fn decode_as<Old: Serialize, New: DeserializeOwned>(value: &Old) -> ContractResult<New> {
+        let bytes = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+        postcard::from_bytes(&bytes).map_err(|_| ContractError::DeserializationFailed)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    #[cfg(feature = "abi")]
+    use super::layout::{StorageLayout, decode_as};
+    use super::*;
+    use crate::ffi::mock;
+
+    const V1: CodeHash = [1; 32];
+    const V2: CodeHash = [2; 32];
+    const BROKEN: CodeHash = [3; 32];
+
+    #[cfg(feature = "abi")]
+    #[derive(Serialize, Deserialize)]
+    struct ConfigV1 {
+        fee_bps: u16,
+    }
+
+    /// `ConfigV1` under a new name, encoded the same way.
+    #[cfg(feature = "abi")]
+    #[derive(Serialize, Deserialize)]
+    struct FeeConfig {
+        fee_bps: u16,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ConfigV2 {
+        fee_bps: u16,
+        max_fee: u64,
+    }
+
+    fn counter_v1(entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+        match entrypoint {
+            "increment" => {
+                let count: u64 = storage().get("count")?.unwrap_or(0) + 1;
+                storage().set("count", &count)?;
+                Ok(count.to_le_bytes().to_vec())
+            }
+            _ => Err(ContractError::ContractCallFailed(entrypoint.into())),
+        }
+    }
+
+    fn counter_v2(entrypoint: &str, input: &[u8]) -> ContractResult<Vec<u8>> {
+        match entrypoint {
+            MIGRATE_ENTRYPOINT => {
+                let from: u32 = postcard::from_bytes(input).expect("version");
+                assert_eq!(from, 0);
+                Upgradeable::migrate(2, |_| {
+                    storage().set(
+                        "config",
+                        &ConfigV2 {
+                            fee_bps: 30,
+                            max_fee: 1_000,
+                        },
+                    )
+                })?;
+                Ok(Vec::new())
+            }
+            "increment" => {
+                let count: u64 = storage().get("count")?.unwrap_or(0) + 10;
+                storage().set("count", &count)?;
+                Ok(count.to_le_bytes().to_vec())
+            }
+            _ => Err(ContractError::ContractCallFailed(entrypoint.into())),
+        }
+    }
+
+    fn broken(_entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+        Upgradeable::migrate(9, |_| Err(ContractError::Custom("boom".into())))?;
+        Ok(Vec::new())
+    }
+
+    fn setup() -> Address {
+        mock::register_code(V1, counter_v1);
+        mock::register_code(V2, counter_v2);
+        mock::register_code(BROKEN, broken);
+        mock::set_code_hash(mock::test_contract().as_str(), V1);
+        let owner = mock::test_sender();
+        AccessControl::initialize(&owner).expect("init");
+        mock::take_events();
+        owner
+    }
+
+    #[test]
+    fn upgrade_migrates_then_swaps_code() {
+        let _guard = mock::test_env();
+        let owner = setup();
+        let stranger = Address::from_public_key(&[9; 32]);
+
+        assert!(matches!(
+            Upgradeable::upgrade(&stranger, V2),
+            Err(ContractError::Unauthorized)
+        ));
+        assert!(Upgradeable::upgrade(&owner, V1).is_err());
+        assert!(matches!(
+            Upgradeable::migrate(2, |_| Ok(())),
+            Err(ContractError::Unauthorized)
+        ));
+
+        Upgradeable::upgrade(&owner, V2).expect("upgrade");
+        assert_eq!(code_hash(), V2);
+        assert_eq!(Upgradeable::version().unwrap(), 2);
+        assert_eq!(
+            storage()
+                .get::<ConfigV2>("config")
+                .unwrap()
+                .unwrap()
+                .max_fee,
+            1_000
+        );
+        assert!(!storage().has(MIGRATING_KEY));
+
+        let events = mock::take_events();
+        assert_eq!(events.len(), 1);
+        let upgraded: Upgraded = postcard::from_bytes(&events[0].data).unwrap();
+        assert_eq!(upgraded.previous_code_hash, Some(V1));
+        assert_eq!(upgraded.code_hash, V2);
+    }
+
+    #[test]
+    fn failed_migration_keeps_old_code() {
+        let _guard = mock::test_env();
+        let owner = setup();
+
+        assert!(Upgradeable::upgrade(&owner, BROKEN).is_err());
+        assert!(Upgradeable::upgrade(&owner, [0xff; 32]).is_err());
+        assert_eq!(code_hash(), V1);
+        assert_eq!(Upgradeable::version().unwrap(), 0);
+        assert!(mock::take_events().is_empty());
+    }
+
+    #[test]
+    fn proxy_forwards_to_current_implementation() {
+        let _guard = mock::test_env();
+        let owner = setup();
+        let stranger = Address::from_public_key(&[9; 32]);
+
+        assert!(Proxy::forward("increment", &[]).is_err());
+        assert!(matches!(
+            Proxy::initialize(&stranger, V2),
+            Err(ContractError::Unauthorized)
+        ));
+        Proxy::initialize(&owner, V1).expect("init proxy");
+        assert!(Proxy::initialize(&owner, V2).is_err());
+
+        assert_eq!(
+            Proxy::forward("increment", &[]).unwrap(),
+            1u64.to_le_bytes()
+        );
+        assert!(matches!(
+            Proxy::upgrade_to(&stranger, V2),
+            Err(ContractError::Unauthorized)
+        ));
+
+        // The new implementation sees the proxy's existing storage.
+        Proxy::upgrade_to(&owner, V2).expect("upgrade proxy");
+        assert_eq!(Proxy::implementation().unwrap(), Some(V2));
+        assert_eq!(
+            Proxy::forward("increment", &[]).unwrap(),
+            11u64.to_le_bytes()
+        );
+        assert_eq!(code_hash(), V1);
+    }
+
+    #[cfg(feature = "abi")]
+    #[test]
+    fn storage_layouts_must_stay_compatible() {
+        let v1 = StorageLayout::new()
+            .entry::<u64>("count")
+            .entry::<ConfigV1>("config");
+        let appended = v1.clone().entry::<bool>("paused");
+        appended
+            .check_upgrade_from(&v1)
+            .expect("appending keys is fine");
+        StorageLayout::new()
+            .entry::<u64>("count")
+            .entry::<FeeConfig>("config")
+            .check_upgrade_from(&v1)
+            .expect("renaming a type keeps its encoding");
+
+        let retyped = StorageLayout::new()
+            .entry::<u64>("count")
+            .entry::<ConfigV2>("config");
+        assert!(retyped.check_upgrade_from(&v1).is_err());
+        assert!(
+            StorageLayout::new()
+                .entry::<u64>("count")
+                .check_upgrade_from(&v1)
+                .is_err()
+        );
+
+        // A retyped value needs a migration: old bytes do not decode as the
+        // new struct.
+        assert!(decode_as::<ConfigV1, ConfigV2>(&ConfigV1 { fee_bps: 30 }).is_err());
+        assert_eq!(decode_as::<u32, u64>(&7).unwrap(), 7);
+    }
+}
@@ -0,0 +1,258 @@
+//! Driving cargo for `build` and `abi`
+//!
+//! Everything goes through the `cargo` on `PATH`, so the workspace's own
+//! release profile (`opt-level = "z"`, LTO, `panic = "abort"`) applies and
+//! `--offline` behaves exactly as it does for cargo.
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use silica_contract_sdk::abi::ContractAbi;
+use silica_contract_validator::{METADATA_KEY, Policy};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Target contracts are compiled for.
+pub const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// The SDK is a cdylib only so it can be inspected on its own; it is never
+/// deployed.
+const SDK_PACKAGE: &str = "silica-contract-sdk";
+
+#[derive(Deserialize)]
+struct Metadata {
+    target_directory: PathBuf,
+    packages: Vec<Package>,
+}
+
+/// A workspace member.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    targets: Vec<Target>,
+    #[serde(default)]
+    metadata: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Target {
+    name: String,
+    crate_types: Vec<String>,
+}
+
+impl Package {
+    /// Directory holding the package's `Cargo.toml`.
+    pub fn dir(&self) -> &Path {
+        self.manifest_path
+            .parent()
+            .expect("manifest path has a parent")
+    }
+
+    /// Validation policy from `[package.metadata.silica-contract]`, with every
+    /// entrypoint in the package's `abi.json` required as an export.
+    pub fn policy(&self) -> Result<Policy> {
+        let mut policy = match self.metadata.get(METADATA_KEY) {
+            Some(table) => Policy::deserialize(table).with_context(|| {
+                format!(
+                    "invalid [package.metadata.{}] in {}",
+                    METADATA_KEY,
+                    self.manifest_path.display()
+                )
+            })?,
+            None => Policy::default(),
+        };
+        let abi = self.dir().join("abi.json");
+        if abi.exists() {
+            let json = fs::read_to_string(&abi)
+                .with_context(|| format!("cannot read {}", abi.display()))?;
+            let abi = ContractAbi::from_json(&json)
+                .map_err(|error| anyhow!("invalid {}: {:?}", abi.display(), error))?;
+            for entrypoint in abi.entrypoints {
+                if !policy.required_exports.contains(&entrypoint.name) {
+                    policy.required_exports.push(entrypoint.name);
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    fn cdylib(&self) -> Option<&Target> {
+        self.targets
+            .iter()
+            .find(|target| target.crate_types.iter().any(|ty| ty == "cdylib"))
+    }
+}
+
+/// The workspace around the current directory.
+pub struct Workspace {
+    target_directory: PathBuf,
+    packages: Vec<Package>,
+}
+
+impl Workspace {
+    pub fn load(offline: bool) -> Result<Self> {
+        let mut command = cargo(offline);
+        command
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .stdout(Stdio::piped());
+        let output = command.output().context("cannot run cargo")?;
+        if !output.status.success() {
+            bail!(
+                "cargo metadata failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let metadata: Metadata =
+            serde_json::from_slice(&output.stdout).context("unexpected cargo metadata output")?;
+        Ok(Self {
+            target_directory: metadata.target_directory,
+            packages: metadata.packages,
+        })
+    }
+
+    pub fn package(&self, name: &str) -> Result<&Package> {
+        self.packages
+            .iter()
+            .find(|package| package.name == name)
+            .with_context(|| format!("no package `{}` in the workspace", name))
+    }
+
+    /// Contract packages: every cdylib member except the SDK.
+    pub fn contracts(&self) -> Vec<&Package> {
+        self.packages
+            .iter()
+            .filter(|package| package.name != SDK_PACKAGE && package.cdylib().is_some())
+            .collect()
+    }
+
+    /// Path of the release wasm built for `package`.
+    pub fn artifact(&self, package: &Package) -> Result<PathBuf> {
+        let target = package
+            .cdylib()
+            .with_context(|| format!("`{}` has no cdylib target", package.name))?;
+        Ok(self
+            .target_directory
+            .join(WASM_TARGET)
+            .join("release")
+            .join(format!("{}.wasm", target.name.replace('-', "_"))))
+    }
+}
+
+/// Build `packages` as release wasm and return the artifact paths.
+pub fn build(workspace: &Workspace, packages: &[&Package], offline: bool) -> Result<Vec<PathBuf>> {
+    let mut command = cargo(offline);
+    command.args(["build", "--release", "--lib", "--target", WASM_TARGET]);
+    for package in packages {
+        command.args(["-p", &package.name]);
+    }
+    let status = command.status().context("cannot run cargo")?;
+    if !status.success() {
+        bail!("cargo build failed");
+    }
+    packages
+        .iter()
+        .map(|package| workspace.artifact(package))
+        .collect()
+}
+
+/// Regenerate and check `package`'s `abi.json` by running its `abi` test,
+/// returning the path of the file.
+pub fn abi(package: &Package, update: bool, offline: bool) -> Result<PathBuf> {
+    let path = package.dir().join("abi.json");
+    if !package.dir().join("tests").join("abi.rs").exists() {
+        bail!("`{}` has no tests/abi.rs describing its ABI", package.name);
+    }
+    // The workspace builds for wasm by default; ABI tests run natively.
+    let host = host_target()?;
+    let mut command = cargo(offline);
+    command.args([
+        "test",
+        "-p",
+        &package.name,
+        "--test",
+        "abi",
+        "--target",
+        &host,
+    ]);
+    if update {
+        command.env(silica_contract_sdk::abi::UPDATE_ENV, "1");
+    }
+    let status = command.status().context("cannot run cargo")?;
+    if !status.success() {
+        bail!(
+            "ABI check for `{}` failed{}",
+            package.name,
+            if update {
+                ""
+            } else {
+                "; rerun with --update to accept compatible changes"
+            }
+        );
+    }
+    Ok(path)
+}
+
+fn host_target() -> Result<String> {
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let output = Command::new(rustc)
+        .arg("-vV")
+        .output()
+        .context("cannot run rustc")?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::to_string)
+        .context("cannot determine the host target")
+}
+
+fn cargo(offline: bool) -> Command {
+    let mut command = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    if offline {
+        command.arg("--offline");
+    }
+    // Keep stdout for our own output, such as the ABI itself.
+    command.stdout(std::io::stderr());
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Module exporting `memory` and a function without parameters for each
+    /// name, the shape `#[export_interface]` gives a contract's entrypoints.
+    fn exporting(names: &[String]) -> Vec<u8> {
+        let functions: String = names
+            .iter()
+            .map(|name| format!(r#"(func (export "{}"))"#, name))
+            .collect();
+        wat::parse_str(format!(
+            r#"(module (memory (export "memory") 1) {})"#,
+            functions
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn contract_policies_accept_their_entrypoints() {
+        // Each contract's tests/abi.rs checks that its ABI entrypoints are
+        // exported this way.
+        let workspace = Workspace::load(true).unwrap();
+        let contracts = workspace.contracts();
+        assert!(
+            contracts
+                .iter()
+                .any(|package| package.name == "timelock-contract")
+        );
+        for package in contracts {
+            let policy = package.policy().unwrap();
+            if package.dir().join("abi.json").exists() {
+                assert!(!policy.required_exports.is_empty(), "{}", package.name);
+            }
+            let violations =
+                silica_contract_validator::validate(&exporting(&policy.required_exports), &policy);
+            assert!(violations.is_empty(), "{}: {:?}", package.name, violations);
+        }
+    }
+}
@@ -0,0 +1,647 @@
+//! Local wasm harness
+//!
+//! Runs one exported entrypoint of a contract module under wasmi, with the
+//! SDK's `env` host functions backed by a [`State`]. Storage, events and
+//! transfers are committed to the state only if the call completes; logs are
+//! reported either way.
+//!
+//! Calls to other contracts, code upgrades and randomness beacons fail the way
+//! a host reports an unavailable service. The proof-verification imports trap,
+//! since answering them wrongly would be worse than stopping. Memory is capped
+//! at the validator's default limit, so `memory.grow` past it fails.
+
+use crate::state::{Block, EventEntry, LogEntry, State, TransferEntry};
+use anyhow::{Context, Result, anyhow, bail};
+use silica_contract_sdk::logging::LogRecord;
+use silica_contract_validator::{DEFAULT_MAX_MEMORY_PAGES, PAGE_SIZE};
+use std::collections::BTreeMap;
+use wasmi::{
+    Caller, Config, Engine, Error, Extern, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, Val,
+};
+
+/// Largest value `state_read` may return, matching the SDK's read buffer.
+const MAX_VALUE_SIZE: usize = 65_536;
+
+/// One call to run.
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    pub entrypoint: String,
+    pub contract: String,
+    pub sender: String,
+    pub value: u128,
+    pub call_data: Vec<u8>,
+    /// Fuel limit; unlimited if `None`
+    pub fuel: Option<u64>,
+}
+
+/// What a call did.
+#[derive(Clone, Debug, Default)]
+pub struct Outcome {
+    /// Values returned by the wasm function itself
+    pub results: Vec<String>,
+    /// Bytes passed to `write_return_data`
+    pub return_data: Vec<u8>,
+    pub events: Vec<EventEntry>,
+    pub logs: Vec<LogEntry>,
+    pub storage_writes: usize,
+    pub fuel_consumed: Option<u64>,
+    /// Why the call trapped, if it did; nothing was committed then
+    pub trap: Option<String>,
+}
+
+struct Host {
+    block: Block,
+    contract: String,
+    sender: String,
+    value: u128,
+    code_hash: [u8; 32],
+    call_data: Vec<u8>,
+    storage: BTreeMap<String, BTreeMap<String, Vec<u8>>>,
+    storage_writes: usize,
+    return_data: Vec<u8>,
+    events: Vec<EventEntry>,
+    logs: Vec<LogEntry>,
+    transfers: Vec<TransferEntry>,
+    limits: StoreLimits,
+}
+
+impl Host {
+    fn log(&mut self, level: Option<String>, message: String, fields: Vec<(String, String)>) {
+        self.logs.push(LogEntry {
+            contract: self.contract.clone(),
+            level,
+            message,
+            fields,
+        });
+    }
+}
+
+/// Run `invocation` against `wasm`, committing its effects to `state`.
+pub fn run(wasm: &[u8], state: &mut State, invocation: &Invocation) -> Result<Outcome> {
+    let mut config = Config::default();
+    config.consume_fuel(invocation.fuel.is_some());
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, wasm).context("invalid wasm module")?;
+
+    let host = Host {
+        block: state.block.clone(),
+        contract: invocation.contract.clone(),
+        sender: invocation.sender.clone(),
+        value: invocation.value,
+        code_hash: *blake3::hash(wasm).as_bytes(),
+        call_data: invocation.call_data.clone(),
+        storage: state.storage_bytes()?,
+        storage_writes: 0,
+        return_data: Vec::new(),
+        events: Vec::new(),
+        logs: Vec::new(),
+        transfers: Vec::new(),
+        limits: StoreLimitsBuilder::new()
+            .memory_size((u64::from(DEFAULT_MAX_MEMORY_PAGES) * PAGE_SIZE) as usize)
+            .build(),
+    };
+    let mut store = Store::new(&engine, host);
+    store.limiter(|host| &mut host.limits);
+    if let Some(fuel) = invocation.fuel {
+        store
+            .set_fuel(fuel)
+            .map_err(|error| anyhow!("cannot set fuel: {}", error))?;
+    }
+
+    let linker = linker(&engine)?;
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .context("cannot link the module against the host")?
+        .start(&mut store)?;
+    let func = instance
+        .get_func(&store, &invocation.entrypoint)
+        .with_context(|| format!("no exported function `{}`", invocation.entrypoint))?;
+    let ty = func.ty(&store);
+    if !ty.params().is_empty() {
+        bail!(
+            "`{}` takes wasm parameters; only entrypoints that read their arguments from call data can be called",
+            invocation.entrypoint
+        );
+    }
+
+    let mut results: Vec<Val> = ty.results().iter().map(|ty| Val::default(*ty)).collect();
+    let trap = func
+        .call(&mut store, &[], &mut results)
+        .err()
+        .map(|error| error.to_string());
+    let fuel_consumed = invocation
+        .fuel
+        .map(|fuel| fuel - store.get_fuel().unwrap_or(0));
+
+    let host = store.into_data();
+    let mut outcome = Outcome {
+        results: Vec::new(),
+        return_data: host.return_data,
+        events: Vec::new(),
+        logs: host.logs.clone(),
+        storage_writes: host.storage_writes,
+        fuel_consumed,
+        trap,
+    };
+    state.logs.extend(host.logs);
+    if outcome.trap.is_none() {
+        outcome.results = results.iter().map(display_val).collect();
+        outcome.events = host.events.clone();
+        state.events.extend(host.events);
+        state.transfers.extend(host.transfers);
+        state.set_storage_bytes(host.storage);
+    }
+    Ok(outcome)
+}
+
+fn display_val(val: &Val) -> String {
+    match val {
+        Val::I32(value) => value.to_string(),
+        Val::I64(value) => value.to_string(),
+        Val::F32(value) => f32::from(*value).to_string(),
+        Val::F64(value) => f64::from(*value).to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn memory(caller: &Caller<'_, Host>) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new("contract does not export `memory`"))
+}
+
+fn read_bytes(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Result<Vec<u8>, Error> {
+    if len < 0 {
+        return Err(Error::new("negative length passed to the host"));
+    }
+    let mut buffer = vec![0; len as usize];
+    memory(caller)?
+        .read(caller, ptr as u32 as usize, &mut buffer)
+        .map_err(|_| Error::new("host read outside of memory"))?;
+    Ok(buffer)
+}
+
+fn read_str(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Result<String, Error> {
+    String::from_utf8(read_bytes(caller, ptr, len)?)
+        .map_err(|_| Error::new("string passed to the host is not UTF-8"))
+}
+
+fn write_bytes(caller: &mut Caller<'_, Host>, ptr: i32, data: &[u8]) -> Result<(), Error> {
+    memory(caller)?
+        .write(caller, ptr as u32 as usize, data)
+        .map_err(|_| Error::new("host write outside of memory"))
+}
+
+fn unsupported(name: &'static str) -> Error {
+    Error::new(format!("`{}` is not supported by the local harness", name))
+}
+
+fn linker(engine: &Engine) -> Result<Linker<Host>> {
+    let mut linker = Linker::new(engine);
+
+    linker.func_wrap(
+        "env",
+        "state_read",
+        |mut caller: Caller<'_, Host>,
+         account_ptr: i32,
+         account_len: i32,
+         key_ptr: i32,
+         key_len: i32,
+         value_ptr: i32,
+         value_len_ptr: i32|
+         -> Result<i32, Error> {
+            let account = read_str(&caller, account_ptr, account_len)?;
+            let key = read_str(&caller, key_ptr, key_len)?;
+            let value = caller
+                .data()
+                .storage
+                .get(&account)
+                .and_then(|entries| entries.get(&key))
+                .cloned()
+                .unwrap_or_default();
+            if value.len() > MAX_VALUE_SIZE {
+                return Ok(-1);
+            }
+            write_bytes(&mut caller, value_ptr, &value)?;
+            write_bytes(
+                &mut caller,
+                value_len_ptr,
+                &(value.len() as i32).to_le_bytes(),
+            )?;
+            Ok(0)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "state_write",
+        |mut caller: Caller<'_, Host>,
+         account_ptr: i32,
+         account_len: i32,
+         key_ptr: i32,
+         key_len: i32,
+         value_ptr: i32,
+         value_len: i32|
+         -> Result<i32, Error> {
+            let account = read_str(&caller, account_ptr, account_len)?;
+            let key = read_str(&caller, key_ptr, key_len)?;
+            let value = read_bytes(&caller, value_ptr, value_len)?;
+            let host = caller.data_mut();
+            let entries = host.storage.entry(account).or_default();
+            // An empty value deletes the key, as on chain.
+            if value.is_empty() {
+                entries.remove(&key);
+            } else {
+                entries.insert(key, value);
+            }
+            host.storage_writes += 1;
+            Ok(0)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "log",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<(), Error> {
+            let message = String::from_utf8_lossy(&read_bytes(&caller, ptr, len)?).into_owned();
+            caller.data_mut().log(None, message, Vec::new());
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "log_record",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<(), Error> {
+            let record: LogRecord = postcard::from_bytes(&read_bytes(&caller, ptr, len)?)
+                .map_err(|_| Error::new("malformed log record"))?;
+            caller.data_mut().log(
+                Some(record.level.to_string()),
+                record.message,
+                record.fields,
+            );
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "emit_event",
+        |mut caller: Caller<'_, Host>,
+         topic_ptr: i32,
+         topic_len: i32,
+         data_ptr: i32,
+         data_len: i32|
+         -> Result<(), Error> {
+            let topic = read_str(&caller, topic_ptr, topic_len)?;
+            let data = read_bytes(&caller, data_ptr, data_len)?;
+            let host = caller.data_mut();
+            host.events.push(EventEntry {
+                block_height: host.block.height,
+                contract: host.contract.clone(),
+                topic,
+                data: hex::encode(data),
+            });
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "transfer_u128",
+        |mut caller: Caller<'_, Host>,
+         to_ptr: i32,
+         to_len: i32,
+         amount_ptr: i32|
+         -> Result<i32, Error> {
+            let to = read_str(&caller, to_ptr, to_len)?;
+            let amount: [u8; 16] = read_bytes(&caller, amount_ptr, 16)?
+                .try_into()
+                .expect("read 16 bytes");
+            let host = caller.data_mut();
+            host.transfers.push(TransferEntry {
+                block_height: host.block.height,
+                from: host.contract.clone(),
+                to,
+                amount: u128::from_le_bytes(amount).to_string(),
+            });
+            Ok(0)
+        },
+    )?;
+
+    linker.func_wrap("env", "get_block_height", |caller: Caller<'_, Host>| {
+        caller.data().block.height as i64
+    })?;
+    linker.func_wrap("env", "get_block_timestamp", |caller: Caller<'_, Host>| {
+        caller.data().block.timestamp as i64
+    })?;
+    linker.func_wrap("env", "get_chain_id", |caller: Caller<'_, Host>| {
+        caller.data().block.chain_id as i64
+    })?;
+    linker.func_wrap("env", "get_shard_id", |caller: Caller<'_, Host>| {
+        caller.data().block.shard_id as i32
+    })?;
+    linker.func_wrap("env", "get_gas_price", |caller: Caller<'_, Host>| {
+        caller.data().block.gas_price as i64
+    })?;
+    // Calls from the harness always come straight from the transaction.
+    linker.func_wrap("env", "get_caller_depth", |_: Caller<'_, Host>| 0i32)?;
+    linker.func_wrap(
+        "env",
+        "get_sender",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> Result<i32, Error> {
+            let sender = caller.data().sender.clone();
+            write_bytes(&mut caller, ptr, sender.as_bytes())?;
+            Ok(sender.len() as i32)
+        },
+    )?;
+    // The harness only runs top-level calls, so the origin is the sender.
+    linker.func_wrap(
+        "env",
+        "get_origin",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> Result<i32, Error> {
+            let origin = caller.data().sender.clone();
+            write_bytes(&mut caller, ptr, origin.as_bytes())?;
+            Ok(origin.len() as i32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_contract_address",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> Result<i32, Error> {
+            let contract = caller.data().contract.clone();
+            write_bytes(&mut caller, ptr, contract.as_bytes())?;
+            Ok(contract.len() as i32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_value_u128",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> Result<(), Error> {
+            let value = caller.data().value.to_le_bytes();
+            write_bytes(&mut caller, ptr, &value)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_tx_hash",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> Result<(), Error> {
+            let host = caller.data();
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(host.sender.as_bytes());
+            hasher.update(host.contract.as_bytes());
+            hasher.update(&host.block.height.to_le_bytes());
+            hasher.update(&host.call_data);
+            let hash = *hasher.finalize().as_bytes();
+            write_bytes(&mut caller, ptr, &hash)
+        },
+    )?;
+
+    linker.func_wrap("env", "get_call_data_length", |caller: Caller<'_, Host>| {
+        caller.data().call_data.len() as i32
+    })?;
+    linker.func_wrap(
+        "env",
+        "read_call_data",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<i32, Error> {
+            let call_data = caller.data().call_data.clone();
+            if len < 0 || len as usize > call_data.len() {
+                return Ok(-1);
+            }
+            write_bytes(&mut caller, ptr, &call_data[..len as usize])?;
+            Ok(0)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "write_return_data",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<i32, Error> {
+            let data = read_bytes(&caller, ptr, len)?;
+            caller.data_mut().return_data = data;
+            Ok(0)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "get_code_hash",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> Result<(), Error> {
+            let code_hash = caller.data().code_hash;
+            write_bytes(&mut caller, ptr, &code_hash)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_random_beacon",
+        |_: Caller<'_, Host>, _: i64, _: i32| -1i32,
+    )?;
+    linker.func_wrap("env", "set_code", |_: Caller<'_, Host>, _: i32| -1i32)?;
+    linker.func_wrap(
+        "env",
+        "delegate_call",
+        |_: Caller<'_, Host>, _: i32, _: i32, _: i32, _: i32, _: i32| -1i32,
+    )?;
+    linker.func_wrap(
+        "env",
+        "call_contract",
+        |_: Caller<'_, Host>, _: i32, _: i32, _: i32, _: i32, _: i32, _: i32| -1i32,
+    )?;
+    linker.func_wrap(
+        "env",
+        "read_call_return",
+        |_: Caller<'_, Host>, _: i32, _: i32| -1i32,
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "vrf_verify",
+        |_: Caller<'_, Host>, _: i32, _: i32, _: i32, _: i32, _: i32| -> Result<i32, Error> {
+            Err(unsupported("vrf_verify"))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "pedersen_add",
+        |_: Caller<'_, Host>, _: i32, _: i32, _: i32| -> Result<i32, Error> {
+            Err(unsupported("pedersen_add"))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "pedersen_sub",
+        |_: Caller<'_, Host>, _: i32, _: i32, _: i32| -> Result<i32, Error> {
+            Err(unsupported("pedersen_sub"))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "pedersen_verify_opening",
+        |_: Caller<'_, Host>, _: i32, _: i64, _: i32| -> Result<i32, Error> {
+            Err(unsupported("pedersen_verify_opening"))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "range_proof_verify",
+        |_: Caller<'_, Host>, _: i32, _: i32, _: i32, _: i32, _: i32| -> Result<i32, Error> {
+            Err(unsupported("range_proof_verify"))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "groth16_verify",
+        |_: Caller<'_, Host>,
+         _: i32,
+         _: i32,
+         _: i32,
+         _: i32,
+         _: i32,
+         _: i32|
+         -> Result<i32, Error> { Err(unsupported("groth16_verify")) },
+    )?;
+
+    Ok(linker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies its call data to storage key "k", emits it as an event and
+    /// returns it, failing on empty call data.
+    const ECHO: &str = r#"
+        (module
+          (import "env" "get_call_data_length" (func $len (result i32)))
+          (import "env" "read_call_data" (func $read (param i32 i32) (result i32)))
+          (import "env" "state_write" (func $write (param i32 i32 i32 i32 i32 i32) (result i32)))
+          (import "env" "emit_event" (func $emit (param i32 i32 i32 i32)))
+          (import "env" "write_return_data" (func $ret (param i32 i32) (result i32)))
+          (import "env" "get_contract_address" (func $address (param i32) (result i32)))
+          (import "env" "log" (func $log (param i32 i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "kechoing")
+          (func (export "echo") (result i32)
+            (local $n i32) (local $account_len i32)
+            (call $log (i32.const 1) (i32.const 7))
+            (local.set $n (call $len))
+            (if (i32.eqz (local.get $n)) (then unreachable))
+            (drop (call $read (i32.const 1024) (local.get $n)))
+            (local.set $account_len (call $address (i32.const 512)))
+            (drop (call $write (i32.const 512) (local.get $account_len)
+                               (i32.const 0) (i32.const 1)
+                               (i32.const 1024) (local.get $n)))
+            (call $emit (i32.const 1) (i32.const 4) (i32.const 1024) (local.get $n))
+            (drop (call $ret (i32.const 1024) (local.get $n)))
+            (local.get $n))
+          (func (export "takes_args") (param i32)))
+    "#;
+
+    fn invocation(call_data: &[u8]) -> Invocation {
+        Invocation {
+            entrypoint: String::from("echo"),
+            contract: String::from("contract"),
+            sender: String::from("sender"),
+            value: 0,
+            call_data: call_data.to_vec(),
+            fuel: Some(1_000_000),
+        }
+    }
+
+    #[test]
+    fn calls_commit_storage_events_and_return_data() {
+        let wasm = wat::parse_str(ECHO).unwrap();
+        let mut state = State::default();
+
+        let outcome = run(&wasm, &mut state, &invocation(b"hi")).unwrap();
+        assert_eq!(outcome.trap, None);
+        assert_eq!(outcome.results, ["2"]);
+        assert_eq!(outcome.return_data, b"hi");
+        assert_eq!(outcome.storage_writes, 1);
+        assert!(outcome.fuel_consumed.unwrap() > 0);
+        assert_eq!(outcome.logs[0].message, "echoing");
+
+        assert_eq!(state.storage["contract"]["k"], hex::encode("hi"));
+        assert_eq!(state.events.len(), 1);
+        assert_eq!(state.events[0].topic, "echo");
+        assert_eq!(state.events[0].data, hex::encode("hi"));
+    }
+
+    #[test]
+    fn traps_commit_nothing_but_logs() {
+        let wasm = wat::parse_str(ECHO).unwrap();
+        let mut state = State::default();
+
+        let outcome = run(&wasm, &mut state, &invocation(b"")).unwrap();
+        assert!(outcome.trap.is_some());
+        assert!(state.storage.is_empty() && state.events.is_empty());
+        assert_eq!(state.logs.len(), 1);
+
+        let mut out_of_fuel = invocation(b"hi");
+        out_of_fuel.fuel = Some(5);
+        assert!(run(&wasm, &mut state, &out_of_fuel).unwrap().trap.is_some());
+        assert!(state.storage.is_empty());
+    }
+
+    #[test]
+    fn entrypoints_must_exist_and_take_no_parameters() {
+        let wasm = wat::parse_str(ECHO).unwrap();
+        let mut state = State::default();
+        let mut call = invocation(b"hi");
+
+        call.entrypoint = String::from("missing");
+        assert!(run(&wasm, &mut state, &call).is_err());
+        call.entrypoint = String::from("takes_args");
+        assert!(run(&wasm, &mut state, &call).is_err());
+    }
+
+    #[test]
+    fn links_every_host_function() {
+        use silica_contract_sdk::ffi::{HOST_FUNCTIONS, HostType};
+
+        let types = |types: &[HostType]| {
+            types
+                .iter()
+                .map(|ty| match ty {
+                    HostType::I32 => " i32",
+                    HostType::I64 => " i64",
+                })
+                .collect::<String>()
+        };
+        let imports: String = HOST_FUNCTIONS
+            .iter()
+            .map(|function| {
+                format!(
+                    "(import \"env\" \"{}\" (func (param{}) (result{})))",
+                    function.name,
+                    types(function.params),
+                    types(function.results)
+                )
+            })
+            .collect();
+        let wat = format!(
+            "(module {} (memory (export \"memory\") 1) (func (export \"echo\")))",
+            imports
+        );
+        let outcome = run(
+            &wat::parse_str(wat).unwrap(),
+            &mut State::default(),
+            &invocation(b""),
+        )
+        .unwrap();
+        assert_eq!(outcome.trap, None);
+    }
+
+    #[test]
+    fn memory_cannot_grow_past_the_limit() {
+        let wat = format!(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "echo") (result i32) (memory.grow (i32.const {}))))"#,
+            DEFAULT_MAX_MEMORY_PAGES
+        );
+        let outcome = run(
+            &wat::parse_str(wat).unwrap(),
+            &mut State::default(),
+            &invocation(b""),
+        )
+        .unwrap();
+        assert_eq!(outcome.results, ["-1"]);
+    }
+}
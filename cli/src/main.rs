@@ -0,0 +1,426 @@
+//! `silica-contract`: build, measure and run contracts locally
+//!
+//! Nothing here needs the network: builds honour `--offline`, and `call` runs
+//! the module in an embedded interpreter against a JSON state file.
+
+mod cargo;
+mod harness;
+mod size;
+mod state;
+
+use anyhow::{Context, Result, bail};
+use clap::{Parser, Subcommand};
+use harness::Invocation;
+use silica_contract_sdk::address::Address;
+use silica_contract_validator::Policy;
+use state::State;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_STATE: &str = "state.json";
+
+#[derive(Parser)]
+#[command(name = "silica-contract", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build contracts as release wasm with the workspace's size profile and
+    /// validate them
+    Build {
+        /// Packages to build; every contract in the workspace by default
+        #[arg(short, long = "package")]
+        packages: Vec<String>,
+        /// Pass --offline to cargo
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Check contracts against the runtime's imports, determinism rules and
+    /// their size budgets
+    Validate {
+        /// Module to check; the packages' release builds by default
+        wasm: Option<PathBuf>,
+        /// Packages whose policy to apply; every contract by default
+        #[arg(short, long = "package")]
+        packages: Vec<String>,
+        /// Pass --offline to cargo
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Break a wasm module's size down by section and export
+    Size {
+        wasm: PathBuf,
+        /// Only list the N largest exports
+        #[arg(long)]
+        top: Option<usize>,
+    },
+    /// Check a contract's ABI against its abi.json and print it
+    Abi {
+        #[arg(short, long = "package")]
+        package: String,
+        /// Accept compatible changes and rewrite abi.json
+        #[arg(long)]
+        update: bool,
+        /// Write the ABI here instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Pass --offline to cargo
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Execute an entrypoint against a local state file
+    Call {
+        wasm: PathBuf,
+        entrypoint: String,
+        #[arg(long, default_value = DEFAULT_STATE)]
+        state: PathBuf,
+        /// Contract address; derived from the code hash by default
+        #[arg(long)]
+        contract: Option<String>,
+        /// Sender address; a fixed development account by default
+        #[arg(long)]
+        sender: Option<String>,
+        /// Hex-encoded call data
+        #[arg(long, default_value = "")]
+        data: String,
+        /// Native value attached to the call
+        #[arg(long, default_value_t = 0)]
+        value: u128,
+        /// Fuel limit; unlimited by default
+        #[arg(long)]
+        fuel: Option<u64>,
+        /// Set the block height before the call
+        #[arg(long)]
+        height: Option<u64>,
+        /// Set the block timestamp before the call
+        #[arg(long)]
+        timestamp: Option<u64>,
+    },
+    /// Dump storage, events and logs from a local state file
+    Inspect {
+        #[arg(long, default_value = DEFAULT_STATE)]
+        state: PathBuf,
+        /// Only show this contract
+        #[arg(long)]
+        contract: Option<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Build { packages, offline } => build(&packages, offline),
+        Command::Validate {
+            wasm,
+            packages,
+            offline,
+        } => validate(wasm.as_deref(), &packages, offline),
+        Command::Size { wasm, top } => size(&wasm, top),
+        Command::Abi {
+            package,
+            update,
+            output,
+            offline,
+        } => abi(&package, update, output.as_deref(), offline),
+        Command::Call {
+            wasm,
+            entrypoint,
+            state,
+            contract,
+            sender,
+            data,
+            value,
+            fuel,
+            height,
+            timestamp,
+        } => {
+            let code = read_wasm(&wasm)?;
+            let invocation = Invocation {
+                entrypoint,
+                contract: match contract {
+                    Some(contract) => parse_address(&contract)?,
+                    None => Address::contract(*blake3::hash(&code).as_bytes()).to_string(),
+                },
+                sender: match sender {
+                    Some(sender) => parse_address(&sender)?,
+                    None => Address::from_public_key(&[0; 32]).to_string(),
+                },
+                value,
+                call_data: hex::decode(data.trim_start_matches("0x"))
+                    .context("--data is not valid hex")?,
+                fuel,
+            };
+            call(&code, &state, &invocation, height, timestamp)
+        }
+        Command::Inspect { state, contract } => inspect(&state, contract.as_deref()),
+    }
+}
+
+fn build(packages: &[String], offline: bool) -> Result<()> {
+    let workspace = cargo::Workspace::load(offline)?;
+    let packages = select(&workspace, packages)?;
+    let mut failed = 0;
+    for (package, artifact) in packages
+        .iter()
+        .zip(cargo::build(&workspace, &packages, offline)?)
+    {
+        let wasm = read_wasm(&artifact)?;
+        println!(
+            "{:<28} {:>9} bytes  {}",
+            package.name,
+            wasm.len(),
+            artifact.display()
+        );
+        if !report_violations(&wasm, &package.policy()?) {
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        bail!(
+            "{} of {} contracts failed validation",
+            failed,
+            packages.len()
+        );
+    }
+    Ok(())
+}
+
+fn validate(wasm: Option<&Path>, packages: &[String], offline: bool) -> Result<()> {
+    let mut failed = 0;
+    let total;
+    if let Some(path) = wasm {
+        let policy = match packages {
+            [] => Policy::default(),
+            [package] => cargo::Workspace::load(offline)?
+                .package(package)?
+                .policy()?,
+            _ => bail!(
+                "give at most one package whose policy applies to {}",
+                path.display()
+            ),
+        };
+        println!("{}", path.display());
+        total = 1;
+        if !report_violations(&read_wasm(path)?, &policy) {
+            failed += 1;
+        }
+    } else {
+        let workspace = cargo::Workspace::load(offline)?;
+        let packages = select(&workspace, packages)?;
+        total = packages.len();
+        for package in packages {
+            let artifact = workspace.artifact(package)?;
+            let wasm = fs::read(&artifact).with_context(|| {
+                format!(
+                    "cannot read {}; run `silica-contract build` first",
+                    artifact.display()
+                )
+            })?;
+            println!("{}", package.name);
+            if !report_violations(&wasm, &package.policy()?) {
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} contracts failed validation", failed, total);
+    }
+    Ok(())
+}
+
+/// The named packages, or every contract in the workspace.
+fn select<'a>(
+    workspace: &'a cargo::Workspace,
+    names: &[String],
+) -> Result<Vec<&'a cargo::Package>> {
+    let packages = if names.is_empty() {
+        workspace.contracts()
+    } else {
+        names
+            .iter()
+            .map(|name| workspace.package(name))
+            .collect::<Result<_>>()?
+    };
+    if packages.is_empty() {
+        bail!("no contract packages in the workspace");
+    }
+    Ok(packages)
+}
+
+/// Print every violation of `policy`, returning whether there were none.
+fn report_violations(wasm: &[u8], policy: &Policy) -> bool {
+    let violations = silica_contract_validator::validate(wasm, policy);
+    for violation in &violations {
+        println!("  error: {}", violation);
+    }
+    violations.is_empty()
+}
+
+fn size(path: &Path, top: Option<usize>) -> Result<()> {
+    let report = size::analyze(&read_wasm(path)?)?;
+    println!("{}: {} bytes", path.display(), report.total);
+    println!();
+    println!("{:<32} {:>9}", "section", "bytes");
+    for (name, bytes) in &report.sections {
+        println!("{:<32} {:>9}", name, bytes);
+    }
+    println!();
+    println!(
+        "{:<32} {:>9} {:>11} {:>9}",
+        "export", "own", "reachable", "functions"
+    );
+    let exports = &report.exports[..top.unwrap_or(usize::MAX).min(report.exports.len())];
+    for export in exports {
+        println!(
+            "{:<32} {:>9} {:>11} {:>9}",
+            export.name, export.own, export.reachable, export.functions
+        );
+    }
+    Ok(())
+}
+
+fn abi(package: &str, update: bool, output: Option<&Path>, offline: bool) -> Result<()> {
+    let workspace = cargo::Workspace::load(offline)?;
+    let path = cargo::abi(workspace.package(package)?, update, offline)?;
+    match output {
+        Some(output) => {
+            fs::copy(&path, output)
+                .with_context(|| format!("cannot write {}", output.display()))?;
+        }
+        None => print!(
+            "{}",
+            fs::read_to_string(&path).with_context(|| format!("cannot read {}", path.display()))?
+        ),
+    }
+    Ok(())
+}
+
+fn call(
+    code: &[u8],
+    path: &Path,
+    invocation: &Invocation,
+    height: Option<u64>,
+    timestamp: Option<u64>,
+) -> Result<()> {
+    let mut state = State::load(path)?;
+    if let Some(height) = height {
+        state.block.height = height;
+    }
+    if let Some(timestamp) = timestamp {
+        state.block.timestamp = timestamp;
+    }
+
+    let outcome = harness::run(code, &mut state, invocation)?;
+    state.save(path)?;
+
+    for log in &outcome.logs {
+        match &log.level {
+            Some(level) => println!("log [{}] {}{}", level, log.message, fields(&log.fields)),
+            None => println!("log {}", log.message),
+        }
+    }
+    for event in &outcome.events {
+        println!("event {} {}", event.topic, event.data);
+    }
+    if let Some(fuel) = outcome.fuel_consumed {
+        println!("fuel {}", fuel);
+    }
+    if let Some(trap) = outcome.trap {
+        bail!(
+            "`{}` trapped: {}; state left unchanged",
+            invocation.entrypoint,
+            trap
+        );
+    }
+    println!("storage writes {}", outcome.storage_writes);
+    if !outcome.results.is_empty() {
+        println!("result {}", outcome.results.join(", "));
+    }
+    println!("return {}", hex::encode(&outcome.return_data));
+    Ok(())
+}
+
+fn inspect(path: &Path, contract: Option<&str>) -> Result<()> {
+    if !path.exists() {
+        bail!("no state file at {}", path.display());
+    }
+    let state = State::load(path)?;
+    let shown = |account: &str| contract.is_none_or(|contract| contract == account);
+
+    println!(
+        "block height {} timestamp {} chain {} shard {}",
+        state.block.height, state.block.timestamp, state.block.chain_id, state.block.shard_id
+    );
+    for (account, entries) in state.storage_bytes()? {
+        if !shown(&account) {
+            continue;
+        }
+        println!();
+        println!("storage {} ({} keys)", account, entries.len());
+        for (key, value) in entries {
+            match std::str::from_utf8(&value) {
+                Ok(text) if text.chars().all(|c| !c.is_control()) => {
+                    println!("  {} = {} \"{}\"", key, hex::encode(&value), text)
+                }
+                _ => println!("  {} = {}", key, hex::encode(&value)),
+            }
+        }
+    }
+
+    let events: Vec<_> = state.events.iter().filter(|e| shown(&e.contract)).collect();
+    if !events.is_empty() {
+        println!();
+        println!("events");
+        for event in events {
+            println!(
+                "  #{} {} {} {}",
+                event.block_height, event.contract, event.topic, event.data
+            );
+        }
+    }
+    let logs: Vec<_> = state.logs.iter().filter(|l| shown(&l.contract)).collect();
+    if !logs.is_empty() {
+        println!();
+        println!("logs");
+        for log in logs {
+            println!(
+                "  {} [{}] {}{}",
+                log.contract,
+                log.level.as_deref().unwrap_or("-"),
+                log.message,
+                fields(&log.fields)
+            );
+        }
+    }
+    let transfers: Vec<_> = state.transfers.iter().filter(|t| shown(&t.from)).collect();
+    if !transfers.is_empty() {
+        println!();
+        println!("transfers");
+        for transfer in transfers {
+            println!(
+                "  #{} {} -> {} {}",
+                transfer.block_height, transfer.from, transfer.to, transfer.amount
+            );
+        }
+    }
+    Ok(())
+}
+
+fn fields(fields: &[(String, String)]) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| format!(" {}={}", key, value))
+        .collect()
+}
+
+fn read_wasm(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("cannot read {}", path.display()))
+}
+
+fn parse_address(value: &str) -> Result<String> {
+    Address::parse(value)
+        .map(|address| address.to_string())
+        .map_err(|error| anyhow::anyhow!("invalid address `{}`: {:?}", value, error))
+}
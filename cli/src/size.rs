@@ -0,0 +1,236 @@
+//! Code size breakdown
+//!
+//! Attributes function bodies to exports: each export is charged for its own
+//! body and for everything reachable from it through direct calls. Indirect
+//! calls are assumed to reach every function in an element segment, so
+//! trait-object heavy code shows up as shared rather than disappearing.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashMap};
+use wasmparser::{ElementItems, ExternalKind, Operator, Parser, Payload, TypeRef};
+
+/// Sizes for one wasm module.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub total: usize,
+    /// `(section name, bytes)` in module order
+    pub sections: Vec<(String, usize)>,
+    pub exports: Vec<ExportSize>,
+}
+
+/// Code attributed to one exported function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportSize {
+    pub name: String,
+    /// Bytes of the export's own body
+    pub own: usize,
+    /// Bytes of every body reachable from the export, its own included
+    pub reachable: usize,
+    /// Number of functions reachable from the export, itself included
+    pub functions: usize,
+}
+
+struct Body {
+    size: usize,
+    calls: Vec<u32>,
+    calls_indirect: bool,
+}
+
+/// Measure `wasm`.
+pub fn analyze(wasm: &[u8]) -> Result<Report> {
+    let mut report = Report {
+        total: wasm.len(),
+        ..Report::default()
+    };
+    let mut imported_functions = 0u32;
+    let mut bodies = Vec::new();
+    let mut table_functions = BTreeSet::new();
+    let mut exports = Vec::new();
+
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload.context("invalid wasm module")?;
+        if let Some((id, range)) = payload.as_section() {
+            report
+                .sections
+                .push((section_name(&payload, id), range.end - range.start));
+        }
+        match payload {
+            Payload::ImportSection(reader) => {
+                for import in reader.into_imports() {
+                    if matches!(import?.ty, TypeRef::Func(_)) {
+                        imported_functions += 1;
+                    }
+                }
+            }
+            Payload::ElementSection(reader) => {
+                for element in reader {
+                    match element?.items {
+                        ElementItems::Functions(functions) => {
+                            for function in functions {
+                                table_functions.insert(function?);
+                            }
+                        }
+                        ElementItems::Expressions(_, expressions) => {
+                            for expression in expressions {
+                                for operator in expression?.get_operators_reader() {
+                                    if let Operator::RefFunc { function_index } = operator? {
+                                        table_functions.insert(function_index);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    if export.kind == ExternalKind::Func {
+                        exports.push((export.name.to_string(), export.index));
+                    }
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                let range = body.range();
+                let mut calls = Vec::new();
+                let mut calls_indirect = false;
+                for operator in body.get_operators_reader()? {
+                    match operator? {
+                        Operator::Call { function_index }
+                        | Operator::ReturnCall { function_index } => calls.push(function_index),
+                        Operator::CallIndirect { .. } | Operator::ReturnCallIndirect { .. } => {
+                            calls_indirect = true
+                        }
+                        _ => {}
+                    }
+                }
+                bodies.push(Body {
+                    size: range.end - range.start,
+                    calls,
+                    calls_indirect,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let body = |index: u32| {
+        index
+            .checked_sub(imported_functions)
+            .and_then(|local| bodies.get(local as usize))
+    };
+    let mut seen = HashMap::new();
+    for (name, index) in exports {
+        let reachable = seen
+            .entry(index)
+            .or_insert_with(|| reachable_from(index, &body, &table_functions))
+            .clone();
+        report.exports.push(ExportSize {
+            name,
+            own: body(index).map_or(0, |body| body.size),
+            reachable: reachable
+                .iter()
+                .filter_map(|index| body(*index))
+                .map(|body| body.size)
+                .sum(),
+            functions: reachable.len(),
+        });
+    }
+    report
+        .exports
+        .sort_by(|a, b| b.reachable.cmp(&a.reachable).then(a.name.cmp(&b.name)));
+    Ok(report)
+}
+
+fn reachable_from<'a>(
+    start: u32,
+    body: &impl Fn(u32) -> Option<&'a Body>,
+    table_functions: &BTreeSet<u32>,
+) -> BTreeSet<u32> {
+    let mut reachable = BTreeSet::new();
+    let mut pending = vec![start];
+    while let Some(index) = pending.pop() {
+        if !reachable.insert(index) {
+            continue;
+        }
+        // Imports have no body and are not counted.
+        let Some(body) = body(index) else {
+            reachable.remove(&index);
+            continue;
+        };
+        pending.extend(&body.calls);
+        if body.calls_indirect {
+            pending.extend(table_functions);
+        }
+    }
+    reachable
+}
+
+fn section_name(payload: &Payload<'_>, id: u8) -> String {
+    match payload {
+        Payload::CustomSection(section) => format!("custom \"{}\"", section.name()),
+        _ => match id {
+            1 => "type",
+            2 => "import",
+            3 => "function",
+            4 => "table",
+            5 => "memory",
+            6 => "global",
+            7 => "export",
+            8 => "start",
+            9 => "element",
+            10 => "code",
+            11 => "data",
+            12 => "data count",
+            13 => "tag",
+            _ => "unknown",
+        }
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"
+        (module
+          (import "env" "log" (func $log (param i32 i32)))
+          (type $thunk (func))
+          (table 1 funcref)
+          (elem (i32.const 0) $callback)
+          (func $shared (call $log (i32.const 0) (i32.const 0)))
+          (func $callback (nop))
+          (func $big (drop (i32.add (i32.const 1) (i32.add (i32.const 2) (i32.const 3)))))
+          (func (export "small") (call $shared))
+          (func (export "large") (call $shared) (call $big))
+          (func (export "dynamic") (call_indirect (type $thunk) (i32.const 0))))
+    "#;
+
+    fn export<'a>(report: &'a Report, name: &str) -> &'a ExportSize {
+        report.exports.iter().find(|e| e.name == name).unwrap()
+    }
+
+    #[test]
+    fn exports_are_charged_for_reachable_bodies() {
+        let wasm = wat::parse_str(MODULE).unwrap();
+        let report = analyze(&wasm).unwrap();
+
+        assert_eq!(report.total, wasm.len());
+        assert!(report.sections.iter().any(|(name, _)| name == "code"));
+        assert_eq!(report.exports[0].name, "large");
+
+        let small = export(&report, "small");
+        let large = export(&report, "large");
+        assert_eq!(small.functions, 2);
+        assert_eq!(large.functions, 3);
+        assert!(large.reachable > small.reachable);
+        assert!(small.reachable > small.own);
+        assert_eq!(export(&report, "dynamic").functions, 2);
+    }
+
+    #[test]
+    fn rejects_invalid_modules() {
+        assert!(analyze(b"not wasm").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+//! Local chain state kept between `call`s
+//!
+//! The state file is plain JSON so it can be inspected, edited and checked
+//! into a test fixture. Byte strings are hex-encoded.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Block environment seen by every call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Block {
+    pub height: u64,
+    pub timestamp: u64,
+    pub chain_id: u64,
+    pub shard_id: u32,
+    pub gas_price: u64,
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Self {
+            height: 1,
+            timestamp: 1_700_000_000,
+            chain_id: 1,
+            shard_id: 0,
+            gas_price: 1,
+        }
+    }
+}
+
+/// An event emitted by a successful call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventEntry {
+    pub block_height: u64,
+    pub contract: String,
+    pub topic: String,
+    /// Hex-encoded event data
+    pub data: String,
+}
+
+/// A log line or structured log record written by a call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub contract: String,
+    /// Level of a structured record; `None` for plain `log` messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<(String, String)>,
+}
+
+/// A token transfer made by a successful call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferEntry {
+    pub block_height: u64,
+    pub from: String,
+    pub to: String,
+    /// Decimal amount, as JSON numbers cannot hold every `u128`
+    pub amount: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub block: Block,
+    /// Storage of every contract: address -> key -> hex-encoded value
+    pub storage: BTreeMap<String, BTreeMap<String, String>>,
+    pub events: Vec<EventEntry>,
+    pub logs: Vec<LogEntry>,
+    pub transfers: Vec<TransferEntry>,
+}
+
+impl State {
+    /// Load `path`, starting from an empty chain if it does not exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("invalid state file {}", path.display())),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => {
+                Err(error).with_context(|| format!("cannot read state file {}", path.display()))
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")
+            .with_context(|| format!("cannot write state file {}", path.display()))
+    }
+
+    /// Decoded storage of every contract.
+    pub fn storage_bytes(&self) -> Result<BTreeMap<String, BTreeMap<String, Vec<u8>>>> {
+        self.storage
+            .iter()
+            .map(|(account, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = hex::decode(value).with_context(|| {
+                            format!("storage value {}/{} is not hex", account, key)
+                        })?;
+                        Ok((key.clone(), value))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((account.clone(), entries))
+            })
+            .collect()
+    }
+
+    /// Replace the stored storage with `storage`, dropping empty accounts.
+    pub fn set_storage_bytes(&mut self, storage: BTreeMap<String, BTreeMap<String, Vec<u8>>>) {
+        self.storage = storage
+            .into_iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .map(|(account, entries)| {
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| (key, hex::encode(value)))
+                    .collect();
+                (account, entries)
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,61 @@
+//! Decoding of events emitted with the SDK's `event!` macro
+//!
+//! Such events carry a postcard-encoded struct whose fields are the
+//! `Display` strings of the emitted values: numbers in decimal, ids and byte
+//! strings in hex, addresses in bech32m. The decoded structs keep them as
+//! strings so nothing is lost when a contract changes a field's formatting.
+
+use silica_contract_sdk::error::ContractError;
+
+pub(crate) fn unknown_topic(topic: &str) -> ContractError {
+    ContractError::InvalidArgument(format!("Unknown event topic: {}", topic))
+}
+
+/// Define one struct per event and an `Event` enum that decodes any of them.
+///
+/// Names after the `;` are events the SDK emits for the contract, such as
+/// `AccessControl`'s, whose typed structs are already in scope under the
+/// topic name.
+macro_rules! string_events {
+    (
+        $($(#[$meta:meta])* $name:ident { $($field:ident),* $(,)? }),* $(,)?
+        $(; $($typed:ident),* $(,)?)?
+    ) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+            pub struct $name {
+                $(pub $field: String),*
+            }
+        )*
+
+        /// Any event the contract emits.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum Event {
+            $($name($name),)*
+            $($($typed($typed),)*)?
+        }
+
+        impl Event {
+            /// Decode the data of an event published under `topic`.
+            pub fn decode(
+                topic: &str,
+                data: &[u8],
+            ) -> silica_contract_sdk::error::ContractResult<Self> {
+                match topic {
+                    $(stringify!($name) => $crate::decode(data).map(Event::$name),)*
+                    $($(stringify!($typed) => $crate::decode(data).map(Event::$typed),)*)?
+                    _ => Err($crate::events::unknown_topic(topic)),
+                }
+            }
+
+            /// Topic the event is published under.
+            pub fn topic(&self) -> &'static str {
+                match self {
+                    $(Event::$name(_) => stringify!($name),)*
+                    $($(Event::$typed(_) => stringify!($typed),)*)?
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,99 @@
+//! Trusted forwarder calls, request signing and events
+//!
+//! A relayed call is built in three steps: wrap the target's [`Call`] in a
+//! [`ForwardRequest`] with [`request`], have the signer sign its [`digest`],
+//! and submit [`execute`] to the forwarder. The forwarder passes the target's
+//! return data through, so `execute` decodes it as the wrapped call would.
+
+use crate::{Address, Call};
+use serde::Serialize;
+use silica_contract_sdk::crypto::typed_data::{Domain, typed_data_hash};
+use silica_contract_sdk::error::ContractResult;
+
+pub use silica_contract_sdk::forwarder::{
+    DOMAIN_NAME, DOMAIN_VERSION, ForwardRequest, RequestForwarded,
+};
+
+/// Signing domain of the forwarder deployed at `forwarder` on `chain_id`.
+pub fn domain(forwarder: &Address, chain_id: u64) -> Domain {
+    Domain::new(DOMAIN_NAME, DOMAIN_VERSION, chain_id, forwarder)
+}
+
+/// Request for `signer` to make `call` on `target`.
+pub fn request<R>(
+    signer: &Address,
+    target: &Address,
+    call: &Call<R>,
+    nonce: u64,
+    deadline: u64,
+) -> ForwardRequest {
+    ForwardRequest {
+        signer: signer.clone(),
+        target: target.clone(),
+        entrypoint: call.entrypoint().to_string(),
+        data: call.data().to_vec(),
+        nonce,
+        deadline,
+    }
+}
+
+/// Digest the signer signs for `request` under `domain`.
+pub fn digest(domain: &Domain, request: &ForwardRequest) -> ContractResult<[u8; 32]> {
+    typed_data_hash(domain, request)
+}
+
+/// `initialize(chain_id)`
+pub fn initialize(chain_id: u64) -> ContractResult<Call<()>> {
+    Call::new("initialize", &chain_id)
+}
+
+/// `execute(request, signer_key, signature)` relaying a call that returns `R`
+pub fn execute<R>(
+    request: &ForwardRequest,
+    signer_key: &[u8; 32],
+    signature: &[u8; 64],
+) -> ContractResult<Call<R>> {
+    #[derive(Serialize)]
+    struct ExecuteArgs<'a> {
+        request: &'a ForwardRequest,
+        signer_key: &'a [u8; 32],
+        signature: &'a [u8],
+    }
+
+    Call::new(
+        "execute",
+        &ExecuteArgs {
+            request,
+            signer_key,
+            signature,
+        },
+    )
+}
+
+/// `get_nonce(signer)`, the nonce the signer's next request must carry
+pub fn get_nonce(signer: &Address) -> ContractResult<Call<u64>> {
+    Call::new("get_nonce", signer)
+}
+
+/// Any event the forwarder emits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    RequestForwarded(RequestForwarded),
+}
+
+impl Event {
+    /// Decode the data of an event published under `topic`.
+    pub fn decode(topic: &str, data: &[u8]) -> ContractResult<Self> {
+        match topic {
+            "RequestForwarded" => crate::decode(data).map(Event::RequestForwarded),
+            _ => Err(crate::events::unknown_topic(topic)),
+        }
+    }
+
+    /// Topic the event is published under.
+    pub fn topic(&self) -> &'static str {
+        match self {
+            Event::RequestForwarded(_) => "RequestForwarded",
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! DAO governor calls and events
+
+use crate::{Address, Call};
+use silica_contract_sdk::error::ContractResult;
+
+pub use silica_contract_sdk::forwarder::TrustedForwarderSet;
+pub use silica_contract_sdk::security::access_control::{OwnershipTransferred, RoleGranted};
+
+/// Vote choices taken by [`cast_vote`]
+pub mod choice {
+    pub const AGAINST: u8 = 0;
+    pub const FOR: u8 = 1;
+    pub const ABSTAIN: u8 = 2;
+}
+
+/// Proposal states returned by [`state`](fn@state)
+pub mod proposal_state {
+    pub const PENDING: u8 = 0;
+    pub const ACTIVE: u8 = 1;
+    pub const SUCCEEDED: u8 = 2;
+    pub const DEFEATED: u8 = 3;
+    pub const EXECUTED: u8 = 4;
+    pub const CANCELLED: u8 = 5;
+    pub const EXPIRED: u8 = 6;
+}
+
+/// Governor parameters passed to [`initialize`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct GovernorParams {
+    pub name: String,
+    pub governance_token: Address,
+    pub timelock_contract: Address,
+    pub voting_delay: u64,
+    pub voting_period: u64,
+    pub proposal_threshold: u64,
+    pub quorum_votes: u64,
+}
+
+/// `initialize(name, governance_token, timelock_contract, voting_delay,
+/// voting_period, proposal_threshold, quorum_votes)`
+pub fn initialize(params: &GovernorParams) -> ContractResult<Call<()>> {
+    Call::new("initialize", params)
+}
+
+/// `propose(title, description, targets, values, calldatas)`, returning the
+/// proposal id
+pub fn propose(
+    title: &str,
+    description: &str,
+    targets: &[Address],
+    values: &[u64],
+    calldatas: &[Vec<u8>],
+) -> ContractResult<Call<u64>> {
+    Call::new("propose", &(title, description, targets, values, calldatas))
+}
+
+/// `cast_vote(proposal_id, choice)`, one of the [`choice`] constants
+pub fn cast_vote(proposal_id: u64, choice: u8) -> ContractResult<Call<bool>> {
+    Call::new("cast_vote", &(proposal_id, choice))
+}
+
+/// `delegate(delegatee)`
+pub fn delegate(delegatee: &Address) -> ContractResult<Call<()>> {
+    Call::new("delegate", delegatee)
+}
+
+/// `execute(proposal_id)`
+pub fn execute(proposal_id: u64) -> ContractResult<Call<()>> {
+    Call::new("execute", &proposal_id)
+}
+
+/// `cancel(proposal_id)`
+pub fn cancel(proposal_id: u64) -> ContractResult<Call<()>> {
+    Call::new("cancel", &proposal_id)
+}
+
+/// `set_governance_token(new_token)`
+pub fn set_governance_token(new_token: &Address) -> ContractResult<Call<()>> {
+    Call::new("set_governance_token", new_token)
+}
+
+/// `set_timelock_contract(new_timelock)`
+pub fn set_timelock_contract(new_timelock: &Address) -> ContractResult<Call<()>> {
+    Call::new("set_timelock_contract", new_timelock)
+}
+
+/// `set_trusted_forwarder(forwarder, trusted)`
+pub fn set_trusted_forwarder(forwarder: &Address, trusted: bool) -> ContractResult<Call<()>> {
+    Call::new("set_trusted_forwarder", &(forwarder, trusted))
+}
+
+/// `state(proposal_id)`, one of the [`proposal_state`] constants
+pub fn state(proposal_id: u64) -> ContractResult<Call<u8>> {
+    Call::new("state", &proposal_id)
+}
+
+/// `has_voted(proposal_id, voter)`
+pub fn has_voted(proposal_id: u64, voter: &Address) -> ContractResult<Call<bool>> {
+    Call::new("has_voted", &(proposal_id, voter))
+}
+
+/// `get_voting_power(account)`
+pub fn get_voting_power(account: &Address) -> ContractResult<Call<u64>> {
+    Call::new("get_voting_power", account)
+}
+
+string_events! {
+    GovernorInitialized {
+        name,
+        governance_token,
+        timelock_contract,
+        voting_delay,
+        voting_period,
+        proposal_threshold,
+        quorum_votes,
+    },
+    ProposalCreated { proposal_id, proposer, title, start_time, end_time },
+    VoteCast { proposal_id, voter, choice, weight },
+    DelegateChanged { delegator, delegatee, new_weight },
+    ProposalExecuted { proposal_id, executor, timelock_id },
+    ProposalCancelled { proposal_id, canceller },
+    GovernanceTokenUpdated { new_token },
+    TimelockContractUpdated { new_timelock },
+    ;
+    OwnershipTransferred,
+    RoleGranted,
+    TrustedForwarderSet,
+}
@@ -0,0 +1,109 @@
+//! # Silica Contract Client
+//!
+//! Off-chain counterpart of the example contracts: typed builders for their
+//! call data, decoders for their return data and events, and the id and digest
+//! helpers the contracts themselves use. Nothing here talks to a node; submit
+//! a [`Call`] with whatever transport the service already has.
+//!
+//! ```rust
+//! use silica_contract_client::{Address, timelock};
+//!
+//! let target = Address::contract([1; 32]);
+//! let salt = [7u8; 32];
+//! let call = timelock::schedule(&target, 0, &[1, 2, 3], None, salt, 172_800).unwrap();
+//! assert_eq!(call.entrypoint(), "schedule");
+//!
+//! // The id the timelock will return, known before the call is sent.
+//! let id = timelock::hash_operation(&target, 0, &[1, 2, 3], &None, &salt);
+//! # let return_data = postcard::to_allocvec(&id).unwrap();
+//! assert_eq!(call.decode_return(&return_data).unwrap(), id);
+//! ```
+//!
+//! Call data is the postcard encoding of the entrypoint's arguments in
+//! declaration order, which is how the contracts' ABI files describe them.
+
+#[macro_use]
+mod events;
+
+pub mod forwarder;
+pub mod governor;
+pub mod multisig;
+pub mod timelock;
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::error::{ContractError, ContractResult};
+
+pub use silica_contract_sdk::address::Address;
+
+/// Encoded call to one entrypoint, returning `R`.
+pub struct Call<R> {
+    entrypoint: &'static str,
+    data: Vec<u8>,
+    returns: PhantomData<fn() -> R>,
+}
+
+impl<R> Call<R> {
+    /// Encode `args` for `entrypoint`.
+    ///
+    /// Pass a tuple for several arguments, a single value for one and `()` for
+    /// none.
+    pub fn new<A: Serialize + ?Sized>(entrypoint: &'static str, args: &A) -> ContractResult<Self> {
+        Ok(Self {
+            entrypoint,
+            data: encode(args)?,
+            returns: PhantomData,
+        })
+    }
+
+    /// Name of the exported function to invoke.
+    pub fn entrypoint(&self) -> &'static str {
+        self.entrypoint
+    }
+
+    /// Call data to send with the invocation.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the call data.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl<R: DeserializeOwned> Call<R> {
+    /// Decode the return data of this call.
+    pub fn decode_return(&self, return_data: &[u8]) -> ContractResult<R> {
+        decode(return_data)
+    }
+}
+
+impl<R> Clone for Call<R> {
+    fn clone(&self) -> Self {
+        Self {
+            entrypoint: self.entrypoint,
+            data: self.data.clone(),
+            returns: PhantomData,
+        }
+    }
+}
+
+impl<R> fmt::Debug for Call<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Call")
+            .field("entrypoint", &self.entrypoint)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+fn encode<T: Serialize + ?Sized>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> ContractResult<T> {
+    postcard::from_bytes(bytes).map_err(|_| ContractError::DeserializationFailed)
+}
@@ -0,0 +1,102 @@
+//! Multisig wallet calls and events
+
+use crate::{Address, Call};
+use silica_contract_sdk::error::ContractResult;
+
+pub use silica_contract_sdk::forwarder::TrustedForwarderSet;
+pub use silica_contract_sdk::security::access_control::{OwnershipTransferred, RoleGranted};
+
+/// `initialize(owners, threshold)`
+pub fn initialize(owners: &[Address], threshold: u64) -> ContractResult<Call<()>> {
+    Call::new("initialize", &(owners, threshold))
+}
+
+/// `submit_transaction(to, value, data, description)`, returning the
+/// transaction id
+pub fn submit_transaction(
+    to: &Address,
+    value: u64,
+    data: &[u8],
+    description: &str,
+) -> ContractResult<Call<u64>> {
+    Call::new("submit_transaction", &(to, value, data, description))
+}
+
+/// `approve_transaction(tx_id)`
+pub fn approve_transaction(tx_id: u64) -> ContractResult<Call<()>> {
+    Call::new("approve_transaction", &tx_id)
+}
+
+/// `revoke_approval(tx_id)`
+pub fn revoke_approval(tx_id: u64) -> ContractResult<Call<()>> {
+    Call::new("revoke_approval", &tx_id)
+}
+
+/// `execute_transaction(tx_id)`
+pub fn execute_transaction(tx_id: u64) -> ContractResult<Call<()>> {
+    Call::new("execute_transaction", &tx_id)
+}
+
+/// `cancel_transaction(tx_id)`
+pub fn cancel_transaction(tx_id: u64) -> ContractResult<Call<()>> {
+    Call::new("cancel_transaction", &tx_id)
+}
+
+/// `set_time_lock(tx_id, delay_seconds)`
+pub fn set_time_lock(tx_id: u64, delay_seconds: u64) -> ContractResult<Call<()>> {
+    Call::new("set_time_lock", &(tx_id, delay_seconds))
+}
+
+/// `set_trusted_forwarder(forwarder, trusted)`
+pub fn set_trusted_forwarder(forwarder: &Address, trusted: bool) -> ContractResult<Call<()>> {
+    Call::new("set_trusted_forwarder", &(forwarder, trusted))
+}
+
+/// `is_owner(address)`
+pub fn is_owner(address: &Address) -> ContractResult<Call<bool>> {
+    Call::new("is_owner", address)
+}
+
+/// `get_threshold()`
+pub fn get_threshold() -> ContractResult<Call<u64>> {
+    Call::new("get_threshold", &())
+}
+
+/// `get_owner_count()`
+pub fn get_owner_count() -> ContractResult<Call<u64>> {
+    Call::new("get_owner_count", &())
+}
+
+/// `get_approval_count(tx_id)`
+pub fn get_approval_count(tx_id: u64) -> ContractResult<Call<u64>> {
+    Call::new("get_approval_count", &tx_id)
+}
+
+/// `has_approved(tx_id, owner)`
+pub fn has_approved(tx_id: u64, owner: &Address) -> ContractResult<Call<bool>> {
+    Call::new("has_approved", &(tx_id, owner))
+}
+
+/// `can_execute(tx_id)`
+pub fn can_execute(tx_id: u64) -> ContractResult<Call<bool>> {
+    Call::new("can_execute", &tx_id)
+}
+
+/// `get_transaction_count()`
+pub fn get_transaction_count() -> ContractResult<Call<u64>> {
+    Call::new("get_transaction_count", &())
+}
+
+string_events! {
+    WalletCreated { owners, threshold },
+    TransactionSubmitted { tx_id, proposer, to, value, description },
+    TransactionApproved { tx_id, approver, approval_count },
+    ApprovalRevoked { tx_id, owner },
+    TransactionExecuted { tx_id, executor },
+    TransactionCancelled { tx_id },
+    TimeLockSet { tx_id, unlock_time },
+    ;
+    OwnershipTransferred,
+    RoleGranted,
+    TrustedForwarderSet,
+}
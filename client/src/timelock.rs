@@ -0,0 +1,160 @@
+//! Timelock controller calls and events
+
+use crate::{Address, Call};
+use silica_contract_sdk::error::ContractResult;
+
+pub use silica_contract_sdk::security::access_control::{
+    OwnershipTransferred, RoleGranted, RoleRevoked,
+};
+pub use silica_contract_sdk::timelock::{
+    OperationState, hash_operation, hash_operation_batch, state,
+};
+
+/// Role id of proposers, as taken by [`grant_role`] and [`revoke_role`]
+pub const PROPOSER_ROLE: u8 = 0;
+/// Role id of executors
+pub const EXECUTOR_ROLE: u8 = 1;
+/// Role id of cancellers
+pub const CANCELLER_ROLE: u8 = 2;
+/// Role id of administrators
+pub const ADMIN_ROLE: u8 = 3;
+
+/// `initialize(min_delay, proposers, executors, admin)`
+pub fn initialize(
+    min_delay: u64,
+    proposers: &[Address],
+    executors: &[Address],
+    admin: &Address,
+) -> ContractResult<Call<()>> {
+    Call::new("initialize", &(min_delay, proposers, executors, admin))
+}
+
+/// `schedule(target, value, data, predecessor, salt, delay)`, returning the
+/// operation id ([`hash_operation`] of the same parameters)
+pub fn schedule(
+    target: &Address,
+    value: u64,
+    data: &[u8],
+    predecessor: Option<[u8; 32]>,
+    salt: [u8; 32],
+    delay: u64,
+) -> ContractResult<Call<[u8; 32]>> {
+    Call::new("schedule", &(target, value, data, predecessor, salt, delay))
+}
+
+/// `schedule_batch(targets, values, datas, predecessor, salt, delay)`,
+/// returning the batch id ([`hash_operation_batch`])
+pub fn schedule_batch(
+    targets: &[Address],
+    values: &[u64],
+    datas: &[Vec<u8>],
+    predecessor: Option<[u8; 32]>,
+    salt: [u8; 32],
+    delay: u64,
+) -> ContractResult<Call<[u8; 32]>> {
+    Call::new(
+        "schedule_batch",
+        &(targets, values, datas, predecessor, salt, delay),
+    )
+}
+
+/// `cancel(id)`
+pub fn cancel(id: [u8; 32]) -> ContractResult<Call<()>> {
+    Call::new("cancel", &id)
+}
+
+/// `execute(target, value, data, predecessor, salt)`
+pub fn execute(
+    target: &Address,
+    value: u64,
+    data: &[u8],
+    predecessor: Option<[u8; 32]>,
+    salt: [u8; 32],
+) -> ContractResult<Call<()>> {
+    Call::new("execute", &(target, value, data, predecessor, salt))
+}
+
+/// `execute_batch(targets, values, datas, predecessor, salt)`
+pub fn execute_batch(
+    targets: &[Address],
+    values: &[u64],
+    datas: &[Vec<u8>],
+    predecessor: Option<[u8; 32]>,
+    salt: [u8; 32],
+) -> ContractResult<Call<()>> {
+    Call::new(
+        "execute_batch",
+        &(targets, values, datas, predecessor, salt),
+    )
+}
+
+/// `update_delay(new_delay)`
+pub fn update_delay(new_delay: u64) -> ContractResult<Call<()>> {
+    Call::new("update_delay", &new_delay)
+}
+
+/// `grant_role(role, account)`
+pub fn grant_role(role: u8, account: &Address) -> ContractResult<Call<()>> {
+    Call::new("grant_role", &(role, account))
+}
+
+/// `revoke_role(role, account)`
+pub fn revoke_role(role: u8, account: &Address) -> ContractResult<Call<()>> {
+    Call::new("revoke_role", &(role, account))
+}
+
+/// `get_operation_state(id)`, one of the [`state`] constants
+pub fn get_operation_state(id: [u8; 32]) -> ContractResult<Call<u8>> {
+    Call::new("get_operation_state", &id)
+}
+
+/// `is_operation_pending(id)`
+pub fn is_operation_pending(id: [u8; 32]) -> ContractResult<Call<bool>> {
+    Call::new("is_operation_pending", &id)
+}
+
+/// `is_operation_ready(id)`
+pub fn is_operation_ready(id: [u8; 32]) -> ContractResult<Call<bool>> {
+    Call::new("is_operation_ready", &id)
+}
+
+/// `is_operation_done(id)`
+pub fn is_operation_done(id: [u8; 32]) -> ContractResult<Call<bool>> {
+    Call::new("is_operation_done", &id)
+}
+
+/// `get_timestamp_op(id)`, the timestamp from which the operation may execute
+pub fn get_timestamp_op(id: [u8; 32]) -> ContractResult<Call<u64>> {
+    Call::new("get_timestamp_op", &id)
+}
+
+/// `get_min_delay()`
+pub fn get_min_delay() -> ContractResult<Call<u64>> {
+    Call::new("get_min_delay", &())
+}
+
+/// `has_role_check(role, account)`
+pub fn has_role_check(role: u8, account: &Address) -> ContractResult<Call<bool>> {
+    Call::new("has_role_check", &(role, account))
+}
+
+string_events! {
+    TimelockInitialized { min_delay, proposers, executors, admin },
+    OperationScheduled {
+        id,
+        index,
+        target,
+        value,
+        data,
+        predecessor,
+        delay,
+        ready_timestamp,
+    },
+    OperationCancelled { id },
+    OperationExecuted { id, index, target, value, data, success },
+    MinDelayChanged { old_delay, new_delay },
+    ;
+    OwnershipTransferred,
+    RoleGranted,
+    RoleRevoked,
+}
@@ -0,0 +1,184 @@
+//! Client encodings checked against what the contracts and the SDK produce.
+
+use silica_contract_client::{Address, forwarder, governor, multisig, timelock};
+use silica_contract_sdk::crypto::typed_data::verify_typed;
+use silica_contract_sdk::event;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::testing::Keyring;
+
+type ScheduleArgs = (Address, u64, Vec<u8>, Option<[u8; 32]>, [u8; 32], u64);
+
+#[test]
+fn timelock_schedule_round_trips() {
+    let target = Address::contract([3; 32]);
+    let salt = [9u8; 32];
+    let call = timelock::schedule(&target, 10, &[1, 2, 3], Some([4; 32]), salt, 3600).unwrap();
+    assert_eq!(call.entrypoint(), "schedule");
+
+    let args: ScheduleArgs = postcard::from_bytes(call.data()).unwrap();
+    assert_eq!(
+        args,
+        (target.clone(), 10, vec![1, 2, 3], Some([4; 32]), salt, 3600)
+    );
+
+    let id = timelock::hash_operation(&target, 10, &[1, 2, 3], &Some([4; 32]), &salt);
+    let return_data = postcard::to_allocvec(&id).unwrap();
+    assert_eq!(call.decode_return(&return_data).unwrap(), id);
+    assert!(call.decode_return(&return_data[..16]).is_err());
+}
+
+#[test]
+fn single_arguments_encode_bare() {
+    assert_eq!(
+        multisig::approve_transaction(7).unwrap().data(),
+        postcard::to_allocvec(&7u64).unwrap()
+    );
+    let delegatee = Address::from_public_key(&[2; 32]);
+    assert_eq!(
+        governor::delegate(&delegatee).unwrap().data(),
+        postcard::to_allocvec(&delegatee).unwrap()
+    );
+    assert!(timelock::get_min_delay().unwrap().data().is_empty());
+}
+
+#[test]
+fn governor_initialize_encodes_arguments_in_order() {
+    let params = governor::GovernorParams {
+        name: "DAO".to_string(),
+        governance_token: Address::contract([1; 32]),
+        timelock_contract: Address::contract([2; 32]),
+        voting_delay: 1,
+        voting_period: 2,
+        proposal_threshold: 3,
+        quorum_votes: 4,
+    };
+    let call = governor::initialize(&params).unwrap();
+    let args: (String, Address, Address, u64, u64, u64, u64) =
+        postcard::from_bytes(call.data()).unwrap();
+    assert_eq!(args.0, "DAO");
+    assert_eq!(args.2, params.timelock_contract);
+    assert_eq!((args.3, args.4, args.5, args.6), (1, 2, 3, 4));
+}
+
+#[test]
+fn events_decode_from_the_sdk_macro() {
+    mock::reset();
+    event!("TransactionApproved", tx_id: 3u64, approver: "alice", approval_count: 2u64);
+    event!("OperationCancelled", id: "ab");
+
+    let events = mock::take_events();
+    let approved = multisig::Event::decode(&events[0].topic, &events[0].data).unwrap();
+    assert_eq!(
+        approved,
+        multisig::Event::TransactionApproved(multisig::TransactionApproved {
+            tx_id: "3".to_string(),
+            approver: "alice".to_string(),
+            approval_count: "2".to_string(),
+        })
+    );
+    assert_eq!(approved.topic(), "TransactionApproved");
+
+    let cancelled = timelock::Event::decode(&events[1].topic, &events[1].data).unwrap();
+    assert!(matches!(cancelled, timelock::Event::OperationCancelled(e) if e.id == "ab"));
+    assert!(governor::Event::decode(&events[1].topic, &events[1].data).is_err());
+}
+
+#[test]
+fn timelock_role_events_decode_as_emitted() {
+    mock::reset();
+    let timelock_address = Address::contract([0x71; 32]);
+    let admin = Address::from_public_key(&[1; 32]);
+    let proposer = Address::contract([0x60; 32]);
+    mock::set_contract_address(timelock_address.as_str());
+    mock::set_sender(admin.as_str());
+    mock::set_origin(admin.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+
+    let call = timelock::initialize(3600, std::slice::from_ref(&proposer), &[], &admin).unwrap();
+    mock::set_call_data(call.data());
+    timelock_contract::initialize();
+
+    let events = mock::take_events();
+    let decoded: Vec<_> = events
+        .iter()
+        .map(|event| timelock::Event::decode(&event.topic, &event.data).unwrap())
+        .collect();
+    assert!(
+        decoded.contains(&timelock::Event::RoleGranted(timelock::RoleGranted {
+            role: "PROPOSER_ROLE".to_string(),
+            account: proposer,
+            sender: admin.clone(),
+            expires_at: None,
+        }))
+    );
+    assert!(decoded.iter().any(|event| matches!(
+        event,
+        timelock::Event::OwnershipTransferred(e) if e.new_owner == admin
+    )));
+    assert!(
+        decoded
+            .iter()
+            .any(|event| event.topic() == "TimelockInitialized")
+    );
+}
+
+#[test]
+fn timelock_states_match_the_contract() {
+    mock::reset();
+    let admin = Address::from_public_key(&[1; 32]);
+    mock::set_contract_address(Address::contract([0x71; 32]).as_str());
+    mock::set_sender(admin.as_str());
+    mock::set_origin(admin.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+
+    let call = timelock::initialize(3600, std::slice::from_ref(&admin), &[], &admin).unwrap();
+    mock::set_call_data(call.data());
+    timelock_contract::initialize();
+
+    let target = Address::contract([3; 32]);
+    let call = timelock::schedule(&target, 0, &[], None, [9; 32], 3600).unwrap();
+    mock::set_call_data(call.data());
+    timelock_contract::schedule();
+    let id = call.decode_return(&mock::take_return_data()).unwrap();
+
+    let state = |id| {
+        let call = timelock::get_operation_state(id).unwrap();
+        mock::set_call_data(call.data());
+        timelock_contract::get_operation_state();
+        call.decode_return(&mock::take_return_data()).unwrap()
+    };
+    assert_eq!(state([0; 32]), timelock::state::UNSET);
+    assert_eq!(state(id), timelock::state::PENDING);
+
+    mock::set_call_data(timelock::cancel(id).unwrap().data());
+    timelock_contract::cancel();
+    assert_eq!(state(id), timelock::state::CANCELLED);
+}
+
+#[test]
+fn forwarded_calls_sign_and_decode_like_the_target() {
+    let keyring = Keyring::new();
+    let alice = keyring.alice();
+    let forwarder_address = Address::contract([5; 32]);
+    let target = Address::contract([6; 32]);
+
+    let inner = multisig::submit_transaction(&target, 1, &[], "pay").unwrap();
+    let request = forwarder::request(&alice.address(), &target, &inner, 0, 100);
+    assert_eq!(request.entrypoint, "submit_transaction");
+    assert_eq!(request.data, inner.data());
+
+    let domain = forwarder::domain(&forwarder_address, 42);
+    let digest = forwarder::digest(&domain, &request).unwrap();
+    let signature = alice.sign(&digest);
+    assert!(verify_typed(&domain, &alice.public_key(), &request, &signature).unwrap());
+
+    let call = forwarder::execute::<u64>(&request, &alice.public_key(), &signature).unwrap();
+    assert_eq!(call.entrypoint(), "execute");
+    assert_eq!(
+        call.decode_return(&postcard::to_allocvec(&5u64).unwrap())
+            .unwrap(),
+        5
+    );
+}
@@ -11,6 +11,7 @@
 //! - Timelock Integration - Queue successful proposals for delayed execution
 //! - Multiple Choice Voting - Support for/against/abstain choices
 //! - Proposal States - Track complete proposal lifecycle
+//! - Gasless Voting - Accept requests relayed by a trusted forwarder
 
 #![cfg_attr(target_arch = "wasm32", no_std)]
 #![cfg_attr(target_arch = "wasm32", no_main)]
@@ -19,7 +20,9 @@
 extern crate alloc;
 
 use silica_contract_sdk::event;
+use silica_contract_sdk::forwarder;
 use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::timelock::TimelockRef;
 // use silica_contract_sdk::storage::Vector; // Unused import
 use serde::{Deserialize, Serialize};
 
@@ -46,7 +49,7 @@ pub enum ProposalState {
 /// Vote structure
 #[derive(Serialize, Deserialize)]
 pub struct Vote {
-    pub voter: String,
+    pub voter: Address,
     pub choice: VoteType,
     pub weight: u64,
     pub timestamp: u64,
@@ -56,10 +59,10 @@ pub struct Vote {
 #[derive(Serialize, Deserialize)]
 pub struct Proposal {
     pub id: u64,
-    pub proposer: String,
+    pub proposer: Address,
     pub title: String,
     pub description: String,
-    pub targets: Vec<String>,
+    pub targets: Vec<Address>,
     pub values: Vec<u64>,
     pub calldatas: Vec<Vec<u8>>,
     pub start_timestamp: u64,
@@ -77,8 +80,8 @@ pub struct Proposal {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct GovernorConfig {
     pub name: String,
-    pub governance_token: String,
-    pub timelock_contract: String,
+    pub governance_token: Address,
+    pub timelock_contract: Address,
     pub voting_delay: u64,
     pub voting_period: u64,
     pub proposal_threshold: u64,
@@ -90,810 +93,697 @@ pub struct GovernorConfig {
 /// Delegate information
 #[derive(Serialize, Deserialize)]
 pub struct DelegateInfo {
-    pub delegate: String,
+    pub delegate: Address,
     pub votes: u64,
     pub timestamp: u64,
 }
 
-/// Initialize the DAO governor contract
-///
-/// # Arguments
-/// * `name` - Governor contract name
-/// * `governance_token` - Address of governance token contract
-/// * `timelock_contract` - Address of timelock contract for execution
-/// * `voting_delay` - Blocks to wait before voting starts
-/// * `voting_period` - Duration of voting period in blocks
-/// * `proposal_threshold` - Minimum token weight to create proposals
-/// * `quorum_votes` - Minimum votes required for valid proposal
-#[unsafe(no_mangle)]
-pub extern "C" fn initialize(
-    name: String,
-    governance_token: String,
-    timelock_contract: String,
-    voting_delay: u64,
-    voting_period: u64,
-    proposal_threshold: u64,
-    quorum_votes: u64,
-) {
-    let ctx = context();
-    let deployer = ctx.sender();
-
-    // Validate parameters
-    if name.is_empty() {
-        log("Governor name is required");
-        return;
-    }
+/// Entrypoints of the DAO governor
+#[contract_interface]
+pub trait Governor {
+    /// Initialize the DAO governor contract
+    #[allow(clippy::too_many_arguments)]
+    fn initialize(
+        &mut self,
+        name: String,
+        governance_token: Address,
+        timelock_contract: Address,
+        voting_delay: u64,
+        voting_period: u64,
+        proposal_threshold: u64,
+        quorum_votes: u64,
+    ) -> ContractResult<()>;
+    /// Create a new governance proposal
+    fn propose(
+        &mut self,
+        title: String,
+        description: String,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        calldatas: Vec<Vec<u8>>,
+    ) -> ContractResult<u64>;
+    /// Cast a vote on a proposal
+    fn cast_vote(&mut self, proposal_id: u64, choice: u8) -> ContractResult<bool>;
+    /// Delegate voting power to another address
+    fn delegate(&mut self, delegatee: Address) -> ContractResult<()>;
+    /// Execute a successful proposal through the timelock
+    fn execute(&mut self, proposal_id: u64) -> ContractResult<()>;
+    /// Cancel a proposal
+    fn cancel(&mut self, proposal_id: u64) -> ContractResult<()>;
+    /// Get the current state of a proposal
+    fn state(&self, proposal_id: u64) -> ContractResult<u8>;
+    /// Query function: Get the number of proposals created
+    fn proposal_count(&self) -> ContractResult<u64>;
+    /// Query function: Get proposal details
+    fn get_proposal(&self, proposal_id: u64) -> ContractResult<Proposal>;
+    /// Query function: Check if an account has voted on a proposal
+    fn has_voted(&self, proposal_id: u64, voter: Address) -> ContractResult<bool>;
+    /// Query function: Get the current voting power of an account
+    fn get_voting_power(&self, account: Address) -> ContractResult<u64>;
+    /// Query function: Get the current governor configuration
+    fn get_config(&self) -> ContractResult<GovernorConfig>;
+    /// Trust or stop trusting a forwarder to relay voters' signed requests
+    /// (timelock only)
+    fn set_trusted_forwarder(&mut self, forwarder: Address, trusted: bool) -> ContractResult<()>;
+    /// Set governance token address (admin function)
+    fn set_governance_token(&mut self, new_token: Address) -> ContractResult<()>;
+    /// Set timelock contract address (timelock only)
+    fn set_timelock_contract(&mut self, new_timelock: Address) -> ContractResult<()>;
+}
 
-    if governance_token.is_empty() {
-        log("Governance token address is required");
-        return;
-    }
+/// The governor; each [`Governor`] method below is an exported entrypoint
+pub struct GovernorContract;
+
+#[export_interface]
+impl Governor for GovernorContract {
+    /// Initialize the DAO governor contract
+    ///
+    /// # Arguments
+    /// * `name` - Governor contract name
+    /// * `governance_token` - Address of governance token contract
+    /// * `timelock_contract` - Address of timelock contract for execution
+    /// * `voting_delay` - Blocks to wait before voting starts
+    /// * `voting_period` - Duration of voting period in blocks
+    /// * `proposal_threshold` - Minimum token weight to create proposals
+    /// * `quorum_votes` - Minimum votes required for valid proposal
+    fn initialize(
+        &mut self,
+        name: String,
+        governance_token: Address,
+        timelock_contract: Address,
+        voting_delay: u64,
+        voting_period: u64,
+        proposal_threshold: u64,
+        quorum_votes: u64,
+    ) -> ContractResult<()> {
+        let deployer = acting_account()?;
+
+        if storage().has("config") {
+            return Err(rejected("Governor already initialized"));
+        }
 
-    if timelock_contract.is_empty() {
-        log("Timelock contract address is required");
-        return;
-    }
+        // Validate parameters
+        if name.is_empty() {
+            return Err(invalid_argument("Governor name is required"));
+        }
 
-    if voting_delay == 0 {
-        log("Voting delay must be > 0");
-        return;
-    }
+        if voting_delay == 0 {
+            return Err(invalid_argument("Voting delay must be > 0"));
+        }
 
-    if voting_period == 0 {
-        log("Voting period must be > 0");
-        return;
-    }
+        if voting_period == 0 {
+            return Err(invalid_argument("Voting period must be > 0"));
+        }
 
-    if proposal_threshold == 0 {
-        log("Proposal threshold must be > 0");
-        return;
-    }
+        if proposal_threshold == 0 {
+            return Err(invalid_argument("Proposal threshold must be > 0"));
+        }
 
-    if quorum_votes == 0 {
-        log("Quorum votes must be > 0");
-        return;
-    }
+        if quorum_votes == 0 {
+            return Err(invalid_argument("Quorum votes must be > 0"));
+        }
 
-    // Initialize governor configuration
-    let config = GovernorConfig {
-        name: name.clone(),
-        governance_token: governance_token.clone(),
-        timelock_contract: timelock_contract.clone(),
-        voting_delay,
-        voting_period,
-        proposal_threshold,
-        quorum_votes,
-        proposal_count: 0,
-        initialized: true,
-    };
+        // Initialize governor configuration
+        let config = GovernorConfig {
+            name: name.clone(),
+            governance_token: governance_token.clone(),
+            timelock_contract: timelock_contract.clone(),
+            voting_delay,
+            voting_period,
+            proposal_threshold,
+            quorum_votes,
+            proposal_count: 0,
+            initialized: true,
+        };
+
+        // Initialize access control with deployer as owner
+        AccessControl::initialize(&deployer)?;
+
+        let mut storage_ref = storage();
+        storage_ref.set("config", &config)?;
+
+        info!(
+            token = governance_token,
+            timelock = timelock_contract,
+            voting_delay = voting_delay,
+            voting_period = voting_period;
+            "DAO Governor '{}' initialized", name
+        );
+        event!("GovernorInitialized",
+            name: name,
+            governance_token: governance_token,
+            timelock_contract: timelock_contract,
+            voting_delay: voting_delay,
+            voting_period: voting_period,
+            proposal_threshold: proposal_threshold,
+            quorum_votes: quorum_votes
+        );
+        Ok(())
+    }
+
+    /// Create a new governance proposal
+    ///
+    /// # Arguments
+    /// * `title` - Short proposal title
+    /// * `description` - Detailed proposal description
+    /// * `targets` - Array of contract addresses to call
+    /// * `values` - Array of CHERT amounts to send
+    /// * `calldatas` - Array of call data for each target
+    ///
+    /// # Returns
+    /// Proposal ID
+    fn propose(
+        &mut self,
+        title: String,
+        description: String,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        calldatas: Vec<Vec<u8>>,
+    ) -> ContractResult<u64> {
+        let proposer = acting_account()?;
+
+        // Check if caller has proposal power
+        if !has_proposal_power(&proposer) {
+            return Err(rejected("Insufficient voting power to create proposals"));
+        }
 
-    // Initialize access control with deployer as owner
-    AccessControl::initialize(&deployer);
+        // Validate parameters
+        if title.is_empty() {
+            return Err(invalid_argument("Proposal title is required"));
+        }
 
-    let mut storage_ref = storage();
-    if storage_ref.set("config", &config).is_err() {
-        log("Failed to store governor config");
-        return;
-    }
+        if description.is_empty() {
+            return Err(invalid_argument("Proposal description is required"));
+        }
 
-    log(&format!(
-        "DAO Governor '{}' initialized with token: {}, timelock: {}, delay: {}, period: {}",
-        name, governance_token, timelock_contract, voting_delay, voting_period
-    ));
-    event!("GovernorInitialized",
-        name: name,
-        governance_token: governance_token,
-        timelock_contract: timelock_contract,
-        voting_delay: voting_delay,
-        voting_period: voting_period,
-        proposal_threshold: proposal_threshold,
-        quorum_votes: quorum_votes
-    );
-}
+        if targets.is_empty() {
+            return Err(invalid_argument("At least one target contract required"));
+        }
 
-/// Get current timestamp
-fn get_timestamp() -> u64 {
-    let ctx = context();
-    ctx.block_timestamp()
-}
+        if targets.len() != values.len() || targets.len() != calldatas.len() {
+            return Err(invalid_argument(
+                "Target, value, and calldata arrays must have same length",
+            ));
+        }
 
-/// Check if caller has sufficient voting power to create proposals
-fn has_proposal_power(caller: &str) -> bool {
-    let storage_ref = storage();
-    let config: GovernorConfig = match storage_ref.get::<GovernorConfig>("config") {
-        Ok(Some(c)) => c,
-        _ => return false,
-    };
+        // Get governor configuration
+        let mut config = load_config()?;
+
+        // Create proposal
+        let current_time = get_timestamp();
+        let start_time = current_time + config.voting_delay;
+        let end_time = start_time + config.voting_period;
+        let proposal_id = config.proposal_count;
+
+        let proposal = Proposal {
+            id: proposal_id,
+            proposer: proposer.clone(),
+            title: title.clone(),
+            description: description.clone(),
+            targets: targets.clone(),
+            values: values.clone(),
+            calldatas: calldatas.clone(),
+            start_timestamp: start_time,
+            end_timestamp: end_time,
+            executed: false,
+            cancelled: false,
+            for_votes: 0,
+            against_votes: 0,
+            abstain_votes: 0,
+            executed_timestamp: 0,
+            timelock_id: None,
+        };
+
+        // Store proposal
+        let mut proposals: Map<u64, Proposal> = Map::new("proposals");
+        proposals.set(&proposal_id, &proposal)?;
+
+        // Track proposal in user's proposals
+        let mut user_proposals: Map<Address, Vec<u64>> = Map::new("user_proposals");
+        let mut user_proposal_ids = user_proposals.get(&proposer)?.unwrap_or_default();
+        user_proposal_ids.push(proposal_id);
+        user_proposals.set(&proposer, &user_proposal_ids)?;
+
+        // Update proposal count
+        config.proposal_count += 1;
+        let mut storage_ref = storage();
+        storage_ref.set("config", &config)?;
+
+        info!(proposal_id = proposal_id, proposer = proposer; "Proposal created: {}", title);
+        event!("ProposalCreated",
+            proposal_id: proposal_id,
+            proposer: proposer,
+            title: title,
+            start_time: start_time,
+            end_time: end_time
+        );
+
+        Ok(proposal_id)
+    }
+
+    /// Cast a vote on a proposal
+    ///
+    /// # Arguments
+    /// * `proposal_id` - ID of the proposal to vote on
+    /// * `choice` - Vote choice (0=against, 1=for, 2=abstain)
+    ///
+    /// # Returns
+    /// True if vote was cast successfully
+    fn cast_vote(&mut self, proposal_id: u64, choice: u8) -> ContractResult<bool> {
+        let voter = acting_account()?;
+
+        // Check if vote choice is valid
+        let vote_choice = match choice {
+            0 => VoteType::AGAINST,
+            1 => VoteType::FOR,
+            2 => VoteType::ABSTAIN,
+            _ => return Err(invalid_argument("Invalid vote choice")),
+        };
+
+        // Check if proposal exists
+        let mut proposals: Map<u64, Proposal> = Map::new("proposals");
+        let mut proposal = load_proposal(&proposals, proposal_id)?;
+
+        // Check if voting period is active
+        let current_time = get_timestamp();
+        if current_time < proposal.start_timestamp {
+            return Err(rejected("Voting has not started yet"));
+        }
 
-    let balances: Map<String, u64> = Map::new("balances");
-    let balance = match balances.get(&caller.to_string()) {
-        Ok(Some(b)) => b,
-        _ => return false,
-    };
+        if current_time > proposal.end_timestamp {
+            return Err(rejected("Voting period has ended"));
+        }
 
-    balance >= config.proposal_threshold
-}
+        if proposal.executed || proposal.cancelled {
+            return Err(rejected("Cannot vote on executed or cancelled proposal"));
+        }
 
-/// Create a new governance proposal
-///
-/// # Arguments
-/// * `title` - Short proposal title
-/// * `description` - Detailed proposal description
-/// * `targets` - Array of contract addresses to call
-/// * `values` - Array of CHERT amounts to send
-/// * `calldatas` - Array of call data for each target
-///
-/// # Returns
-/// Proposal ID
-#[unsafe(no_mangle)]
-pub extern "C" fn propose(
-    title: String,
-    description: String,
-    targets: Vec<String>,
-    values: Vec<u64>,
-    calldatas: Vec<Vec<u8>>,
-) -> u64 {
-    let ctx = context();
-    let proposer = ctx.sender();
+        // Check if voter has already voted
+        let mut votes: Map<(u64, Address), Vote> = Map::new("votes");
+        if votes.get(&(proposal_id, voter.clone()))?.is_some() {
+            return Err(rejected("Already voted on this proposal"));
+        }
 
-    // Check if caller has proposal power
-    if !has_proposal_power(&proposer) {
-        log("Insufficient voting power to create proposals");
-        return 0;
-    }
+        // Get voter's voting power (token balance)
+        let balances: Map<Address, u64> = Map::new("balances");
+        let voting_power = balances
+            .get(&voter)?
+            .ok_or_else(|| rejected("No voting power found"))?;
+
+        // Cast vote
+        let vote = Vote {
+            voter: voter.clone(),
+            choice: vote_choice,
+            weight: voting_power,
+            timestamp: current_time,
+        };
+
+        // Store vote
+        votes.set(&(proposal_id, voter.clone()), &vote)?;
+
+        // Update proposal vote counts
+        match vote_choice {
+            VoteType::FOR => proposal.for_votes += voting_power,
+            VoteType::AGAINST => proposal.against_votes += voting_power,
+            VoteType::ABSTAIN => proposal.abstain_votes += voting_power,
+        }
 
-    // Validate parameters
-    if title.is_empty() {
-        log("Proposal title is required");
-        return 0;
-    }
+        // Update proposal in storage
+        proposals.set(&proposal_id, &proposal)?;
 
-    if description.is_empty() {
-        log("Proposal description is required");
-        return 0;
-    }
+        info!(
+            proposal_id = proposal_id, voter = voter, choice = choice, weight = voting_power;
+            "Vote cast"
+        );
+        event!("VoteCast",
+            proposal_id: proposal_id,
+            voter: voter,
+            choice: choice,
+            weight: voting_power
+        );
 
-    if targets.is_empty() {
-        log("At least one target contract required");
-        return 0;
+        Ok(true)
     }
 
-    if targets.len() != values.len() || targets.len() != calldatas.len() {
-        log("Target, value, and calldata arrays must have same length");
-        return 0;
-    }
+    /// Delegate voting power to another address
+    ///
+    /// # Arguments
+    /// * `delegatee` - Address to delegate voting power to
+    fn delegate(&mut self, delegatee: Address) -> ContractResult<()> {
+        let delegator = acting_account()?;
 
-    // Get governor configuration
-    let storage_ref = storage();
-    let config: GovernorConfig = match storage_ref.get::<GovernorConfig>("config") {
-        Ok(Some(c)) => c,
-        _ => {
-            log("Failed to load governor config");
-            return 0;
+        if delegatee == delegator {
+            return Err(invalid_argument("Cannot delegate to yourself"));
         }
-    };
 
-    // Create proposal
-    let current_time = get_timestamp();
-    let start_time = current_time + config.voting_delay;
-    let end_time = start_time + config.voting_period;
-
-    let proposal = Proposal {
-        id: config.proposal_count,
-        proposer: proposer.to_string(),
-        title: title.clone(),
-        description: description.clone(),
-        targets: targets.clone(),
-        values: values.clone(),
-        calldatas: calldatas.clone(),
-        start_timestamp: start_time,
-        end_timestamp: end_time,
-        executed: false,
-        cancelled: false,
-        for_votes: 0,
-        against_votes: 0,
-        abstain_votes: 0,
-        executed_timestamp: 0,
-        timelock_id: None,
-    };
+        // Get delegator's current voting power
+        let balances: Map<Address, u64> = Map::new("balances");
+        let voting_power = balances
+            .get(&delegator)?
+            .ok_or_else(|| rejected("No voting power found"))?;
+
+        // Store delegation
+        let mut delegations: Map<Address, Address> = Map::new("delegations");
+        delegations.set(&delegator, &delegatee)?;
+
+        // Update delegatee's total delegated votes
+        let mut delegate_votes: Map<Address, u64> = Map::new("delegate_votes");
+        let current_delegate_votes = delegate_votes.get(&delegatee)?.unwrap_or(0);
+        delegate_votes.set(&delegatee, &(current_delegate_votes + voting_power))?;
+
+        info!(delegator = delegator, delegatee = delegatee, votes = voting_power; "Votes delegated");
+        event!("DelegateChanged",
+            delegator: delegator,
+            delegatee: delegatee,
+            new_weight: current_delegate_votes + voting_power
+        );
+        Ok(())
+    }
+
+    /// Execute a successful proposal through the timelock
+    ///
+    /// Fails, leaving the proposal unexecuted, if the timelock refuses to
+    /// schedule it.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - ID of the proposal to execute
+    fn execute(&mut self, proposal_id: u64) -> ContractResult<()> {
+        let executor = acting_account()?;
+
+        // Check if proposal exists and can be executed
+        let mut proposals: Map<u64, Proposal> = Map::new("proposals");
+        let mut proposal = load_proposal(&proposals, proposal_id)?;
+
+        // Check if voting period has ended
+        let current_time = get_timestamp();
+        if current_time <= proposal.end_timestamp {
+            return Err(rejected("Voting period has not ended yet"));
+        }
 
-    // Store proposal
-    let mut proposals: Map<u64, Proposal> = Map::new("proposals");
-    if proposals.set(&config.proposal_count, &proposal).is_err() {
-        log("Failed to store proposal");
-        return 0;
-    }
+        if proposal.executed {
+            return Err(rejected("Proposal already executed"));
+        }
 
-    // Track proposal in user's proposals
-    let mut user_proposals: Map<String, Vec<u64>> = Map::new("user_proposals");
-    let mut user_proposal_ids = match user_proposals.get(&proposer.to_string()) {
-        Ok(Some(ids)) => ids,
-        Ok(None) => Vec::new(),
-        Err(_) => Vec::new(),
-    };
-    user_proposal_ids.push(config.proposal_count);
-    if user_proposals
-        .set(&proposer.to_string(), &user_proposal_ids)
-        .is_err()
-    {
-        log("Failed to track user's proposals");
-        return 0;
-    }
+        if proposal.cancelled {
+            return Err(rejected("Cannot execute cancelled proposal"));
+        }
 
-    // Update proposal count
-    let config_clone = config.clone();
-    let mut config_mut = config_clone;
-    config_mut.proposal_count += 1;
-    let mut storage_ref = storage();
-    if storage_ref.set("config", &config_mut).is_err() {
-        log("Failed to update proposal count");
-        return 0;
-    }
+        // Check if proposal succeeded
+        let config = load_config()?;
+
+        if proposal.for_votes <= proposal.against_votes {
+            warn!(
+                proposal_id = proposal_id,
+                for_votes = proposal.for_votes,
+                against_votes = proposal.against_votes;
+                "Proposal did not pass"
+            );
+            proposal.cancelled = true;
+            return proposals.set(&proposal_id, &proposal);
+        }
 
-    log(&format!(
-        "Proposal {} created by {}: {}",
-        config.proposal_count, proposer, title
-    ));
-    event!("ProposalCreated",
-        proposal_id: config.proposal_count,
-        proposer: proposer,
-        title: title,
-        start_time: start_time,
-        end_time: end_time
-    );
-
-    config.proposal_count
-}
+        if proposal.for_votes < config.quorum_votes {
+            warn!(
+                proposal_id = proposal_id,
+                for_votes = proposal.for_votes,
+                quorum = config.quorum_votes;
+                "Proposal did not reach quorum"
+            );
+            proposal.cancelled = true;
+            return proposals.set(&proposal_id, &proposal);
+        }
 
-/// Cast a vote on a proposal
-///
-/// # Arguments
-/// * `proposal_id` - ID of the proposal to vote on
-/// * `choice` - Vote choice (0=against, 1=for, 2=abstain)
-///
-/// # Returns
-/// True if vote was cast successfully
-#[unsafe(no_mangle)]
-pub extern "C" fn cast_vote(proposal_id: u64, choice: u8) -> bool {
-    let ctx = context();
-    let voter = ctx.sender();
+        // Queue proposal in timelock
+        let timelock_id = queue_in_timelock(&config, proposal_id, &proposal)?;
+        proposal.timelock_id = Some(timelock_id);
 
-    // Check if vote choice is valid
-    if choice > 2 {
-        log("Invalid vote choice");
-        return false;
-    }
+        // Mark proposal as executed
+        proposal.executed = true;
+        proposal.executed_timestamp = current_time;
+        proposals.set(&proposal_id, &proposal)?;
+
+        info!(proposal_id = proposal_id, executor = executor; "Proposal executed");
+        event!("ProposalExecuted",
+            proposal_id: proposal_id,
+            executor: executor,
+            timelock_id: hex::encode(timelock_id)
+        );
+        Ok(())
+    }
+
+    /// Cancel a proposal
+    ///
+    /// # Arguments
+    /// * `proposal_id` - ID of the proposal to cancel
+    fn cancel(&mut self, proposal_id: u64) -> ContractResult<()> {
+        let canceller = acting_account()?;
+
+        // Check if proposal exists
+        let mut proposals: Map<u64, Proposal> = Map::new("proposals");
+        let mut proposal = load_proposal(&proposals, proposal_id)?;
+
+        if proposal.executed {
+            return Err(rejected("Cannot cancel executed proposal"));
+        }
 
-    // Check if proposal exists
-    let storage_ref = storage();
-    let mut proposals: Map<u64, Proposal> = Map::new("proposals");
-    let mut proposal = match proposals.get(&proposal_id) {
-        Ok(Some(p)) => p,
-        Ok(None) => {
-            log("Proposal does not exist");
-            return false;
+        if proposal.cancelled {
+            return Err(rejected("Proposal is already cancelled"));
         }
-        Err(_) => {
-            log("Failed to read proposal");
-            return false;
+
+        // Only proposer can cancel (or anyone after expiry)
+        let current_time = get_timestamp();
+        if proposal.proposer != canceller && current_time <= proposal.end_timestamp {
+            return Err(rejected("Only proposer can cancel active proposals"));
         }
-    };
 
-    // Check if voting period is active
-    let current_time = get_timestamp();
-    if current_time < proposal.start_timestamp {
-        log("Voting has not started yet");
-        return false;
-    }
+        // Cancel proposal
+        proposal.cancelled = true;
+        proposals.set(&proposal_id, &proposal)?;
 
-    if current_time > proposal.end_timestamp {
-        log("Voting period has ended");
-        return false;
+        info!(proposal_id = proposal_id, canceller = canceller; "Proposal cancelled");
+        event!("ProposalCancelled", proposal_id: proposal_id, canceller: canceller);
+        Ok(())
     }
 
-    if proposal.executed || proposal.cancelled {
-        log("Cannot vote on executed or cancelled proposal");
-        return false;
-    }
+    /// Get the current state of a proposal
+    fn state(&self, proposal_id: u64) -> ContractResult<u8> {
+        let storage_ref = storage();
+        let proposals: Map<u64, Proposal> = Map::new("proposals");
+        let proposal = load_proposal(&proposals, proposal_id)?;
+        let current_time = get_timestamp();
 
-    // Check if voter has already voted
-    let mut votes: Map<(u64, String), Vote> = Map::new("votes");
-    if votes
-        .get(&(proposal_id, voter.to_string()))
-        .ok()
-        .flatten()
-        .is_some()
-    {
-        log("Already voted on this proposal");
-        return false;
-    }
+        if proposal.executed {
+            return Ok(ProposalState::EXECUTED as u8);
+        }
 
-    // Get voter's voting power (token balance)
-    let balances: Map<String, u64> = Map::new("balances");
-    let voting_power = match balances.get(&voter.to_string()) {
-        Ok(Some(balance)) => balance,
-        _ => {
-            log("No voting power found");
-            return false;
+        if proposal.cancelled {
+            return Ok(ProposalState::CANCELLED as u8);
         }
-    };
 
-    // Cast vote
-    let vote_choice = match choice {
-        0 => VoteType::AGAINST,
-        1 => VoteType::FOR,
-        2 => VoteType::ABSTAIN,
-        _ => VoteType::FOR, // Fallback, shouldn't reach here
-    };
+        if current_time < proposal.start_timestamp {
+            return Ok(ProposalState::PENDING as u8);
+        }
 
-    let vote = Vote {
-        voter: voter.to_string(),
-        choice: vote_choice,
-        weight: voting_power,
-        timestamp: current_time,
-    };
+        if current_time >= proposal.start_timestamp && current_time <= proposal.end_timestamp {
+            return Ok(ProposalState::ACTIVE as u8);
+        }
 
-    // Store vote
-    if votes.set(&(proposal_id, voter.to_string()), &vote).is_err() {
-        log("Failed to store vote");
-        return false;
+        // Voting ended, determine result
+        let config: GovernorConfig = match storage_ref.get("config")? {
+            Some(c) => c,
+            None => return Ok(ProposalState::DEFEATED as u8),
+        };
+
+        if proposal.for_votes > proposal.against_votes && proposal.for_votes >= config.quorum_votes
+        {
+            Ok(ProposalState::SUCCEEDED as u8)
+        } else {
+            Ok(ProposalState::DEFEATED as u8)
+        }
     }
 
-    // Update proposal vote counts
-    match vote_choice {
-        VoteType::FOR => proposal.for_votes += voting_power,
-        VoteType::AGAINST => proposal.against_votes += voting_power,
-        VoteType::ABSTAIN => proposal.abstain_votes += voting_power,
+    /// Query function: Get the number of proposals created
+    fn proposal_count(&self) -> ContractResult<u64> {
+        Ok(load_config()?.proposal_count)
     }
 
-    // Update proposal in storage
-    if proposals.set(&proposal_id, &proposal).is_err() {
-        log("Failed to update proposal");
-        return false;
+    /// Query function: Get proposal details
+    fn get_proposal(&self, proposal_id: u64) -> ContractResult<Proposal> {
+        let proposals: Map<u64, Proposal> = Map::new("proposals");
+        load_proposal(&proposals, proposal_id)
     }
 
-    log(&format!(
-        "Vote cast on proposal {} by {}: {:?} with weight {}",
-        proposal_id, voter, vote_choice, voting_power
-    ));
-    event!("VoteCast",
-        proposal_id: proposal_id,
-        voter: voter,
-        choice: choice,
-        weight: voting_power
-    );
-
-    true
-}
-
-/// Delegate voting power to another address
-///
-/// # Arguments
-/// * `delegatee` - Address to delegate voting power to
-#[unsafe(no_mangle)]
-pub extern "C" fn delegate(delegatee: String) {
-    let ctx = context();
-    let delegator = ctx.sender();
-
-    if delegatee.is_empty() {
-        log("Delegatee address is required");
-        return;
+    /// Query function: Check if an account has voted on a proposal
+    fn has_voted(&self, proposal_id: u64, voter: Address) -> ContractResult<bool> {
+        let votes: Map<(u64, Address), Vote> = Map::new("votes");
+        Ok(votes.get(&(proposal_id, voter))?.is_some())
     }
 
-    if delegatee == delegator {
-        log("Cannot delegate to yourself");
-        return;
-    }
+    /// Query function: Get the current voting power of an account
+    fn get_voting_power(&self, account: Address) -> ContractResult<u64> {
+        // Get direct balance
+        let balances: Map<Address, u64> = Map::new("balances");
+        let mut total_power = balances.get(&account)?.unwrap_or(0);
 
-    // Get delegator's current voting power
-    let balances: Map<String, u64> = Map::new("balances");
-    let voting_power = match balances.get(&delegator.to_string()) {
-        Ok(Some(balance)) => balance,
-        _ => {
-            log("No voting power found");
-            return;
+        // Add delegated power
+        let delegate_votes: Map<Address, u64> = Map::new("delegate_votes");
+        if let Some(delegated_votes) = delegate_votes.get(&account)? {
+            total_power += delegated_votes;
         }
-    };
 
-    // Store delegation
-    let mut delegations: Map<String, String> = Map::new("delegations");
-    if delegations.set(&delegator.to_string(), &delegatee).is_err() {
-        log("Failed to store delegation");
-        return;
+        Ok(total_power)
     }
 
-    // Update delegatee's total delegated votes
-    let mut delegate_votes: Map<String, u64> = Map::new("delegate_votes");
-    let current_delegate_votes = match delegate_votes.get(&delegatee) {
-        Ok(Some(votes)) => votes,
-        _ => 0,
-    };
-
-    if delegate_votes
-        .set(&delegatee, &(current_delegate_votes + voting_power))
-        .is_err()
-    {
-        log("Failed to update delegate votes");
-        return;
+    /// Query function: Get the current governor configuration
+    fn get_config(&self) -> ContractResult<GovernorConfig> {
+        load_config()
     }
 
-    log(&format!(
-        "Delegated {} votes from {} to {}",
-        voting_power, delegator, delegatee
-    ));
-    event!("DelegateChanged",
-        delegator: delegator,
-        delegatee: delegatee,
-        new_weight: current_delegate_votes + voting_power
-    );
-}
-
-/// Execute a successful proposal through the timelock
-///
-/// # Arguments
-/// * `proposal_id` - ID of the proposal to execute
-#[unsafe(no_mangle)]
-pub extern "C" fn execute(proposal_id: u64) {
-    let ctx = context();
-    let executor = ctx.sender();
-
-    // Check if proposal exists and can be executed
-    let storage_ref = storage();
-    let mut proposals: Map<u64, Proposal> = Map::new("proposals");
-    let mut proposal = match proposals.get(&proposal_id) {
-        Ok(Some(p)) => p,
-        Ok(None) => {
-            log("Proposal does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read proposal");
-            return;
-        }
-    };
+    /// Trust or stop trusting a forwarder to relay voters' signed requests
+    /// (timelock only)
+    ///
+    /// Token holders can then propose, vote and delegate through the forwarder
+    /// without paying for execution. A trusted forwarder can vote as any
+    /// holder, so only a passed proposal, executed by the timelock, may change
+    /// this.
+    ///
+    /// # Arguments
+    /// * `forwarder` - Forwarder contract address
+    /// * `trusted` - Whether calls it relays act for their signer
+    fn set_trusted_forwarder(&mut self, forwarder: Address, trusted: bool) -> ContractResult<()> {
+        require_timelock(&load_config()?)?;
+        forwarder::trust_forwarder(&forwarder, trusted)?;
 
-    // Check if voting period has ended
-    let current_time = get_timestamp();
-    if current_time <= proposal.end_timestamp {
-        log("Voting period has not ended yet");
-        return;
+        info!(forwarder = forwarder, trusted = trusted; "Trusted forwarder updated");
+        Ok(())
     }
 
-    if proposal.executed {
-        log("Proposal already executed");
-        return;
-    }
+    /// Set governance token address (admin function)
+    fn set_governance_token(&mut self, new_token: Address) -> ContractResult<()> {
+        let caller = acting_account()?;
 
-    if proposal.cancelled {
-        log("Cannot execute cancelled proposal");
-        return;
-    }
+        // Check if caller is authorized (owner or admin)
+        AccessControl::authorize(&caller, Some("admin"))?;
 
-    // Check if proposal succeeded
-    let config: GovernorConfig = match storage_ref.get("config") {
-        Ok(Some(c)) => c,
-        _ => {
-            log("Failed to load governor config");
-            return;
-        }
-    };
+        let mut config = load_config()?;
+        config.governance_token = new_token.clone();
+        let mut storage_ref = storage();
+        storage_ref.set("config", &config)?;
+        info!(token = new_token; "Governance token updated");
 
-    if proposal.for_votes <= proposal.against_votes {
-        log("Proposal did not pass");
-        proposal.cancelled = true;
-        if proposals.set(&proposal_id, &proposal).is_err() {
-            log("Failed to update proposal");
-            return;
-        }
-        return;
+        event!("GovernanceTokenUpdated", new_token: new_token);
+        Ok(())
     }
 
-    if proposal.for_votes < config.quorum_votes {
-        log("Proposal did not reach quorum");
-        proposal.cancelled = true;
-        if proposals.set(&proposal_id, &proposal).is_err() {
-            log("Failed to update proposal");
-            return;
-        }
-        return;
-    }
+    /// Set timelock contract address (timelock only)
+    ///
+    /// The timelock decides what governance may change, so moving it takes a
+    /// passed proposal too.
+    fn set_timelock_contract(&mut self, new_timelock: Address) -> ContractResult<()> {
+        let mut config = load_config()?;
+        require_timelock(&config)?;
 
-    // Queue proposal in timelock
-    if proposal.targets.len() > 1 {
-        // Schedule batch operation
-        // In real implementation, this would call timelock.schedule_batch()
-        log(&format!(
-            "Queueing batch proposal {} in timelock with {} operations",
-            proposal_id,
-            proposal.targets.len()
-        ));
-
-        // For now, simulate a timelock ID
-        let timelock_id = [proposal_id as u8; 32];
-        proposal.timelock_id = Some(timelock_id);
-    } else {
-        // Schedule single operation
-        // In real implementation, this would call timelock.schedule()
-        log(&format!(
-            "Queueing proposal {} in timelock: {} -> {}",
-            proposal_id, proposal.targets[0], proposal.title
-        ));
-
-        // For now, simulate a timelock ID
-        let timelock_id = [proposal_id as u8; 32];
-        proposal.timelock_id = Some(timelock_id);
-    }
+        config.timelock_contract = new_timelock.clone();
+        let mut storage_ref = storage();
+        storage_ref.set("config", &config)?;
+        info!(timelock = new_timelock; "Timelock contract updated");
 
-    // Mark proposal as executed
-    proposal.executed = true;
-    proposal.executed_timestamp = current_time;
-
-    if proposals.set(&proposal_id, &proposal).is_err() {
-        log("Failed to update proposal");
-        return;
+        event!("TimelockContractUpdated", new_timelock: new_timelock);
+        Ok(())
     }
-
-    log(&format!(
-        "Proposal {} executed successfully by {}",
-        proposal_id, executor
-    ));
-    event!("ProposalExecuted",
-        proposal_id: proposal_id,
-        executor: executor,
-        timelock_id: "set" // Placeholder - would need real hash formatting
-    );
 }
 
-/// Cancel a proposal
-///
-/// # Arguments
-/// * `proposal_id` - ID of the proposal to cancel
-#[unsafe(no_mangle)]
-pub extern "C" fn cancel(proposal_id: u64) {
-    let ctx = context();
-    let canceller = ctx.sender();
-
-    // Check if proposal exists
-    let mut proposals: Map<u64, Proposal> = Map::new("proposals");
-    let mut proposal = match proposals.get(&proposal_id) {
-        Ok(Some(p)) => p,
-        Ok(None) => {
-            log("Proposal does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read proposal");
-            return;
-        }
-    };
-
-    if proposal.executed {
-        log("Cannot cancel executed proposal");
-        return;
-    }
-
-    if proposal.cancelled {
-        log("Proposal is already cancelled");
-        return;
-    }
-
-    // Only proposer can cancel (or anyone after expiry)
-    let current_time = get_timestamp();
-    if proposal.proposer != canceller && current_time <= proposal.end_timestamp {
-        log("Only proposer can cancel active proposals");
-        return;
-    }
-
-    // Cancel proposal
-    proposal.cancelled = true;
-    if proposals.set(&proposal_id, &proposal).is_err() {
-        log("Failed to update proposal");
-        return;
-    }
-
-    log(&format!(
-        "Proposal {} cancelled by {}",
-        proposal_id, canceller
-    ));
-    event!("ProposalCancelled", proposal_id: proposal_id, canceller: canceller);
+/// Error for a call the governor refuses
+fn rejected(message: &str) -> ContractError {
+    ContractError::Custom(String::from(message))
 }
 
-/// Get the current state of a proposal
-#[unsafe(no_mangle)]
-pub extern "C" fn state(proposal_id: u64) -> u8 {
-    let storage_ref = storage();
-    let proposals: Map<u64, Proposal> = Map::new("proposals");
-
-    match proposals.get(&proposal_id) {
-        Ok(Some(proposal)) => {
-            let current_time = get_timestamp();
-
-            if proposal.executed {
-                return ProposalState::EXECUTED as u8;
-            }
-
-            if proposal.cancelled {
-                return ProposalState::CANCELLED as u8;
-            }
-
-            if current_time < proposal.start_timestamp {
-                return ProposalState::PENDING as u8;
-            }
-
-            if current_time >= proposal.start_timestamp && current_time <= proposal.end_timestamp {
-                return ProposalState::ACTIVE as u8;
-            }
-
-            // Voting ended, determine result
-            let config: GovernorConfig = match storage_ref.get("config") {
-                Ok(Some(c)) => c,
-                _ => return ProposalState::DEFEATED as u8,
-            };
-
-            if proposal.for_votes > proposal.against_votes
-                && proposal.for_votes >= config.quorum_votes
-            {
-                ProposalState::SUCCEEDED as u8
-            } else {
-                ProposalState::DEFEATED as u8
-            }
-        }
-        Ok(None) => {
-            log("Proposal does not exist");
-            ProposalState::DEFEATED as u8
-        }
-        Err(_) => {
-            log("Failed to read proposal");
-            ProposalState::DEFEATED as u8
-        }
-    }
+/// Error for a call whose arguments the governor refuses
+fn invalid_argument(message: &str) -> ContractError {
+    ContractError::InvalidArgument(String::from(message))
 }
 
-/// Query function: Get the number of proposals created
-#[unsafe(no_mangle)]
-pub extern "C" fn proposal_count() {
-    let storage_ref = storage();
-    match storage_ref.get::<GovernorConfig>("config") {
-        Ok(Some(c)) => c.proposal_count,
-        _ => 0,
-    };
+/// Account the call acts for: the signer of a request relayed by a trusted
+/// forwarder, otherwise the sender
+fn acting_account() -> ContractResult<Address> {
+    context().msg_sender()
 }
 
-/// Query function: Get proposal details
-#[unsafe(no_mangle)]
-pub extern "C" fn get_proposal(proposal_id: u64) -> u64 {
-    let storage_ref = storage();
-    let proposals: Map<u64, Proposal> = Map::new("proposals");
-
-    match proposals.get(&proposal_id) {
-        Ok(Some(_proposal)) => {
-            log(&format!("Proposal {} exists", proposal_id));
-            1 // Return 1 to indicate existence
-        }
-        _ => {
-            log(&format!("Proposal {} not found", proposal_id));
-            0 // Return 0 to indicate not found
-        }
+/// Require the call to come from the configured timelock, which only calls the
+/// governor to execute passed proposals
+fn require_timelock(config: &GovernorConfig) -> ContractResult<()> {
+    if context().sender() != &config.timelock_contract {
+        return Err(rejected("Only the timelock can do this"));
     }
+    Ok(())
 }
 
-/// Query function: Check if an account has voted on a proposal
-#[unsafe(no_mangle)]
-pub extern "C" fn has_voted(proposal_id: u64, voter: String) -> bool {
-    let votes: Map<(u64, String), Vote> = Map::new("votes");
-    match votes.get(&(proposal_id, voter)) {
-        Ok(Some(_vote)) => true,
-        _ => false,
-    }
+/// Load the governor configuration
+fn load_config() -> ContractResult<GovernorConfig> {
+    storage()
+        .get("config")?
+        .ok_or_else(|| rejected("Governor is not initialized"))
 }
 
-/// Query function: Get the current voting power of an account
-#[unsafe(no_mangle)]
-pub extern "C" fn get_voting_power(account: String) -> u64 {
-    // Get direct balance
-    let balances: Map<String, u64> = Map::new("balances");
-    let mut total_power = match balances.get(&account) {
-        Ok(Some(balance)) => balance,
-        _ => 0,
-    };
-
-    // Add delegated power
-    let delegate_votes: Map<String, u64> = Map::new("delegate_votes");
-    if let Ok(Some(delegated_votes)) = delegate_votes.get(&account) {
-        total_power += delegated_votes;
-    }
+/// Load a stored proposal
+fn load_proposal(proposals: &Map<u64, Proposal>, proposal_id: u64) -> ContractResult<Proposal> {
+    proposals
+        .get(&proposal_id)?
+        .ok_or_else(|| rejected("Proposal does not exist"))
+}
 
-    total_power
+/// Get current timestamp
+fn get_timestamp() -> u64 {
+    let ctx = context();
+    ctx.block_timestamp()
 }
 
-/// Query function: Get the current governor configuration
-#[unsafe(no_mangle)]
-pub extern "C" fn get_config() -> u64 {
+/// Check if caller has sufficient voting power to create proposals
+fn has_proposal_power(caller: &Address) -> bool {
     let storage_ref = storage();
     let config: GovernorConfig = match storage_ref.get::<GovernorConfig>("config") {
-        Ok(Some(c)) => {
-            log(&format!(
-                "Governor: {}, Token: {}, Timelock: {}, Delay: {}, Period: {}, Threshold: {}, Quorum: {}",
-                c.name,
-                c.governance_token,
-                c.timelock_contract,
-                c.voting_delay,
-                c.voting_period,
-                c.proposal_threshold,
-                c.quorum_votes
-            ));
-            c
-        }
-        _ => {
-            log("Failed to load governor config");
-            return 0;
-        }
+        Ok(Some(c)) => c,
+        _ => return false,
     };
-    1
-}
-
-/// Set governance token address (admin function)
-#[unsafe(no_mangle)]
-pub extern "C" fn set_governance_token(new_token: String) {
-    let ctx = context();
-    let caller = ctx.sender();
 
-    // Check if caller is authorized (owner or admin)
-    if let Err(_) = AccessControl::authorize(&ctx.sender(), Some("admin")) {
-        log("Unauthorized: Only admin can set governance token");
-        return;
-    }
-
-    let storage_ref = storage();
-    let config: GovernorConfig = match storage_ref.get::<GovernorConfig>("config") {
-        Ok(Some(mut c)) => {
-            c.governance_token = new_token.clone();
-            let mut storage_ref = storage();
-            if storage_ref.set("config", &c).is_err() {
-                log("Failed to update config");
-                return;
-            }
-            log(&format!("Governance token updated to {}", new_token));
-            c
-        }
-        _ => {
-            log("Failed to load config");
-            return;
-        }
+    let balances: Map<Address, u64> = Map::new("balances");
+    let balance = match balances.get(caller) {
+        Ok(Some(b)) => b,
+        _ => return false,
     };
 
-    event!("GovernanceTokenUpdated", new_token: new_token);
+    balance >= config.proposal_threshold
 }
 
-/// Set timelock contract address (admin function)
-#[unsafe(no_mangle)]
-pub extern "C" fn set_timelock_contract(new_timelock: String) {
-    let ctx = context();
-    let caller = ctx.sender();
+/// Schedule a passed proposal's actions in the timelock at its minimum delay,
+/// returning the operation id; fails if the timelock refuses them
+fn queue_in_timelock(
+    config: &GovernorConfig,
+    proposal_id: u64,
+    proposal: &Proposal,
+) -> ContractResult<[u8; 32]> {
+    let timelock = TimelockRef::new(config.timelock_contract.clone());
+    let delay = timelock.get_min_delay()?;
+    // Salting with the proposal id keeps identical proposals distinct
+    let salt = crypto::hash_blake3(&proposal_id.to_le_bytes());
 
-    // Check if caller is authorized (owner or admin)
-    if let Err(_) = AccessControl::authorize(&ctx.sender(), Some("admin")) {
-        log("Unauthorized: Only admin can set timelock contract");
-        return;
+    if proposal.targets.len() > 1 {
+        timelock.schedule_batch(
+            proposal.targets.clone(),
+            proposal.values.clone(),
+            proposal.calldatas.clone(),
+            None,
+            salt,
+            delay,
+        )
+    } else {
+        timelock.schedule(
+            proposal.targets[0].clone(),
+            proposal.values[0],
+            proposal.calldatas[0].clone(),
+            None,
+            salt,
+            delay,
+        )
     }
-
-    let storage_ref = storage();
-    let config: GovernorConfig = match storage_ref.get::<GovernorConfig>("config") {
-        Ok(Some(mut c)) => {
-            c.timelock_contract = new_timelock.clone();
-            let mut storage_ref = storage();
-            if storage_ref.set("config", &c).is_err() {
-                log("Failed to update config");
-                return;
-            }
-            log(&format!("Timelock contract updated to {}", new_timelock));
-            c
-        }
-        _ => {
-            log("Failed to load config");
-            return;
-        }
-    };
-
-    event!("TimelockContractUpdated", new_timelock: new_timelock);
 }
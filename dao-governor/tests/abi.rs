@@ -0,0 +1,109 @@
+//! Machine-readable interface of the governor, checked against `abi.json`.
+
+use dao_governor_contract::{
+    GovernorConfig, GovernorContract, GovernorRef, Proposal, Vote, VoteType,
+};
+use silica_contract_sdk::abi::{self, AbiBuilder, ContractAbi};
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::security::DEFAULT_ADMIN_ROLE;
+use silica_contract_sdk::timelock::TimelockRef;
+
+fn governor_abi() -> ContractAbi {
+    AbiBuilder::new("dao-governor-contract", env!("CARGO_PKG_VERSION"))
+        .interface(GovernorRef::describe)
+        .string_event(
+            "GovernorInitialized",
+            &[
+                "name",
+                "governance_token",
+                "timelock_contract",
+                "voting_delay",
+                "voting_period",
+                "proposal_threshold",
+                "quorum_votes",
+            ],
+        )
+        .string_event(
+            "ProposalCreated",
+            &["proposal_id", "proposer", "title", "start_time", "end_time"],
+        )
+        .string_event("VoteCast", &["proposal_id", "voter", "choice", "weight"])
+        .string_event("DelegateChanged", &["delegator", "delegatee", "new_weight"])
+        .string_event(
+            "ProposalExecuted",
+            &["proposal_id", "executor", "timelock_id"],
+        )
+        .string_event("ProposalCancelled", &["proposal_id", "canceller"])
+        .string_event("GovernanceTokenUpdated", &["new_token"])
+        .string_event("TimelockContractUpdated", &["new_timelock"])
+        .error(
+            "Unauthorized",
+            "Only the admin can change governor settings",
+        )
+        .error("InvalidConfig", "A governor parameter is missing or zero")
+        .error(
+            "InsufficientVotingPower",
+            "Insufficient voting power to create proposals",
+        )
+        .error(
+            "InvalidProposal",
+            "Proposal title, description or actions are invalid",
+        )
+        .error("ProposalNotFound", "Proposal does not exist")
+        .error("VotingNotStarted", "Voting has not started yet")
+        .error("VotingEnded", "Voting period has ended")
+        .error("VotingNotEnded", "Voting period has not ended yet")
+        .error("InvalidVoteChoice", "Invalid vote choice")
+        .error("QuorumNotReached", "Proposal did not reach quorum")
+        .error("ProposalDefeated", "Proposal did not pass")
+        .error("AlreadyExecuted", "Proposal already executed")
+        .error("AlreadyCancelled", "Proposal is already cancelled")
+        .error("NotProposer", "Only proposer can cancel active proposals")
+        .ty::<VoteType>()
+        .access_control(&[DEFAULT_ADMIN_ROLE])
+        .trusted_forwarders()
+        .value::<GovernorConfig>("config")
+        .map::<Address, u64>("balances")
+        .map::<u64, Proposal>("proposals")
+        .map::<Address, Vec<u64>>("user_proposals")
+        .map::<(u64, Address), Vote>("votes")
+        .map::<Address, Address>("delegations")
+        .map::<Address, u64>("delegate_votes")
+        .build()
+        .expect("governor ABI")
+}
+
+#[test]
+fn abi_is_compatible() {
+    abi::check_file(
+        &governor_abi(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/abi.json"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn abi_entrypoints_are_exported() {
+    // Builds require every ABI entrypoint as an export without parameters.
+    for entrypoint in governor_abi().entrypoints {
+        assert!(
+            GovernorContract::EXPORTS
+                .iter()
+                .any(|(name, _)| *name == entrypoint.name),
+            "{} is not exported",
+            entrypoint.name
+        );
+    }
+}
+
+#[test]
+fn timelock_provides_the_interface_the_governor_calls() {
+    // Every entrypoint `TimelockRef` can call must exist in the timelock's
+    // committed ABI with the same argument and return encodings.
+    let timelock = ContractAbi::from_json(include_str!("../../timelock/abi.json")).unwrap();
+    let called = AbiBuilder::new("timelock-interface", env!("CARGO_PKG_VERSION"))
+        .interface(TimelockRef::describe)
+        .build()
+        .expect("timelock interface");
+    timelock.check_compatible_with(&called).unwrap();
+}
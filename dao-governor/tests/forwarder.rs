@@ -0,0 +1,65 @@
+//! A trusted forwarder can vote as any holder, so only the timelock, executing
+//! a passed proposal, can trust one or move the governor to another timelock.
+
+use dao_governor_contract::{GovernorContract, GovernorRef};
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::forwarder;
+
+/// Call the governor from `caller`.
+fn call_from(caller: &Address) {
+    mock::set_contract_address(caller.as_str());
+    mock::set_sender(caller.as_str());
+}
+
+#[test]
+fn only_the_timelock_trusts_forwarders() {
+    mock::reset();
+    let deployer = Address::from_public_key(&[1; 32]);
+    let governor_address = Address::contract([0x60; 32]);
+    let timelock_address = Address::contract([0x71; 32]);
+    let trusted_forwarder = Address::contract([0xf0; 32]);
+    mock::deploy_exports(
+        [0x60; 32],
+        governor_address.as_str(),
+        GovernorContract::EXPORTS,
+    );
+    mock::set_origin(deployer.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+    let is_trusted = || {
+        mock::invoke(governor_address.as_str(), deployer.as_str(), || {
+            forwarder::is_trusted_forwarder(&trusted_forwarder)
+        })
+        .unwrap()
+    };
+
+    let governor = GovernorRef::new(governor_address.clone());
+    call_from(&deployer);
+    governor
+        .initialize(
+            String::from("DAO"),
+            Address::contract([0x70; 32]),
+            timelock_address.clone(),
+            10,
+            100,
+            1,
+            1,
+        )
+        .unwrap();
+
+    // The deployer administers the governor but cannot bypass governance.
+    assert!(
+        governor
+            .set_trusted_forwarder(trusted_forwarder.clone(), true)
+            .is_err()
+    );
+    assert!(governor.set_timelock_contract(deployer.clone()).is_err());
+    assert!(!is_trusted());
+
+    call_from(&timelock_address);
+    governor
+        .set_trusted_forwarder(trusted_forwarder.clone(), true)
+        .unwrap();
+    assert!(is_trusted());
+}
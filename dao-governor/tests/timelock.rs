@@ -0,0 +1,176 @@
+//! Passed proposals are queued through the timelock's typed interface, and a
+//! timelock that refuses them leaves the proposal unexecuted.
+
+use dao_governor_contract::{GovernorContract, GovernorRef};
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::error::{ContractError, ContractResult};
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::storage::Map;
+use silica_contract_sdk::timelock::{TimelockRef, hash_operation_batch, state};
+use timelock_contract::TimelockContract;
+
+const TIMELOCK_CODE: [u8; 32] = [0x71; 32];
+
+/// A timelock that never granted the governor PROPOSER_ROLE: it reports its
+/// delay but refuses to schedule.
+fn unauthorized_timelock_code(entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+    match entrypoint {
+        "get_min_delay" => Ok(postcard::to_allocvec(&3600u64).unwrap()),
+        "schedule" | "schedule_batch" => Err(ContractError::Custom(String::from(
+            "Caller does not have PROPOSER_ROLE",
+        ))),
+        other => Err(ContractError::ContractCallFailed(other.to_string())),
+    }
+}
+
+#[test]
+fn rejected_scheduling_leaves_the_proposal_unexecuted() {
+    mock::reset();
+    let voter = Address::from_public_key(&[1; 32]);
+    let governor_address = Address::contract([0x60; 32]);
+    let timelock_address = Address::contract([0x71; 32]);
+    mock::deploy_exports(
+        [0x60; 32],
+        governor_address.as_str(),
+        GovernorContract::EXPORTS,
+    );
+    mock::register_code(TIMELOCK_CODE, unauthorized_timelock_code);
+    mock::set_code_hash(timelock_address.as_str(), TIMELOCK_CODE);
+    mock::set_contract_address(voter.as_str());
+    mock::set_sender(voter.as_str());
+    mock::set_origin(voter.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+
+    let governor = GovernorRef::new(governor_address.clone());
+    governor
+        .initialize(
+            String::from("DAO"),
+            Address::contract([0x70; 32]),
+            timelock_address.clone(),
+            10,
+            100,
+            1,
+            1,
+        )
+        .unwrap();
+    mock::invoke(governor_address.as_str(), voter.as_str(), || {
+        Map::<Address, u64>::new("balances").set(&voter, &50)
+    })
+    .unwrap();
+
+    let id = governor
+        .propose(
+            String::from("Upgrade"),
+            String::from("Upgrade the treasury"),
+            vec![Address::contract([9; 32])],
+            vec![0],
+            vec![vec![1, 2]],
+        )
+        .unwrap();
+    mock::set_block_timestamp(1_700_000_020);
+    assert!(governor.cast_vote(id, 1).unwrap());
+    mock::take_events();
+
+    mock::set_block_timestamp(1_700_000_200);
+    assert!(governor.execute(id).is_err());
+
+    // Still succeeded rather than executed, with nothing queued or announced.
+    assert_eq!(governor.state(id).unwrap(), 2);
+    let proposal = governor.get_proposal(id).unwrap();
+    assert_eq!((proposal.id, proposal.for_votes), (id, 50));
+    assert!(!proposal.executed);
+    assert_eq!(proposal.timelock_id, None);
+    assert!(mock::take_events().is_empty());
+
+    assert_eq!(governor.proposal_count().unwrap(), 1);
+    assert!(governor.get_proposal(id + 1).is_err());
+    let config = governor.get_config().unwrap();
+    assert_eq!(config.timelock_contract, timelock_address);
+    assert_eq!(config.proposal_count, 1);
+}
+
+#[test]
+fn multi_action_proposals_are_queued_as_timelock_batches() {
+    mock::reset();
+    let voter = Address::from_public_key(&[1; 32]);
+    let governor_address = Address::contract([0x60; 32]);
+    let timelock_address = Address::contract([0x71; 32]);
+    mock::deploy_exports(
+        [0x60; 32],
+        governor_address.as_str(),
+        GovernorContract::EXPORTS,
+    );
+    mock::deploy_exports(
+        TIMELOCK_CODE,
+        timelock_address.as_str(),
+        TimelockContract::EXPORTS,
+    );
+    mock::set_contract_address(voter.as_str());
+    mock::set_sender(voter.as_str());
+    mock::set_origin(voter.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+
+    let timelock = TimelockRef::new(timelock_address.clone());
+    timelock
+        .initialize(3600, vec![governor_address.clone()], vec![], voter.clone())
+        .unwrap();
+    let governor = GovernorRef::new(governor_address.clone());
+    governor
+        .initialize(
+            String::from("DAO"),
+            Address::contract([0x70; 32]),
+            timelock_address,
+            10,
+            100,
+            1,
+            1,
+        )
+        .unwrap();
+    mock::invoke(governor_address.as_str(), voter.as_str(), || {
+        Map::<Address, u64>::new("balances").set(&voter, &50)
+    })
+    .unwrap();
+
+    let targets = vec![Address::contract([8; 32]), Address::contract([9; 32])];
+    let id = governor
+        .propose(
+            String::from("Upgrade"),
+            String::from("Upgrade the treasury and the registry"),
+            targets.clone(),
+            vec![0, 5],
+            vec![vec![1], vec![2, 3]],
+        )
+        .unwrap();
+    mock::set_block_timestamp(1_700_000_020);
+    assert!(governor.cast_vote(id, 1).unwrap());
+
+    mock::set_block_timestamp(1_700_000_200);
+    governor.execute(id).unwrap();
+
+    // The timelock stored both calls as one batch under the recorded id.
+    let timelock_id = governor.get_proposal(id).unwrap().timelock_id.unwrap();
+    let salt = silica_contract_sdk::crypto::hash_blake3(&id.to_le_bytes());
+    assert_eq!(
+        timelock_id,
+        hash_operation_batch(&targets, &[0, 5], &[vec![1], vec![2, 3]], &None, &salt)
+    );
+    assert_eq!(
+        timelock.get_operation_state(timelock_id).unwrap(),
+        state::PENDING
+    );
+    assert_eq!(
+        timelock.get_timestamp_op(timelock_id).unwrap(),
+        1_700_003_800
+    );
+
+    mock::set_block_timestamp(1_700_003_800);
+    timelock
+        .execute_batch(targets, vec![0, 5], vec![vec![1], vec![2, 3]], None, salt)
+        .unwrap();
+    assert_eq!(
+        timelock.get_operation_state(timelock_id).unwrap(),
+        state::EXECUTED
+    );
+}
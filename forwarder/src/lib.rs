@@ -0,0 +1,80 @@
+//! Trusted Forwarder
+//!
+//! Relays meta-transactions on Chert Coin: users sign a `ForwardRequest`
+//! off-chain and any relayer submits it here, paying for execution. The target
+//! contract must trust this forwarder and read the acting account with
+//! `Context::msg_sender()`. Requests are signed for the chain the forwarder
+//! runs on, so there is nothing to initialize.
+//!
+//! ## Features
+//! - Signature Verification - Typed-data signatures bound to this forwarder
+//! - Replay Protection - Sequential per-signer nonces and request deadlines
+//! - Transparent Targets - Targets see the original signer, not the relayer
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+#[cfg(target_arch = "wasm32")]
+extern crate alloc;
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::crypto::Domain;
+use silica_contract_sdk::forwarder::{self, ForwardRequest};
+use silica_contract_sdk::interface;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::Nonces;
+
+pub use silica_contract_sdk::forwarder::{DOMAIN_NAME, DOMAIN_VERSION};
+
+/// Arguments of `execute`
+#[derive(Serialize, Deserialize)]
+pub struct ExecuteArgs {
+    pub request: ForwardRequest,
+    /// Ed25519 public key of `request.signer`
+    pub signer_key: [u8; 32],
+    /// Signature over the request's typed-data digest (64 bytes)
+    pub signature: Vec<u8>,
+}
+
+fn decode<T: for<'de> Deserialize<'de>>(input: &[u8]) -> ContractResult<T> {
+    postcard::from_bytes(input).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Signing domain of this forwarder on the chain it runs on, so requests
+/// signed for another chain or forwarder are rejected
+fn domain() -> ContractResult<Domain> {
+    let chain_id = try_context()?.chain_id();
+    Domain::for_current_contract(DOMAIN_NAME, DOMAIN_VERSION, chain_id)
+}
+
+/// Verify and relay a signed request, returning the target's return data
+///
+/// Fails the call if the request is expired, forged, replayed or the target
+/// fails.
+///
+/// # Arguments (call data)
+/// * `ExecuteArgs` - The request, the signer's public key and the signature
+#[unsafe(no_mangle)]
+pub extern "C" fn execute() {
+    interface::serve("execute", |_, input| {
+        let args: ExecuteArgs = decode(input)?;
+        let signature: [u8; 64] = args
+            .signature
+            .as_slice()
+            .try_into()
+            .map_err(|_| ContractError::InvalidSignature)?;
+        forwarder::execute(&domain()?, &args.signer_key, &args.request, &signature)
+    });
+}
+
+/// Return the next nonce a signer must use
+///
+/// # Arguments (call data)
+/// * `signer` - Address of the signer
+#[unsafe(no_mangle)]
+pub extern "C" fn get_nonce() {
+    interface::serve("get_nonce", |_, input| {
+        let nonce = Nonces::current(&decode::<Address>(input)?)?;
+        postcard::to_allocvec(&nonce).map_err(|_| ContractError::SerializationFailed)
+    });
+}
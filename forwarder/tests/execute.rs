@@ -0,0 +1,119 @@
+//! The exported entrypoints relay signed requests to a trusting target, as a
+//! relayer would submit them.
+
+use forwarder_contract::{DOMAIN_NAME, DOMAIN_VERSION, ExecuteArgs};
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::context::try_context;
+use silica_contract_sdk::crypto::Domain;
+use silica_contract_sdk::error::{ContractError, ContractResult};
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::forwarder::{self, ForwardRequest};
+use silica_contract_sdk::security::AccessControl;
+use silica_contract_sdk::storage::storage;
+use silica_contract_sdk::testing::Keyring;
+
+const NOW: u64 = 1_700_000_000;
+const CHAIN_ID: u64 = 7;
+const GOVERNOR_CODE: [u8; 32] = [0x60; 32];
+
+fn forwarder_address() -> Address {
+    Address::contract([0xf0; 32])
+}
+
+fn governor() -> Address {
+    Address::contract([0x60; 32])
+}
+
+/// Records who it thinks is voting, as a governor would.
+fn governor_code(entrypoint: &str, _input: &[u8]) -> ContractResult<Vec<u8>> {
+    assert_eq!(entrypoint, "vote");
+    let ctx = try_context()?;
+    let voter = ctx.msg_sender()?;
+    let support: bool =
+        postcard::from_bytes(&ctx.msg_data()?).map_err(|_| ContractError::DeserializationFailed)?;
+    storage().set("last_vote", &(voter, support))?;
+    Ok(Vec::new())
+}
+
+fn last_vote() -> Option<(Address, bool)> {
+    mock::invoke(governor().as_str(), forwarder_address().as_str(), || {
+        storage().get("last_vote").unwrap()
+    })
+}
+
+fn get_nonce(signer: &Address) -> u64 {
+    mock::set_call_data(&postcard::to_allocvec(signer).unwrap());
+    forwarder_contract::get_nonce();
+    postcard::from_bytes(&mock::take_return_data()).unwrap()
+}
+
+fn relay(args: &ExecuteArgs) -> Option<ContractError> {
+    mock::set_call_data(&postcard::to_allocvec(args).unwrap());
+    forwarder_contract::execute();
+    mock::take_failure()
+}
+
+#[test]
+fn relays_signed_requests_once() {
+    mock::reset();
+    let relayer = Address::from_public_key(&[1; 32]);
+    mock::set_contract_address(forwarder_address().as_str());
+    mock::set_sender(relayer.as_str());
+    mock::set_origin(relayer.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(NOW);
+    mock::set_chain_id(CHAIN_ID);
+    mock::register_code(GOVERNOR_CODE, governor_code);
+    mock::set_code_hash(governor().as_str(), GOVERNOR_CODE);
+    mock::invoke(governor().as_str(), relayer.as_str(), || {
+        AccessControl::initialize(&relayer).unwrap();
+        forwarder::set_trusted_forwarder(&relayer, &forwarder_address(), true).unwrap();
+    });
+
+    let alice = Keyring::new().alice();
+    let request = ForwardRequest {
+        signer: alice.address(),
+        target: governor(),
+        entrypoint: String::from("vote"),
+        data: postcard::to_allocvec(&true).unwrap(),
+        nonce: get_nonce(&alice.address()),
+        deadline: NOW + 60,
+    };
+    let domain = Domain::new(DOMAIN_NAME, DOMAIN_VERSION, CHAIN_ID, &forwarder_address());
+    let args = ExecuteArgs {
+        signer_key: alice.public_key(),
+        signature: alice.sign_typed(&domain, &request).unwrap().to_vec(),
+        request,
+    };
+
+    assert!(relay(&args).is_none());
+    assert_eq!(last_vote(), Some((alice.address(), true)));
+    assert_eq!(get_nonce(&alice.address()), 1);
+
+    // The same signed request cannot be relayed twice.
+    assert!(matches!(
+        relay(&args),
+        Some(ContractError::InvalidNonce { .. })
+    ));
+    assert_eq!(get_nonce(&alice.address()), 1);
+
+    // Nor can a request signed for another chain.
+    let mut request = args.request;
+    request.nonce = 1;
+    let other_chain = Domain::new(
+        DOMAIN_NAME,
+        DOMAIN_VERSION,
+        CHAIN_ID + 1,
+        &forwarder_address(),
+    );
+    let args = ExecuteArgs {
+        signer_key: alice.public_key(),
+        signature: alice.sign_typed(&other_chain, &request).unwrap().to_vec(),
+        request,
+    };
+    assert!(matches!(
+        relay(&args),
+        Some(ContractError::InvalidSignature)
+    ));
+    assert_eq!(get_nonce(&alice.address()), 1);
+}
@@ -12,6 +12,7 @@
 //! - Transaction History - Track all proposals and executions
 //! - Cancellation - Revoke pending transactions
 //! - Time Locks - Optional execution delays for security
+//! - Gasless Approvals - Accept requests relayed by a trusted forwarder
 
 #![cfg_attr(target_arch = "wasm32", no_std)]
 #![cfg_attr(target_arch = "wasm32", no_main)]
@@ -20,6 +21,8 @@
 extern crate alloc;
 
 use silica_contract_sdk::event;
+use silica_contract_sdk::forwarder;
+use silica_contract_sdk::interface::decode_call;
 use silica_contract_sdk::prelude::*;
 use silica_contract_sdk::storage::Vector;
 use serde::{Deserialize, Serialize};
@@ -27,11 +30,11 @@ use serde::{Deserialize, Serialize};
 /// Transaction structure
 #[derive(Serialize, Deserialize)]
 pub struct Transaction {
-    pub to: String,
+    pub to: Address,
     pub value: u64,
     pub data: Vec<u8>,
     pub description: String,
-    pub proposer: String,
+    pub proposer: Address,
     pub timestamp: u64,
     pub executed: bool,
     pub cancelled: bool,
@@ -48,398 +51,535 @@ pub struct WalletConfig {
     pub initialized: bool,
 }
 
-/// Initialize the multisig wallet
-///
-/// # Arguments
-/// * `owners` - Array of owner addresses (signers)
-/// * `threshold` - Number of required signatures (M)
-#[unsafe(no_mangle)]
-pub extern "C" fn initialize(owners: Vec<String>, threshold: u64) {
-    let ctx = context();
-    let deployer = ctx.sender();
+/// Entrypoints of the multisig wallet
+#[contract_interface]
+pub trait Multisig {
+    /// Initialize the multisig wallet
+    fn initialize(&mut self, owners: Vec<Address>, threshold: u64) -> ContractResult<()>;
+    /// Submit a new transaction for approval
+    fn submit_transaction(
+        &mut self,
+        to: Address,
+        value: u64,
+        data: Vec<u8>,
+        description: String,
+    ) -> ContractResult<u64>;
+    /// Approve a pending transaction
+    fn approve_transaction(&mut self, tx_id: u64) -> ContractResult<()>;
+    /// Revoke approval from a pending transaction
+    fn revoke_approval(&mut self, tx_id: u64) -> ContractResult<()>;
+    /// Execute a transaction that has reached threshold signatures
+    fn execute_transaction(&mut self, tx_id: u64) -> ContractResult<()>;
+    /// Cancel a pending transaction (requires M signatures)
+    fn cancel_transaction(&mut self, tx_id: u64) -> ContractResult<()>;
+    /// Set time lock on a transaction for additional security
+    fn set_time_lock(&mut self, tx_id: u64, delay_seconds: u64) -> ContractResult<()>;
+    /// Trust or stop trusting a forwarder to relay owners' signed requests
+    fn set_trusted_forwarder(&mut self, forwarder: Address, trusted: bool) -> ContractResult<()>;
+    /// Query function: Check if an address is an owner
+    fn is_owner(&self, address: Address) -> ContractResult<bool>;
+    /// Query function: Get the current threshold
+    fn get_threshold(&self) -> ContractResult<u64>;
+    /// Query function: Get the number of owners
+    fn get_owner_count(&self) -> ContractResult<u64>;
+    /// Query function: Get the number of approvals for a transaction
+    fn get_approval_count(&self, tx_id: u64) -> ContractResult<u64>;
+    /// Query function: Check if an owner has approved a transaction
+    fn has_approved(&self, tx_id: u64, owner: Address) -> ContractResult<bool>;
+    /// Query function: Check if a transaction can be executed
+    fn can_execute(&self, tx_id: u64) -> ContractResult<bool>;
+    /// Query function: Get the total transaction count
+    fn get_transaction_count(&self) -> ContractResult<u64>;
+}
 
-    // Validate parameters
-    if owners.is_empty() {
-        log("At least one owner required");
-        return;
-    }
+/// The wallet; each [`Multisig`] method below is an exported entrypoint
+pub struct MultisigWallet;
+
+#[export_interface]
+impl Multisig for MultisigWallet {
+    /// Initialize the multisig wallet
+    ///
+    /// # Arguments
+    /// * `owners` - Array of owner addresses (signers)
+    /// * `threshold` - Number of required signatures (M)
+    fn initialize(&mut self, owners: Vec<Address>, threshold: u64) -> ContractResult<()> {
+        if storage().has("config") {
+            return Err(rejected("Wallet already initialized"));
+        }
 
-    if threshold == 0 {
-        log("Threshold must be > 0");
-        return;
-    }
+        // Validate parameters
+        if owners.is_empty() {
+            return Err(invalid_argument("At least one owner required"));
+        }
 
-    if threshold > owners.len() as u64 {
-        log("Threshold cannot exceed number of owners");
-        return;
-    }
+        if threshold == 0 {
+            return Err(invalid_argument("Threshold must be > 0"));
+        }
 
-    if owners.len() > 50 {
-        log("Maximum 50 owners allowed");
-        return;
-    }
+        if threshold > owners.len() as u64 {
+            return Err(invalid_argument("Threshold cannot exceed number of owners"));
+        }
 
-    // Check for duplicate owners
-    let mut unique_owners = Vec::new();
-    for owner in &owners {
-        if !unique_owners.contains(owner) {
-            unique_owners.push(owner.clone());
+        if owners.len() > 50 {
+            return Err(invalid_argument("Maximum 50 owners allowed"));
         }
-    }
 
-    if unique_owners.len() != owners.len() {
-        log("Duplicate owners not allowed");
-        return;
-    }
+        // Check for duplicate owners
+        let mut unique_owners = Vec::new();
+        for owner in &owners {
+            if !unique_owners.contains(owner) {
+                unique_owners.push(owner.clone());
+            }
+        }
 
-    // Initialize wallet configuration
-    let config = WalletConfig {
-        threshold,
-        transaction_count: 0,
-        max_owners: 50,
-        max_time_lock: 30 * 24 * 60 * 60, // 30 days
-        initialized: true,
-    };
+        if unique_owners.len() != owners.len() {
+            return Err(invalid_argument("Duplicate owners not allowed"));
+        }
 
-    let mut storage_ref = storage();
-    if storage_ref.set("config", &config).is_err() {
-        log("Failed to store wallet config");
-        return;
-    }
+        // Initialize wallet configuration
+        let config = WalletConfig {
+            threshold,
+            transaction_count: 0,
+            max_owners: 50,
+            max_time_lock: 30 * 24 * 60 * 60, // 30 days
+            initialized: true,
+        };
+
+        let mut storage_ref = storage();
+        storage_ref.set("config", &config)?;
+
+        // The wallet administers its own settings, such as trusted forwarders,
+        // so changing them takes a transaction approved at threshold
+        AccessControl::initialize(context().contract_address())?;
+
+        // Initialize owner management
+        let mut is_owner: Map<Address, bool> = Map::new("is_owner");
+        let mut owners_vec: Vector<Address> = Vector::new("owners");
+
+        for owner in &unique_owners {
+            is_owner.set(owner, &true)?;
+            owners_vec.push(owner)?;
+        }
 
-    // Initialize owner management
-    let mut is_owner: Map<String, bool> = Map::new("is_owner");
-    let mut owners_vec: Vector<String> = Vector::new("owners");
+        info!(owners = unique_owners.len(), threshold = threshold; "Multisig wallet initialized");
+        event!("WalletCreated", owners: format!("{:?}", unique_owners), threshold: threshold);
+        Ok(())
+    }
+
+    /// Submit a new transaction for approval
+    ///
+    /// # Arguments
+    /// * `to` - Recipient address
+    /// * `value` - Amount of CHERT to send
+    /// * `data` - Contract call data (empty for simple transfers)
+    /// * `description` - Human-readable transaction description
+    ///
+    /// # Returns
+    /// Transaction ID
+    fn submit_transaction(
+        &mut self,
+        to: Address,
+        value: u64,
+        data: Vec<u8>,
+        description: String,
+    ) -> ContractResult<u64> {
+        let caller = acting_account()?;
+
+        // Check if caller is an owner
+        if !is_owner_check(&caller) {
+            return Err(rejected("Only owners can submit transactions"));
+        }
 
-    for owner in &unique_owners {
-        if is_owner.set(owner, &true).is_err() {
-            log("Failed to set owner status");
-            return;
+        // Validate parameters
+        if description.is_empty() {
+            return Err(invalid_argument("Description is required"));
         }
-        if owners_vec.push(owner).is_err() {
-            log("Failed to add owner to vector");
-            return;
+
+        if !data.is_empty() && decode_call(&data).is_err() {
+            return Err(invalid_argument("Call data must come from encode_call"));
         }
-    }
 
-    log(&format!(
-        "Multisig wallet initialized with {} owners, threshold: {}",
-        unique_owners.len(),
-        threshold
-    ));
-    event!("WalletCreated", owners: format!("{:?}", unique_owners), threshold: threshold);
-}
+        // Get wallet configuration
+        let mut storage_ref = storage();
+        let mut config = load_config()?;
+
+        // Create transaction
+        let timestamp = get_timestamp();
+        let transaction = Transaction {
+            to: to.clone(),
+            value,
+            data: data.clone(),
+            description: description.clone(),
+            proposer: caller.clone(),
+            timestamp,
+            executed: false,
+            cancelled: false,
+            time_lock: None,
+        };
+
+        // Store transaction
+        let mut transactions: Map<u64, Transaction> = Map::new("transactions");
+        let tx_id = config.transaction_count;
+        transactions.set(&tx_id, &transaction)?;
+
+        // Track approvals
+        let mut approvals: Map<u64, Vec<Address>> = Map::new("approvals");
+        let mut has_approved: Map<(u64, Address), bool> = Map::new("has_approved");
+
+        // Automatically approve transaction by proposer
+        let approvers = Vec::from([caller.clone()]);
+        approvals.set(&tx_id, &approvers)?;
+        has_approved.set(&(tx_id, caller.clone()), &true)?;
+
+        // Update transaction count
+        config.transaction_count += 1;
+        storage_ref.set("config", &config)?;
+
+        info!(
+            tx_id = tx_id, proposer = caller, to = to, value = value;
+            "Transaction submitted: {}", description
+        );
+        event!("TransactionSubmitted",
+            tx_id: tx_id,
+            proposer: caller,
+            to: to,
+            value: value,
+            description: description
+        );
+
+        Ok(tx_id)
+    }
+
+    /// Approve a pending transaction
+    ///
+    /// # Arguments
+    /// * `tx_id` - Transaction ID to approve
+    fn approve_transaction(&mut self, tx_id: u64) -> ContractResult<()> {
+        let caller = acting_account()?;
+
+        // Check if caller is an owner
+        if !is_owner_check(&caller) {
+            return Err(rejected("Only owners can approve transactions"));
+        }
 
-/// Check if caller is an owner
-fn is_owner_check() -> bool {
-    let ctx = context();
-    let storage = storage();
-    let is_owner: Map<String, bool> = Map::new("is_owner");
-    match is_owner.get(&ctx.sender().to_string()) {
-        Ok(Some(true)) => true,
-        _ => false,
-    }
-}
+        // Get transaction
+        let mut transactions: Map<u64, Transaction> = Map::new("transactions");
+        let transaction = load_transaction(&transactions, tx_id)?;
 
-/// Get current timestamp
-fn get_timestamp() -> u64 {
-    // In real implementation, this would use block timestamp
-    // For now, using a placeholder value
-    1700000000u64 // Unix timestamp placeholder
-}
+        // Check if transaction can be approved
+        if transaction.executed {
+            return Err(rejected("Transaction already executed"));
+        }
 
-/// Submit a new transaction for approval
-///
-/// # Arguments
-/// * `to` - Recipient address
-/// * `value` - Amount of CHERT to send
-/// * `data` - Contract call data (empty for simple transfers)
-/// * `description` - Human-readable transaction description
-///
-/// # Returns
-/// Transaction ID
-#[unsafe(no_mangle)]
-pub extern "C" fn submit_transaction(
-    to: String,
-    value: u64,
-    data: Vec<u8>,
-    description: String,
-) -> u64 {
-    let ctx = context();
-    let caller = ctx.sender();
+        if transaction.cancelled {
+            return Err(rejected("Transaction is cancelled"));
+        }
 
-    // Check if caller is an owner
-    if !is_owner_check() {
-        log("Only owners can submit transactions");
-        return 0;
-    }
+        // Check if already approved
+        let mut has_approved: Map<(u64, Address), bool> = Map::new("has_approved");
+        if has_approved.get(&(tx_id, caller.clone()))? == Some(true) {
+            return Err(rejected("Transaction already approved by this owner"));
+        }
 
-    // Validate parameters
-    if to.is_empty() {
-        log("Invalid recipient address");
-        return 0;
-    }
+        // Add approval
+        let mut approvals: Map<u64, Vec<Address>> = Map::new("approvals");
+        let mut approvers = approvals
+            .get(&tx_id)?
+            .ok_or_else(|| rejected("No approval record found"))?;
+
+        approvers.push(caller.clone());
+        approvals.set(&tx_id, &approvers)?;
+        has_approved.set(&(tx_id, caller.clone()), &true)?;
+
+        // Update transaction in storage
+        transactions.set(&tx_id, &transaction)?;
+
+        info!(tx_id = tx_id, approver = caller; "Transaction approved");
+        event!("TransactionApproved",
+            tx_id: tx_id,
+            approver: caller,
+            approval_count: approvers.len() as u64
+        );
+        Ok(())
+    }
+
+    /// Revoke approval from a pending transaction
+    ///
+    /// # Arguments
+    /// * `tx_id` - Transaction ID to revoke approval from
+    fn revoke_approval(&mut self, tx_id: u64) -> ContractResult<()> {
+        let caller = acting_account()?;
+
+        // Check if caller is an owner
+        if !is_owner_check(&caller) {
+            return Err(rejected("Only owners can revoke approvals"));
+        }
 
-    if description.is_empty() {
-        log("Description is required");
-        return 0;
-    }
+        // Get transaction
+        let mut transactions: Map<u64, Transaction> = Map::new("transactions");
+        let transaction = load_transaction(&transactions, tx_id)?;
 
-    // Get wallet configuration
-    let mut storage_ref = storage();
-    let config: WalletConfig = match storage_ref.get::<WalletConfig>("config") {
-        Ok(Some(c)) => c,
-        _ => {
-            log("Failed to load wallet config");
-            return 0;
+        // Check if transaction can be modified
+        if transaction.executed {
+            return Err(rejected("Cannot revoke approval from executed transaction"));
         }
-    };
 
-    // Create transaction
-    let timestamp = get_timestamp();
-    let transaction = Transaction {
-        to: to.clone(),
-        value,
-        data: data.clone(),
-        description: description.clone(),
-        proposer: caller.to_string(),
-        timestamp,
-        executed: false,
-        cancelled: false,
-        time_lock: None,
-    };
+        // Remove approval
+        let mut approvals: Map<u64, Vec<Address>> = Map::new("approvals");
+        let mut approvers = approvals
+            .get(&tx_id)?
+            .ok_or_else(|| rejected("No approval record found"))?;
 
-    // Store transaction
-    let mut transactions: Map<u64, Transaction> = Map::new("transactions");
-    let tx_id = config.transaction_count;
+        // Find and remove caller from approvers
+        let Some(pos) = approvers.iter().position(|x| *x == caller) else {
+            return Err(rejected("No approval to revoke"));
+        };
+        approvers.remove(pos);
+        approvals.set(&tx_id, &approvers)?;
 
-    if transactions.set(&tx_id, &transaction).is_err() {
-        log("Failed to store transaction");
-        return 0;
+        // Update approval tracking
+        let mut has_approved: Map<(u64, Address), bool> = Map::new("has_approved");
+        has_approved.set(&(tx_id, caller.clone()), &false)?;
+
+        // Update transaction in storage
+        transactions.set(&tx_id, &transaction)?;
+
+        info!(tx_id = tx_id, owner = caller; "Approval revoked");
+        event!("ApprovalRevoked", tx_id: tx_id, owner: caller);
+        Ok(())
     }
 
-    // Track approvals
-    let mut approvals: Map<u64, Vec<String>> = Map::new("approvals");
-    let mut has_approved: Map<(u64, String), bool> = Map::new("has_approved");
+    /// Execute a transaction that has reached threshold signatures
+    ///
+    /// # Arguments
+    /// * `tx_id` - Transaction ID to execute
+    fn execute_transaction(&mut self, tx_id: u64) -> ContractResult<()> {
+        let caller = acting_account()?;
 
-    // Automatically approve transaction by proposer
-    let mut approvers = Vec::new();
-    approvers.push(caller.to_string());
+        // Check if transaction can be executed
+        if !can_execute_transaction(tx_id) {
+            return Err(rejected("Transaction cannot be executed"));
+        }
 
-    if approvals.set(&tx_id, &approvers).is_err() {
-        log("Failed to store approvals");
-        return 0;
-    }
+        // Get transaction
+        let mut transactions: Map<u64, Transaction> = Map::new("transactions");
+        let mut transaction = load_transaction(&transactions, tx_id)?;
 
-    if has_approved
-        .set(&(tx_id, caller.to_string()), &true)
-        .is_err()
-    {
-        log("Failed to track approval");
-        return 0;
-    }
+        // Mark as executed before calling out, so the call cannot execute it again
+        transaction.executed = true;
+        transactions.set(&tx_id, &transaction)?;
+
+        // Send the value, then make the call; a failure reverts the execution
+        let ctx = context();
+        if transaction.value > 0 {
+            ctx.transfer_tokens(&transaction.to, transaction.value)?;
+        }
+        if !transaction.data.is_empty() {
+            let (entrypoint, input) = decode_call(&transaction.data)?;
+            ctx.call_contract(&transaction.to, &entrypoint, &input)?;
+        }
+
+        info!(
+            tx_id = tx_id, executor = caller, to = transaction.to, value = transaction.value;
+            "Transaction executed: {}", transaction.description
+        );
 
-    // Update transaction count
-    let mut config_mut = config;
-    config_mut.transaction_count += 1;
-    if storage_ref.set("config", &config_mut).is_err() {
-        log("Failed to update transaction count");
-        return 0;
+        event!("TransactionExecuted", tx_id: tx_id, executor: caller);
+        Ok(())
     }
 
-    log(&format!(
-        "Transaction {} submitted by {}: {} {} CHERT to {}",
-        tx_id, caller, description, value, to
-    ));
-    event!("TransactionSubmitted",
-        tx_id: tx_id,
-        proposer: caller,
-        to: to,
-        value: value,
-        description: description
-    );
-
-    tx_id
-}
+    /// Cancel a pending transaction (requires M signatures)
+    ///
+    /// # Arguments
+    /// * `tx_id` - Transaction ID to cancel
+    fn cancel_transaction(&mut self, tx_id: u64) -> ContractResult<()> {
+        let caller = acting_account()?;
 
-/// Approve a pending transaction
-///
-/// # Arguments
-/// * `tx_id` - Transaction ID to approve
-#[unsafe(no_mangle)]
-pub extern "C" fn approve_transaction(tx_id: u64) {
-    let ctx = context();
-    let caller = ctx.sender();
+        // Check if caller is an owner
+        if !is_owner_check(&caller) {
+            return Err(rejected("Only owners can cancel transactions"));
+        }
 
-    // Check if caller is an owner
-    if !is_owner_check() {
-        log("Only owners can approve transactions");
-        return;
-    }
+        // Get transaction
+        let mut transactions: Map<u64, Transaction> = Map::new("transactions");
+        let mut transaction = load_transaction(&transactions, tx_id)?;
 
-    // Get transaction
-    let mut transactions: Map<u64, Transaction> = Map::new("transactions");
-    let mut transaction = match transactions.get(&tx_id) {
-        Ok(Some(tx)) => tx,
-        Ok(None) => {
-            log("Transaction does not exist");
-            return;
+        // Check if transaction can be cancelled
+        if transaction.executed {
+            return Err(rejected("Cannot cancel executed transaction"));
         }
-        Err(_) => {
-            log("Failed to read transaction");
-            return;
+
+        if transaction.cancelled {
+            return Err(rejected("Transaction is already cancelled"));
         }
-    };
 
-    // Check if transaction can be approved
-    if transaction.executed {
-        log("Transaction already executed");
-        return;
-    }
+        // Check if caller has already approved (makes it harder to cancel)
+        let has_approved: Map<(u64, Address), bool> = Map::new("has_approved");
+        let has_approved_by_caller = has_approved.get(&(tx_id, caller.clone()))? == Some(true);
 
-    if transaction.cancelled {
-        log("Transaction is cancelled");
-        return;
-    }
+        // Simple cancellation - any owner can cancel, but log it
+        // In a real implementation, you might require M signatures like execution
 
-    // Check if already approved
-    let mut has_approved: Map<(u64, String), bool> = Map::new("has_approved");
-    if has_approved
-        .get(&(tx_id, caller.to_string()))
-        .ok()
-        .flatten()
-        == Some(true)
-    {
-        log("Transaction already approved by this owner");
-        return;
+        transaction.cancelled = true;
+        transactions.set(&tx_id, &transaction)?;
+
+        info!(
+            tx_id = tx_id, canceller = caller, approved = has_approved_by_caller;
+            "Transaction cancelled"
+        );
+        event!("TransactionCancelled", tx_id: tx_id);
+        Ok(())
     }
 
-    // Add approval
-    let mut approvals: Map<u64, Vec<String>> = Map::new("approvals");
-    let mut approvers = match approvals.get(&tx_id) {
-        Ok(Some(list)) => list,
-        Ok(None) => {
-            log("No approval record found");
-            return;
+    /// Set time lock on a transaction for additional security
+    ///
+    /// # Arguments
+    /// * `tx_id` - Transaction ID
+    /// * `delay_seconds` - Seconds to wait after threshold reached
+    fn set_time_lock(&mut self, tx_id: u64, delay_seconds: u64) -> ContractResult<()> {
+        let caller = acting_account()?;
+
+        // Check if caller is an owner
+        if !is_owner_check(&caller) {
+            return Err(rejected("Only owners can set time locks"));
         }
-        Err(_) => {
-            log("Failed to read approvals");
-            return;
+
+        // Get wallet configuration
+        let config = load_config()?;
+
+        // Validate delay
+        if delay_seconds > config.max_time_lock {
+            return Err(invalid_argument("Time lock exceeds maximum allowed"));
         }
-    };
 
-    approvers.push(caller.to_string());
+        // Get transaction
+        let mut transactions: Map<u64, Transaction> = Map::new("transactions");
+        let mut transaction = load_transaction(&transactions, tx_id)?;
 
-    if approvals.set(&tx_id, &approvers).is_err() {
-        log("Failed to update approvals");
-        return;
-    }
+        // Check if transaction can be time-locked
+        if transaction.executed {
+            return Err(rejected("Cannot set time lock on executed transaction"));
+        }
 
-    if has_approved
-        .set(&(tx_id, caller.to_string()), &true)
-        .is_err()
-    {
-        log("Failed to track approval");
-        return;
-    }
+        if transaction.cancelled {
+            return Err(rejected("Cannot set time lock on cancelled transaction"));
+        }
 
-    // Update transaction in storage
-    if transactions.set(&tx_id, &transaction).is_err() {
-        log("Failed to update transaction");
-        return;
-    }
+        // Set time lock
+        let unlock_time = get_timestamp() + delay_seconds;
+        transaction.time_lock = Some(unlock_time);
+        transactions.set(&tx_id, &transaction)?;
 
-    log(&format!("Transaction {} approved by {}", tx_id, caller));
-    event!("TransactionApproved",
-        tx_id: tx_id,
-        approver: caller,
-        approval_count: approvers.len() as u64
-    );
-}
+        info!(tx_id = tx_id, unlock_time = unlock_time; "Time lock set");
+        event!("TimeLockSet", tx_id: tx_id, unlock_time: unlock_time);
+        Ok(())
+    }
 
-/// Revoke approval from a pending transaction
-///
-/// # Arguments
-/// * `tx_id` - Transaction ID to revoke approval from
-#[unsafe(no_mangle)]
-pub extern "C" fn revoke_approval(tx_id: u64) {
-    let ctx = context();
-    let caller = ctx.sender();
+    /// Trust or stop trusting a forwarder to relay owners' signed requests
+    /// (only through a wallet transaction)
+    ///
+    /// Owners can then act through the forwarder without paying for execution.
+    /// A trusted forwarder can act for every owner, so the change must be a
+    /// transaction to the wallet itself that reached the threshold.
+    ///
+    /// # Arguments
+    /// * `forwarder` - Forwarder contract address
+    /// * `trusted` - Whether calls it relays act for their signer
+    fn set_trusted_forwarder(&mut self, forwarder: Address, trusted: bool) -> ContractResult<()> {
+        let ctx = context();
+        forwarder::set_trusted_forwarder(ctx.sender(), &forwarder, trusted)?;
 
-    // Check if caller is an owner
-    if !is_owner_check() {
-        log("Only owners can revoke approvals");
-        return;
+        info!(forwarder = forwarder, trusted = trusted; "Trusted forwarder updated");
+        Ok(())
     }
 
-    // Get transaction
-    let mut transactions: Map<u64, Transaction> = Map::new("transactions");
-    let mut transaction = match transactions.get(&tx_id) {
-        Ok(Some(tx)) => tx,
-        Ok(None) => {
-            log("Transaction does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read transaction");
-            return;
-        }
-    };
+    /// Query function: Check if an address is an owner
+    fn is_owner(&self, address: Address) -> ContractResult<bool> {
+        let is_owner: Map<Address, bool> = Map::new("is_owner");
+        Ok(is_owner.get(&address)? == Some(true))
+    }
 
-    // Check if transaction can be modified
-    if transaction.executed {
-        log("Cannot revoke approval from executed transaction");
-        return;
+    /// Query function: Get the current threshold
+    fn get_threshold(&self) -> ContractResult<u64> {
+        let storage_ref = storage();
+        let config = storage_ref.get::<WalletConfig>("config")?;
+        Ok(config.map_or(0, |c| c.threshold))
     }
 
-    // Remove approval
-    let mut approvals: Map<u64, Vec<String>> = Map::new("approvals");
-    let mut approvers = match approvals.get(&tx_id) {
-        Ok(Some(list)) => list,
-        Ok(None) => {
-            log("No approval record found");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read approvals");
-            return;
-        }
-    };
+    /// Query function: Get the number of owners
+    fn get_owner_count(&self) -> ContractResult<u64> {
+        let owners_vec: Vector<Address> = Vector::new("owners");
+        owners_vec.len()
+    }
 
-    // Find and remove caller from approvers
-    if let Some(pos) = approvers.iter().position(|x| x == &caller) {
-        approvers.remove(pos);
-    } else {
-        log("No approval to revoke");
-        return;
+    /// Query function: Get the number of approvals for a transaction
+    fn get_approval_count(&self, tx_id: u64) -> ContractResult<u64> {
+        let approvals: Map<u64, Vec<Address>> = Map::new("approvals");
+        Ok(approvals.get(&tx_id)?.map_or(0, |list| list.len() as u64))
     }
 
-    if approvals.set(&tx_id, &approvers).is_err() {
-        log("Failed to update approvals");
-        return;
+    /// Query function: Check if an owner has approved a transaction
+    fn has_approved(&self, tx_id: u64, owner: Address) -> ContractResult<bool> {
+        let has_approved: Map<(u64, Address), bool> = Map::new("has_approved");
+        Ok(has_approved.get(&(tx_id, owner))? == Some(true))
     }
 
-    // Update approval tracking
-    let mut has_approved: Map<(u64, String), bool> = Map::new("has_approved");
-    if has_approved
-        .set(&(tx_id, caller.to_string()), &false)
-        .is_err()
-    {
-        log("Failed to update approval tracking");
-        return;
+    /// Query function: Check if a transaction can be executed
+    fn can_execute(&self, tx_id: u64) -> ContractResult<bool> {
+        Ok(can_execute_transaction(tx_id))
     }
 
-    // Update transaction in storage
-    if transactions.set(&tx_id, &transaction).is_err() {
-        log("Failed to update transaction");
-        return;
+    /// Query function: Get the total transaction count
+    fn get_transaction_count(&self) -> ContractResult<u64> {
+        let storage_ref = storage();
+        let config = storage_ref.get::<WalletConfig>("config")?;
+        Ok(config.map_or(0, |c| c.transaction_count))
     }
+}
 
-    log(&format!(
-        "Approval revoked from transaction {} by {}",
-        tx_id, caller
-    ));
-    event!("ApprovalRevoked", tx_id: tx_id, owner: caller);
+/// Error for a call the wallet refuses
+fn rejected(message: &str) -> ContractError {
+    ContractError::Custom(String::from(message))
+}
+
+/// Error for a call whose arguments the wallet refuses
+fn invalid_argument(message: &str) -> ContractError {
+    ContractError::InvalidArgument(String::from(message))
+}
+
+/// Account the call acts for: the signer of a request relayed by a trusted
+/// forwarder, otherwise the sender
+fn acting_account() -> ContractResult<Address> {
+    context().msg_sender()
+}
+
+/// Load the wallet configuration
+fn load_config() -> ContractResult<WalletConfig> {
+    storage()
+        .get("config")?
+        .ok_or_else(|| rejected("Wallet is not initialized"))
+}
+
+/// Load a stored transaction
+fn load_transaction(
+    transactions: &Map<u64, Transaction>,
+    tx_id: u64,
+) -> ContractResult<Transaction> {
+    transactions
+        .get(&tx_id)?
+        .ok_or_else(|| rejected("Transaction does not exist"))
+}
+
+/// Check if caller is an owner
+fn is_owner_check(caller: &Address) -> bool {
+    let is_owner: Map<Address, bool> = Map::new("is_owner");
+    matches!(is_owner.get(caller), Ok(Some(true)))
+}
+
+/// Get current timestamp
+fn get_timestamp() -> u64 {
+    let ctx = context();
+    ctx.block_timestamp()
 }
 
 /// Check if transaction can be executed
@@ -450,7 +590,7 @@ fn can_execute_transaction(tx_id: u64) -> bool {
     };
 
     // Check approval count
-    let approvals: Map<u64, Vec<String>> = Map::new("approvals");
+    let approvals: Map<u64, Vec<Address>> = Map::new("approvals");
     let approvers = match approvals.get(&tx_id) {
         Ok(Some(list)) => list,
         _ => return false,
@@ -462,7 +602,7 @@ fn can_execute_transaction(tx_id: u64) -> bool {
 
     // Check transaction state
     let transactions: Map<u64, Transaction> = Map::new("transactions");
-    let mut transaction = match transactions.get(&tx_id) {
+    let transaction = match transactions.get(&tx_id) {
         Ok(Some(tx)) => tx,
         _ => return false,
     };
@@ -472,262 +612,11 @@ fn can_execute_transaction(tx_id: u64) -> bool {
     }
 
     // Check time lock
-    if let Some(unlock_time) = transaction.time_lock {
-        if get_timestamp() < unlock_time {
-            return false;
-        }
+    if let Some(unlock_time) = transaction.time_lock
+        && get_timestamp() < unlock_time
+    {
+        return false;
     }
 
     true
 }
-
-/// Execute a transaction that has reached threshold signatures
-///
-/// # Arguments
-/// * `tx_id` - Transaction ID to execute
-#[unsafe(no_mangle)]
-pub extern "C" fn execute_transaction(tx_id: u64) {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Check if transaction can be executed
-    if !can_execute_transaction(tx_id) {
-        log("Transaction cannot be executed");
-        return;
-    }
-
-    // Get transaction
-    let mut transactions: Map<u64, Transaction> = Map::new("transactions");
-    let mut transaction = match transactions.get(&tx_id) {
-        Ok(Some(tx)) => tx,
-        Ok(None) => {
-            log("Transaction does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read transaction");
-            return;
-        }
-    };
-
-    // Mark as executed
-    transaction.executed = true;
-    if transactions.set(&tx_id, &transaction).is_err() {
-        log("Failed to update transaction");
-        return;
-    }
-
-    // In a real implementation, this is where you'd execute the actual call
-    // For now, we'll just log the execution
-    log(&format!(
-        "Transaction {} executed by {}: {} {} CHERT to {}",
-        tx_id, caller, transaction.description, transaction.value, transaction.to
-    ));
-
-    event!("TransactionExecuted", tx_id: tx_id, executor: caller);
-}
-
-/// Cancel a pending transaction (requires M signatures)
-///
-/// # Arguments
-/// * `tx_id` - Transaction ID to cancel
-#[unsafe(no_mangle)]
-pub extern "C" fn cancel_transaction(tx_id: u64) {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Check if caller is an owner
-    if !is_owner_check() {
-        log("Only owners can cancel transactions");
-        return;
-    }
-
-    // Get transaction
-    let mut transactions: Map<u64, Transaction> = Map::new("transactions");
-    let mut transaction = match transactions.get(&tx_id) {
-        Ok(Some(tx)) => tx,
-        Ok(None) => {
-            log("Transaction does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read transaction");
-            return;
-        }
-    };
-
-    // Check if transaction can be cancelled
-    if transaction.executed {
-        log("Cannot cancel executed transaction");
-        return;
-    }
-
-    if transaction.cancelled {
-        log("Transaction is already cancelled");
-        return;
-    }
-
-    // Check if caller has already approved (makes it harder to cancel)
-    let has_approved: Map<(u64, String), bool> = Map::new("has_approved");
-    let has_approved_by_caller = has_approved
-        .get(&(tx_id, caller.to_string()))
-        .ok()
-        .flatten()
-        == Some(true);
-
-    // Simple cancellation - any owner can cancel, but log it
-    // In a real implementation, you might require M signatures like execution
-
-    transaction.cancelled = true;
-    if transactions.set(&tx_id, &transaction).is_err() {
-        log("Failed to update transaction");
-        return;
-    }
-
-    log(&format!(
-        "Transaction {} cancelled by {} (approved: {})",
-        tx_id, caller, has_approved_by_caller
-    ));
-    event!("TransactionCancelled", tx_id: tx_id);
-}
-
-/// Set time lock on a transaction for additional security
-///
-/// # Arguments
-/// * `tx_id` - Transaction ID
-/// * `delay_seconds` - Seconds to wait after threshold reached
-#[unsafe(no_mangle)]
-pub extern "C" fn set_time_lock(tx_id: u64, delay_seconds: u64) {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Check if caller is an owner
-    if !is_owner_check() {
-        log("Only owners can set time locks");
-        return;
-    }
-
-    // Get wallet configuration
-    let storage = storage();
-    let config: WalletConfig = match storage.get("config") {
-        Ok(Some(c)) => c,
-        _ => {
-            log("Failed to load wallet config");
-            return;
-        }
-    };
-
-    // Validate delay
-    if delay_seconds > config.max_time_lock {
-        log("Time lock exceeds maximum allowed");
-        return;
-    }
-
-    // Get transaction
-    let mut transactions: Map<u64, Transaction> = Map::new("transactions");
-    let mut transaction = match transactions.get(&tx_id) {
-        Ok(Some(tx)) => tx,
-        Ok(None) => {
-            log("Transaction does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read transaction");
-            return;
-        }
-    };
-
-    // Check if transaction can be time-locked
-    if transaction.executed {
-        log("Cannot set time lock on executed transaction");
-        return;
-    }
-
-    if transaction.cancelled {
-        log("Cannot set time lock on cancelled transaction");
-        return;
-    }
-
-    // Set time lock
-    let unlock_time = get_timestamp() + delay_seconds;
-    transaction.time_lock = Some(unlock_time);
-
-    if transactions.set(&tx_id, &transaction).is_err() {
-        log("Failed to update transaction");
-        return;
-    }
-
-    log(&format!(
-        "Time lock set on transaction {}: unlocks at timestamp {}",
-        tx_id, unlock_time
-    ));
-    event!("TimeLockSet", tx_id: tx_id, unlock_time: unlock_time);
-}
-
-/// Query function: Check if an address is an owner
-#[unsafe(no_mangle)]
-pub extern "C" fn is_owner(address: String) -> bool {
-    let is_owner: Map<String, bool> = Map::new("is_owner");
-    match is_owner.get(&address) {
-        Ok(Some(true)) => true,
-        _ => false,
-    }
-}
-
-/// Query function: Get the current threshold
-#[unsafe(no_mangle)]
-pub extern "C" fn get_threshold() -> u64 {
-    let storage_ref = storage();
-    let config_value = match storage_ref.get::<WalletConfig>("config") {
-        Ok(Some(c)) => c.threshold,
-        _ => 0,
-    };
-    config_value
-}
-
-/// Query function: Get the number of owners
-#[unsafe(no_mangle)]
-pub extern "C" fn get_owner_count() -> u64 {
-    let owners_vec: Vector<String> = Vector::new("owners");
-    match owners_vec.len() {
-        Ok(count) => count,
-        _ => 0,
-    }
-}
-
-/// Query function: Get the number of approvals for a transaction
-#[unsafe(no_mangle)]
-pub extern "C" fn get_approval_count(tx_id: u64) -> u64 {
-    let approvals: Map<u64, Vec<String>> = Map::new("approvals");
-    match approvals.get(&tx_id) {
-        Ok(Some(list)) => list.len() as u64,
-        _ => 0,
-    }
-}
-
-/// Query function: Check if an owner has approved a transaction
-#[unsafe(no_mangle)]
-pub extern "C" fn has_approved(tx_id: u64, owner: String) -> bool {
-    let has_approved: Map<(u64, String), bool> = Map::new("has_approved");
-    match has_approved.get(&(tx_id, owner)) {
-        Ok(Some(true)) => true,
-        _ => false,
-    }
-}
-
-/// Query function: Check if a transaction can be executed
-#[unsafe(no_mangle)]
-pub extern "C" fn can_execute(tx_id: u64) -> bool {
-    can_execute_transaction(tx_id)
-}
-
-/// Query function: Get the total transaction count
-#[unsafe(no_mangle)]
-pub extern "C" fn get_transaction_count() -> u64 {
-    let storage_ref = storage();
-    let config_value = match storage_ref.get::<WalletConfig>("config") {
-        Ok(Some(c)) => c.transaction_count,
-        _ => 0,
-    };
-    config_value
-}
@@ -0,0 +1,74 @@
+//! Machine-readable interface of the multisig wallet, checked against `abi.json`.
+
+use multisig_contract::{MultisigRef, MultisigWallet, Transaction, WalletConfig};
+use silica_contract_sdk::abi::{self, AbiBuilder, ContractAbi};
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::security::DEFAULT_ADMIN_ROLE;
+
+fn multisig_abi() -> ContractAbi {
+    AbiBuilder::new("multisig-contract", env!("CARGO_PKG_VERSION"))
+        .interface(MultisigRef::describe)
+        .string_event("WalletCreated", &["owners", "threshold"])
+        .string_event(
+            "TransactionSubmitted",
+            &["tx_id", "proposer", "to", "value", "description"],
+        )
+        .string_event(
+            "TransactionApproved",
+            &["tx_id", "approver", "approval_count"],
+        )
+        .string_event("ApprovalRevoked", &["tx_id", "owner"])
+        .string_event("TransactionExecuted", &["tx_id", "executor"])
+        .string_event("TransactionCancelled", &["tx_id"])
+        .string_event("TimeLockSet", &["tx_id", "unlock_time"])
+        .error("NotOwner", "Only owners can perform this action")
+        .error(
+            "InvalidThreshold",
+            "Threshold must be > 0 and <= number of owners",
+        )
+        .error("TooManyOwners", "Maximum 50 owners allowed")
+        .error("InvalidRecipient", "Invalid recipient address")
+        .error("TransactionNotFound", "Transaction does not exist")
+        .error(
+            "AlreadyApproved",
+            "Transaction already approved by this owner",
+        )
+        .error("NoApproval", "No approval to revoke")
+        .error("NotExecutable", "Transaction cannot be executed")
+        .error("AlreadyExecuted", "Transaction already executed")
+        .error("TransactionCancelled", "Transaction is cancelled")
+        .error("TimeLockTooLong", "Time lock exceeds maximum allowed")
+        .access_control(&[DEFAULT_ADMIN_ROLE])
+        .trusted_forwarders()
+        .value::<WalletConfig>("config")
+        .map::<Address, bool>("is_owner")
+        .vector::<Address>("owners")
+        .map::<u64, Transaction>("transactions")
+        .map::<u64, Vec<Address>>("approvals")
+        .map::<(u64, Address), bool>("has_approved")
+        .build()
+        .expect("multisig ABI")
+}
+
+#[test]
+fn abi_is_compatible() {
+    abi::check_file(
+        &multisig_abi(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/abi.json"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn abi_entrypoints_are_exported() {
+    // Builds require every ABI entrypoint as an export without parameters.
+    for entrypoint in multisig_abi().entrypoints {
+        assert!(
+            MultisigWallet::EXPORTS
+                .iter()
+                .any(|(name, _)| *name == entrypoint.name),
+            "{} is not exported",
+            entrypoint.name
+        );
+    }
+}
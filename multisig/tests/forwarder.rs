@@ -0,0 +1,72 @@
+//! A trusted forwarder can act for every owner, so only a wallet transaction
+//! approved at threshold can trust one.
+
+use multisig_contract::{MultisigRef, MultisigWallet};
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::forwarder;
+use silica_contract_sdk::interface::encode_call;
+
+/// Call the wallet as `owner`.
+fn as_owner(owner: &Address) {
+    mock::set_contract_address(owner.as_str());
+    mock::set_sender(owner.as_str());
+}
+
+#[test]
+fn only_approved_wallet_transactions_trust_a_forwarder() {
+    mock::reset();
+    let alice = Address::from_public_key(&[1; 32]);
+    let bob = Address::from_public_key(&[2; 32]);
+    let wallet_address = Address::contract([0x5a; 32]);
+    let trusted_forwarder = Address::contract([0xf0; 32]);
+    mock::deploy_exports([0x5a; 32], wallet_address.as_str(), MultisigWallet::EXPORTS);
+    mock::set_origin(alice.as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+    let is_trusted = || {
+        mock::invoke(wallet_address.as_str(), alice.as_str(), || {
+            forwarder::is_trusted_forwarder(&trusted_forwarder)
+        })
+        .unwrap()
+    };
+
+    let wallet = MultisigRef::new(wallet_address.clone());
+    as_owner(&alice);
+    wallet
+        .initialize(vec![alice.clone(), bob.clone()], 2)
+        .unwrap();
+
+    // Neither the deployer nor any single owner can trust it directly.
+    assert!(
+        wallet
+            .set_trusted_forwarder(trusted_forwarder.clone(), true)
+            .is_err()
+    );
+    assert!(!is_trusted());
+
+    let data = encode_call("set_trusted_forwarder", &(&trusted_forwarder, true)).unwrap();
+    let tx_id = wallet
+        .submit_transaction(
+            wallet_address.clone(),
+            0,
+            data,
+            String::from("Trust the forwarder"),
+        )
+        .unwrap();
+    assert!(wallet.execute_transaction(tx_id).is_err());
+    assert!(!is_trusted());
+
+    // Once the second owner approves, executing makes the wallet call itself.
+    as_owner(&bob);
+    wallet.approve_transaction(tx_id).unwrap();
+    wallet.execute_transaction(tx_id).unwrap();
+    assert!(is_trusted());
+
+    // Call data that does not name an entrypoint is refused up front.
+    assert!(
+        wallet
+            .submit_transaction(wallet_address, 0, vec![0xff], String::from("Garbage"),)
+            .is_err()
+    );
+}
@@ -18,47 +18,37 @@
 #[cfg(target_arch = "wasm32")]
 extern crate alloc;
 
-#[cfg(target_arch = "wasm32")]
-use alloc::vec;
-
-use blake3::Hasher;
 use silica_contract_sdk::event;
 use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::DEFAULT_ADMIN_ROLE;
+use silica_contract_sdk::timelock::{Timelock, hash_operation, hash_operation_batch};
+pub use silica_contract_sdk::timelock::OperationState;
 use serde::{Deserialize, Serialize};
 
-/// Role enumeration for access control
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
-pub enum Role {
-    PROPOSER_ROLE,
-    EXECUTOR_ROLE,
-    CANCELLER_ROLE,
-    ADMIN_ROLE,
-}
-
-impl core::fmt::Display for Role {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        match self {
-            Role::PROPOSER_ROLE => write!(f, "PROPOSER_ROLE"),
-            Role::EXECUTOR_ROLE => write!(f, "EXECUTOR_ROLE"),
-            Role::CANCELLER_ROLE => write!(f, "CANCELLER_ROLE"),
-            Role::ADMIN_ROLE => write!(f, "ADMIN_ROLE"),
-        }
+/// Role allowed to schedule operations
+pub const PROPOSER_ROLE: &str = "PROPOSER_ROLE";
+/// Role allowed to execute ready operations (unrestricted while it has no members)
+pub const EXECUTOR_ROLE: &str = "EXECUTOR_ROLE";
+/// Role allowed to cancel pending operations
+pub const CANCELLER_ROLE: &str = "CANCELLER_ROLE";
+/// Administrator role, shared with the SDK's access control
+pub const ADMIN_ROLE: &str = DEFAULT_ADMIN_ROLE;
+
+/// Map the numeric role id used by the external interface to a role name
+fn role_from_id(role: u8) -> Option<&'static str> {
+    match role {
+        0 => Some(PROPOSER_ROLE),
+        1 => Some(EXECUTOR_ROLE),
+        2 => Some(CANCELLER_ROLE),
+        3 => Some(ADMIN_ROLE),
+        _ => None,
     }
 }
 
-/// Operation state enumeration
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
-pub enum OperationState {
-    Unset,    // Operation doesn't exist
-    Pending,  // Scheduled, waiting for delay
-    Ready,    // Delay expired, can execute
-    Executed, // Already executed
-}
-
 /// Operation structure
 #[derive(Serialize, Deserialize)]
 pub struct Operation {
-    pub target: String,
+    pub target: Address,
     pub value: u64,
     pub data: Vec<u8>,
     pub predecessor: Option<[u8; 32]>,
@@ -68,863 +58,685 @@ pub struct Operation {
     pub cancelled: bool,
 }
 
+/// Batch operation structure; its calls execute together
+#[derive(Serialize, Deserialize)]
+pub struct BatchOperation {
+    pub targets: Vec<Address>,
+    pub values: Vec<u64>,
+    pub datas: Vec<Vec<u8>>,
+    pub predecessor: Option<[u8; 32]>,
+    pub salt: [u8; 32],
+    pub ready_timestamp: u64,
+    pub executed: bool,
+    pub cancelled: bool,
+}
+
+/// Progress of a scheduled operation, single or batch
+struct Progress {
+    ready_timestamp: u64,
+    executed: bool,
+    cancelled: bool,
+}
+
 /// Timelock configuration
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TimelockConfig {
     pub min_delay: u64,
     pub max_delay: u64,
     pub operation_counter: u64,
-    pub admin: String,
+    pub admin: Address,
     pub initialized: bool,
 }
 
-/// Initialize the timelock contract
-///
-/// # Arguments (should be parsed from transaction data)
-/// * `min_delay` - Minimum delay in seconds (e.g., 2 days = 172800)
-/// * `proposers` - Array of addresses that can schedule operations
-/// * `executors` - Array of addresses that can execute (empty = anyone)
-/// * `admin` - Address with admin role (typically the timelock itself)
-#[unsafe(no_mangle)]
-pub extern "C" fn initialize() {
-    let ctx = context();
-    let deployer = ctx.sender();
-
-    // In a real implementation, these would be parsed from transaction data
-    // For now, using example values from specification
-    let min_delay = 172800u64; // 2 days
-    let proposers: Vec<String> = vec![deployer.to_string()]; // Deployer as default proposer
-    let executors: Vec<String> = vec![]; // Anyone can execute by default
-    let admin = deployer.to_string(); // Self-administered
-
-    // Validate parameters
-    if min_delay == 0 || min_delay > 30 * 24 * 60 * 60 {
-        log("Invalid minimum delay: must be > 0 and <= 30 days");
-        return;
-    }
-
-    if proposers.is_empty() {
-        log("At least one proposer required");
-        return;
-    }
-
-    // Initialize timelock configuration
-    let config = TimelockConfig {
-        min_delay,
-        max_delay: 30 * 24 * 60 * 60, // 30 days
-        operation_counter: 0,
-        admin: admin.clone(),
-        initialized: true,
-    };
-
-    let mut storage = storage();
-    if storage.set("config", &config).is_err() {
-        log("Failed to store timelock config");
-        return;
-    }
-
-    // Initialize role mappings
-    let mut roles: Map<(Role, String), bool> = Map::new("roles");
-    let mut role_counts: Map<Role, u64> = Map::new("role_counts");
-
-    // Grant roles to initial accounts
-    for proposer in &proposers {
-        if roles
-            .set(&(Role::PROPOSER_ROLE, proposer.clone()), &true)
-            .is_err()
-        {
-            log("Failed to set proposer role");
-            return;
-        }
-    }
-
-    for executor in &executors {
-        if roles
-            .set(&(Role::EXECUTOR_ROLE, executor.clone()), &true)
-            .is_err()
-        {
-            log("Failed to set executor role");
-            return;
-        }
-    }
-
-    if roles
-        .set(&(Role::ADMIN_ROLE, admin.to_string()), &true)
-        .is_err()
-    {
-        log("Failed to set admin role");
-        return;
-    }
-
-    // Update role counts
-    let proposer_count = proposers.len() as u64;
-    let executor_count = executors.len() as u64;
-
-    if role_counts
-        .set(&Role::PROPOSER_ROLE, &proposer_count)
-        .is_err()
-        || role_counts
-            .set(&Role::EXECUTOR_ROLE, &executor_count)
-            .is_err()
-        || role_counts.set(&Role::ADMIN_ROLE, &1).is_err()
-        || role_counts.set(&Role::CANCELLER_ROLE, &0).is_err()
-    {
-        log("Failed to set role counts");
-        return;
-    }
-
-    log(&format!(
-        "Timelock initialized with min_delay: {}, proposers: {}, executors: {}, admin: {}",
-        min_delay,
-        proposers.len(),
-        executors.len(),
-        admin
-    ));
-    event!("TimelockInitialized",
-        min_delay: min_delay,
-        proposers: proposers.len().to_string(),
-        executors: executors.len().to_string(),
-        admin: admin
-    );
-}
-
 /// Check if caller has a specific role
-fn has_role(role: Role, account: &str) -> bool {
-    let storage = storage();
-    let roles: Map<(Role, String), bool> = Map::new("roles");
-    match roles.get(&(role, account.to_string())) {
-        Ok(Some(true)) => true,
-        _ => false,
-    }
+fn has_role(role: &str, account: &Address) -> bool {
+    AccessControl::has_role(account, role)
 }
 
 /// Check if caller is admin
 fn is_admin() -> bool {
     let ctx = context();
-    let storage = storage();
-    let config: TimelockConfig = match storage.get("config") {
-        Ok(Some(c)) => c,
-        _ => return false,
-    };
-    ctx.sender() == config.admin
+    AccessControl::authorize(ctx.sender(), Some(ADMIN_ROLE)).is_ok()
 }
 
 /// Get current timestamp
 fn get_timestamp() -> u64 {
-    // In real implementation, this would use block timestamp
-    // For now, using a placeholder value
-    1700000000u64 // Unix timestamp placeholder
+    let ctx = context();
+    ctx.block_timestamp()
 }
 
-/// Hash operation to create unique ID
-fn hash_operation(
-    target: &str,
-    value: u64,
-    data: &[u8],
-    predecessor: &Option<[u8; 32]>,
-    salt: &[u8; 32],
-) -> [u8; 32] {
-    let mut hasher = Hasher::new();
-    hasher.update(target.as_bytes());
-    hasher.update(&value.to_le_bytes());
-    hasher.update(data);
-
-    match predecessor {
-        Some(pred) => {
-            hasher.update(pred);
-        }
-        None => {
-            hasher.update(&[0u8; 32]);
-        }
-    }
+/// Error for a call the timelock refuses
+fn rejected(message: &str) -> ContractError {
+    ContractError::Custom(String::from(message))
+}
 
-    hasher.update(salt);
-    *hasher.finalize().as_bytes()
+/// Error for a call whose arguments the timelock refuses
+fn invalid_argument(message: &str) -> ContractError {
+    ContractError::InvalidArgument(String::from(message))
 }
 
-/// Schedule an operation for future execution
+/// Load the progress of an operation, looking up single operations first
 ///
-/// # Arguments
-/// * `target` - Contract address to call
-/// * `value` - CHERT amount to send
-/// * `data` - Calldata for the operation
-/// * `predecessor` - Operation ID that must execute first (optional)
-/// * `salt` - Random bytes for unique operation ID
-/// * `delay` - Delay in seconds (must be ≥ min_delay)
-///
-/// # Returns
-/// Operation ID (hash of parameters)
-#[unsafe(no_mangle)]
-pub extern "C" fn schedule() -> [u8; 32] {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Check if caller has PROPOSER_ROLE
-    if !has_role(Role::PROPOSER_ROLE, &caller) {
-        log("Caller does not have PROPOSER_ROLE");
-        return [0u8; 32];
-    }
-
-    // In real implementation, parse from transaction data
-    // For now, using example values
-    let target = "target_contract_address".to_string();
-    let value = 0u64;
-    let data = vec![0u8; 4]; // Example call data
-    let predecessor = None;
-    let salt = [1u8; 32];
-    let delay = 172800u64; // 2 days
-
-    // Validate parameters
-    let mut storage = storage();
-    let config: TimelockConfig = match storage.get("config") {
-        Ok(Some(c)) => c,
-        _ => {
-            log("Failed to load timelock config");
-            return [0u8; 32];
-        }
-    };
-
-    if delay < config.min_delay {
-        log("Delay must be >= minimum delay");
-        return [0u8; 32];
-    }
-
-    if delay > config.max_delay {
-        log("Delay exceeds maximum allowed");
-        return [0u8; 32];
-    }
-
-    // Generate operation ID
-    let operation_id = hash_operation(&target, value, &data, &predecessor, &salt);
-
-    // Check if operation already exists
-    let mut operations: Map<[u8; 32], Operation> = Map::new("operations");
-    if operations.get(&operation_id).ok().flatten().is_some() {
-        log("Operation already exists");
-        return [0u8; 32];
-    }
+/// A batch of one call hashes like the call itself, so the two share ids.
+fn load_progress(id: &[u8; 32]) -> ContractResult<Option<Progress>> {
+    let operations: Map<[u8; 32], Operation> = Map::new("operations");
+    if let Some(op) = operations.get(id)? {
+        return Ok(Some(Progress {
+            ready_timestamp: op.ready_timestamp,
+            executed: op.executed,
+            cancelled: op.cancelled,
+        }));
+    }
+    let batches: Map<[u8; 32], BatchOperation> = Map::new("batches");
+    Ok(batches.get(id)?.map(|batch| Progress {
+        ready_timestamp: batch.ready_timestamp,
+        executed: batch.executed,
+        cancelled: batch.cancelled,
+    }))
+}
 
-    // Check predecessor if specified
+/// Require a predecessor, if any, to exist and have been executed
+fn require_predecessor_executed(predecessor: &Option<[u8; 32]>) -> ContractResult<()> {
     if let Some(pred_id) = predecessor {
-        let pred_operation = match operations.get(&pred_id) {
-            Ok(Some(op)) => op,
-            Ok(None) => {
-                log("Predecessor operation does not exist");
-                return [0u8; 32];
-            }
-            Err(_) => {
-                log("Failed to read predecessor operation");
-                return [0u8; 32];
-            }
-        };
+        let pred_progress = load_progress(pred_id)?
+            .ok_or_else(|| rejected("Predecessor operation does not exist"))?;
 
-        if !pred_operation.executed {
-            log("Predecessor operation must be executed first");
-            return [0u8; 32];
+        if !pred_progress.executed {
+            return Err(rejected("Predecessor operation must be executed first"));
         }
     }
+    Ok(())
+}
 
-    // Create operation
-    let current_time = get_timestamp();
-    let operation = Operation {
-        target,
-        value,
-        data,
-        predecessor,
-        salt,
-        ready_timestamp: current_time + delay,
-        executed: false,
-        cancelled: false,
-    };
-
-    // Store operation
-    if operations.set(&operation_id, &operation).is_err() {
-        log("Failed to store operation");
-        return [0u8; 32];
+/// Require a scheduled operation to be ready and neither executed nor cancelled
+fn require_ready(progress: &Progress) -> ContractResult<()> {
+    if progress.executed {
+        return Err(rejected("Operation already executed"));
     }
 
-    // Update operation counter
-    let mut config_mut = config;
-    config_mut.operation_counter += 1;
-    if storage.set("config", &config_mut).is_err() {
-        log("Failed to update operation counter");
-        return [0u8; 32];
+    if progress.cancelled {
+        return Err(rejected("Operation is cancelled"));
     }
 
-    log(&format!("Operation scheduled with ID: {:?}", operation_id));
-    event!("OperationScheduled",
-        id: hex::encode(operation_id),
-        index: config_mut.operation_counter,
-        target: operation.target,
-        value: operation.value,
-        data: hex::encode(&operation.data),
-        predecessor: {
-            match operation.predecessor {
-                Some(p) => hex::encode(p),
-                None => "none".to_string(),
-            }
-        },
-        delay: delay,
-        ready_timestamp: operation.ready_timestamp
-    );
-
-    operation_id
-}
-
-/// Schedule a batch of operations to execute atomically
-///
-/// # Arguments
-/// * `targets` - Array of contract addresses
-/// * `values` - Array of CHERT amounts
-/// * `datas` - Array of calldata
-/// * `predecessor` - Operation ID dependency (optional)
-/// * `salt` - Random bytes for unique operation ID
-/// * `delay` - Delay in seconds
-///
-/// # Returns
-/// Batch operation ID
-#[unsafe(no_mangle)]
-pub extern "C" fn schedule_batch() -> [u8; 32] {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Check if caller has PROPOSER_ROLE
-    if !has_role(Role::PROPOSER_ROLE, &caller) {
-        log("Caller does not have PROPOSER_ROLE");
-        return [0u8; 32];
+    if get_timestamp() < progress.ready_timestamp {
+        return Err(rejected("Operation is not ready to execute"));
     }
+    Ok(())
+}
 
-    // In real implementation, parse from transaction data
-    let targets = vec!["target1".to_string(), "target2".to_string()];
-    let values = vec![0u64, 0u64];
-    let datas = vec![vec![0u8; 4], vec![0u8; 4]];
-    let predecessor = None;
-    let salt = [2u8; 32];
-    let delay = 172800u64; // 2 days
-
-    // Validate batch parameters
-    if targets.len() != values.len() || targets.len() != datas.len() {
-        log("Batch parameters must have same length");
-        return [0u8; 32];
+/// Require the caller to be allowed to execute operations
+fn require_executor(caller: &Address) -> ContractResult<()> {
+    // An executor role with no live grants means anyone can execute
+    if AccessControl::active_role_member_count(EXECUTOR_ROLE)? > 0
+        && !has_role(EXECUTOR_ROLE, caller)
+    {
+        return Err(rejected("Caller does not have EXECUTOR_ROLE"));
     }
+    Ok(())
+}
 
-    // For batch operations, we'll create a single "batch" operation
-    // In a real implementation, you might create individual operations and link them
-    let batch_operation_id = hash_operation_batch(&targets, &values, &datas, &predecessor, &salt);
-
-    // Log the batch scheduling
-    log(&format!(
-        "Batch operation scheduled with ID: {:?}",
-        batch_operation_id
-    ));
-    event!("OperationScheduled",
-        id: hex::encode(batch_operation_id),
-        index: 0, // Batch operations might have different indexing
-        target: "batch".to_string(),
-        value: 0,
-        data: "".to_string(),
-        predecessor: {
-            match predecessor {
-                Some(p) => hex::encode(p),
-                None => "none".to_string(),
-            }
-        },
-        delay: delay,
-        ready_timestamp: get_timestamp() + delay
-    );
+/// The timelock; each [`Timelock`] method below is an exported entrypoint
+pub struct TimelockContract;
+
+#[export_interface]
+impl Timelock for TimelockContract {
+    /// Initialize the timelock contract
+    ///
+    /// # Arguments
+    /// * `min_delay` - Minimum delay in seconds (e.g., 2 days = 172800)
+    /// * `proposers` - Array of addresses that can schedule operations
+    /// * `executors` - Array of addresses that can execute (empty = anyone)
+    /// * `admin` - Address with admin role (typically the timelock itself)
+    fn initialize(
+        &mut self,
+        min_delay: u64,
+        proposers: Vec<Address>,
+        executors: Vec<Address>,
+        admin: Address,
+    ) -> ContractResult<()> {
+        let mut storage = storage();
+        if storage.has("config") {
+            return Err(rejected("Timelock already initialized"));
+        }
 
-    batch_operation_id
-}
+        // Validate parameters
+        if min_delay == 0 || min_delay > 30 * 24 * 60 * 60 {
+            return Err(invalid_argument("Invalid minimum delay: must be > 0 and <= 30 days"));
+        }
 
-/// Hash operation batch to create unique ID
-fn hash_operation_batch(
-    targets: &[String],
-    values: &[u64],
-    datas: &[Vec<u8>],
-    predecessor: &Option<[u8; 32]>,
-    salt: &[u8; 32],
-) -> [u8; 32] {
-    let mut hasher = Hasher::new();
-
-    for target in targets {
-        hasher.update(target.as_bytes());
-    }
+        if proposers.is_empty() {
+            return Err(invalid_argument("At least one proposer required"));
+        }
 
-    for value in values {
-        hasher.update(&value.to_le_bytes());
-    }
+        // Initialize timelock configuration
+        let config = TimelockConfig {
+            min_delay,
+            max_delay: 30 * 24 * 60 * 60, // 30 days
+            operation_counter: 0,
+            admin: admin.clone(),
+            initialized: true,
+        };
+        storage.set("config", &config)?;
 
-    for data in datas {
-        hasher.update(data);
-    }
+        // Initialize role mappings; the admin administers every timelock role
+        AccessControl::initialize(&admin)?;
 
-    match predecessor {
-        Some(pred) => {
-            hasher.update(pred);
-        }
-        None => {
-            hasher.update(&[0u8; 32]);
+        for proposer in &proposers {
+            AccessControl::grant_role(&admin, proposer, PROPOSER_ROLE)?;
         }
-    }
 
-    hasher.update(salt);
-    *hasher.finalize().as_bytes()
-}
+        for executor in &executors {
+            AccessControl::grant_role(&admin, executor, EXECUTOR_ROLE)?;
+        }
 
-/// Cancel a pending operation
-///
-/// # Arguments
-/// * `id` - Operation ID to cancel
-#[unsafe(no_mangle)]
-pub extern "C" fn cancel(id: [u8; 32]) {
-    let ctx = context();
-    let caller = ctx.sender();
+        info!(
+            min_delay = min_delay,
+            proposers = proposers.len(),
+            executors = executors.len(),
+            admin = admin;
+            "Timelock initialized"
+        );
+        event!("TimelockInitialized",
+            min_delay: min_delay,
+            proposers: proposers.len().to_string(),
+            executors: executors.len().to_string(),
+            admin: admin
+        );
+        Ok(())
+    }
+
+    /// Schedule an operation for future execution
+    ///
+    /// # Arguments
+    /// * `target` - Contract address to call
+    /// * `value` - CHERT amount to send
+    /// * `data` - Calldata for the operation
+    /// * `predecessor` - Operation ID that must execute first (optional)
+    /// * `salt` - Random bytes for unique operation ID
+    /// * `delay` - Delay in seconds (must be ≥ min_delay)
+    ///
+    /// # Returns
+    /// Operation ID (hash of parameters)
+    fn schedule(
+        &mut self,
+        target: Address,
+        value: u64,
+        data: Vec<u8>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+        delay: u64,
+    ) -> ContractResult<[u8; 32]> {
+        let ctx = context();
+        let caller = ctx.sender();
+
+        // Check if caller has PROPOSER_ROLE
+        if !has_role(PROPOSER_ROLE, caller) {
+            return Err(rejected("Caller does not have PROPOSER_ROLE"));
+        }
 
-    // Check if caller has CANCELLER_ROLE
-    if !has_role(Role::CANCELLER_ROLE, &caller) && !is_admin() {
-        log("Caller does not have CANCELLER_ROLE or ADMIN_ROLE");
-        return;
-    }
+        // Validate parameters
+        let mut storage = storage();
+        let mut config: TimelockConfig = storage
+            .get("config")?
+            .ok_or_else(|| rejected("Timelock is not initialized"))?;
 
-    let mut operations: Map<[u8; 32], Operation> = Map::new("operations");
-    let mut operation = match operations.get(&id) {
-        Ok(Some(op)) => op,
-        Ok(None) => {
-            log("Operation does not exist");
-            return;
+        if delay < config.min_delay {
+            return Err(invalid_argument("Delay must be >= minimum delay"));
         }
-        Err(_) => {
-            log("Failed to read operation");
-            return;
+
+        if delay > config.max_delay {
+            return Err(invalid_argument("Delay exceeds maximum allowed"));
         }
-    };
 
-    // Check if operation can be cancelled
-    if operation.executed {
-        log("Cannot cancel already executed operation");
-        return;
-    }
+        // Generate operation ID
+        let operation_id = hash_operation(&target, value, &data, &predecessor, &salt);
 
-    if operation.cancelled {
-        log("Operation is already cancelled");
-        return;
-    }
+        // Check if operation already exists
+        if load_progress(&operation_id)?.is_some() {
+            return Err(rejected("Operation already exists"));
+        }
 
-    // Cancel the operation
-    operation.cancelled = true;
-    if operations.set(&id, &operation).is_err() {
-        log("Failed to update operation");
-        return;
-    }
+        // Check predecessor if specified
+        require_predecessor_executed(&predecessor)?;
+
+        // Create operation
+        let current_time = get_timestamp();
+        let operation = Operation {
+            target,
+            value,
+            data,
+            predecessor,
+            salt,
+            ready_timestamp: current_time + delay,
+            executed: false,
+            cancelled: false,
+        };
 
-    log(&format!("Operation {:?} cancelled", id));
-    event!("OperationCancelled", id: hex::encode(id));
-}
+        // Store operation
+        let mut operations: Map<[u8; 32], Operation> = Map::new("operations");
+        operations.set(&operation_id, &operation)?;
+
+        // Update operation counter
+        config.operation_counter += 1;
+        storage.set("config", &config)?;
+
+        info!(id = hex::encode(operation_id); "Operation scheduled");
+        event!("OperationScheduled",
+            id: hex::encode(operation_id),
+            index: config.operation_counter,
+            target: operation.target,
+            value: operation.value,
+            data: hex::encode(&operation.data),
+            predecessor: {
+                match operation.predecessor {
+                    Some(p) => hex::encode(p),
+                    None => "none".to_string(),
+                }
+            },
+            delay: delay,
+            ready_timestamp: operation.ready_timestamp
+        );
+
+        Ok(operation_id)
+    }
+
+    /// Schedule a batch of operations to execute atomically
+    ///
+    /// # Arguments
+    /// * `targets` - Array of contract addresses
+    /// * `values` - Array of CHERT amounts
+    /// * `datas` - Array of calldata
+    /// * `predecessor` - Operation ID dependency (optional)
+    /// * `salt` - Random bytes for unique operation ID
+    /// * `delay` - Delay in seconds
+    ///
+    /// # Returns
+    /// Batch operation ID
+    fn schedule_batch(
+        &mut self,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        datas: Vec<Vec<u8>>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+        delay: u64,
+    ) -> ContractResult<[u8; 32]> {
+        let ctx = context();
+        let caller = ctx.sender();
+
+        // Check if caller has PROPOSER_ROLE
+        if !has_role(PROPOSER_ROLE, caller) {
+            return Err(rejected("Caller does not have PROPOSER_ROLE"));
+        }
 
-/// Execute a ready operation
-///
-/// # Arguments
-/// * `target` - Contract address to call
-/// * `value` - CHERT amount to send
-/// * `data` - Calldata for the operation
-/// * `predecessor` - Operation ID that must execute first (optional)
-/// * `salt` - Random bytes for unique operation ID
-#[unsafe(no_mangle)]
-pub extern "C" fn execute(
-    target: String,
-    value: u64,
-    data: Vec<u8>,
-    predecessor: Option<[u8; 32]>,
-    salt: [u8; 32],
-) {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Check if caller has EXECUTOR_ROLE (or empty executor role means anyone can execute)
-    let storage = storage();
-    let role_counts: Map<Role, u64> = Map::new("role_counts");
-    let executor_count = match role_counts.get(&Role::EXECUTOR_ROLE) {
-        Ok(Some(count)) => count,
-        _ => {
-            log("Failed to read executor role count");
-            return;
+        // Validate batch parameters
+        if targets.len() != values.len() || targets.len() != datas.len() {
+            return Err(invalid_argument("Batch parameters must have same length"));
         }
-    };
 
-    if executor_count > 0 && !has_role(Role::EXECUTOR_ROLE, &caller) {
-        log("Caller does not have EXECUTOR_ROLE");
-        return;
-    }
+        let mut storage = storage();
+        let mut config: TimelockConfig = storage
+            .get("config")?
+            .ok_or_else(|| rejected("Timelock is not initialized"))?;
 
-    // Generate operation ID and check if it exists
-    let operation_id = hash_operation(&target, value, &data, &predecessor, &salt);
-    let mut operations: Map<[u8; 32], Operation> = Map::new("operations");
-    let mut operation = match operations.get(&operation_id) {
-        Ok(Some(op)) => op,
-        Ok(None) => {
-            log("Operation does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read operation");
-            return;
+        if delay < config.min_delay {
+            return Err(invalid_argument("Delay must be >= minimum delay"));
         }
-    };
 
-    // Check if operation can be executed
-    if operation.executed {
-        log("Operation already executed");
-        return;
-    }
+        if delay > config.max_delay {
+            return Err(invalid_argument("Delay exceeds maximum allowed"));
+        }
 
-    if operation.cancelled {
-        log("Operation is cancelled");
-        return;
-    }
+        // Generate batch operation ID
+        let batch_operation_id = hash_operation_batch(&targets, &values, &datas, &predecessor, &salt);
 
-    // Check if delay has passed
-    let current_time = get_timestamp();
-    if current_time < operation.ready_timestamp {
-        log("Operation is not ready to execute");
-        return;
-    }
+        // Check if operation already exists
+        if load_progress(&batch_operation_id)?.is_some() {
+            return Err(rejected("Operation already exists"));
+        }
 
-    // Check predecessor if specified
-    if let Some(pred_id) = operation.predecessor {
-        let pred_operation = match operations.get(&pred_id) {
-            Ok(Some(op)) => op,
-            _ => {
-                log("Predecessor operation not found");
-                return;
-            }
+        // Check predecessor if specified
+        require_predecessor_executed(&predecessor)?;
+
+        // Store the batch as one operation
+        let batch = BatchOperation {
+            targets,
+            values,
+            datas,
+            predecessor,
+            salt,
+            ready_timestamp: get_timestamp() + delay,
+            executed: false,
+            cancelled: false,
         };
-
-        if !pred_operation.executed {
-            log("Predecessor operation must be executed first");
-            return;
+        let mut batches: Map<[u8; 32], BatchOperation> = Map::new("batches");
+        batches.set(&batch_operation_id, &batch)?;
+
+        config.operation_counter += 1;
+        storage.set("config", &config)?;
+
+        // One event per call, indexed by its position in the batch
+        info!(id = hex::encode(batch_operation_id), calls = batch.targets.len(); "Batch operation scheduled");
+        for (index, target) in batch.targets.iter().enumerate() {
+            event!("OperationScheduled",
+                id: hex::encode(batch_operation_id),
+                index: index,
+                target: target,
+                value: batch.values[index],
+                data: hex::encode(&batch.datas[index]),
+                predecessor: {
+                    match batch.predecessor {
+                        Some(p) => hex::encode(p),
+                        None => "none".to_string(),
+                    }
+                },
+                delay: delay,
+                ready_timestamp: batch.ready_timestamp
+            );
         }
-    }
 
-    // Mark operation as executed
-    operation.executed = true;
-    if operations.set(&operation_id, &operation).is_err() {
-        log("Failed to update operation");
-        return;
+        Ok(batch_operation_id)
     }
 
-    // In a real implementation, this is where you'd execute the actual call
-    // For now, we'll just log the execution
-    log(&format!(
-        "Operation {:?} executed successfully",
-        operation_id
-    ));
-    event!("OperationExecuted",
-        id: hex::encode(operation_id),
-        index: 0,
-        target: target,
-        value: value,
-        data: hex::encode(&data),
-        success: true
-    );
-}
+    /// Cancel a pending operation
+    ///
+    /// # Arguments
+    /// * `id` - Operation ID to cancel
+    fn cancel(&mut self, id: [u8; 32]) -> ContractResult<()> {
+        let ctx = context();
+        let caller = ctx.sender();
 
-/// Execute a batch of operations atomically
-#[unsafe(no_mangle)]
-pub extern "C" fn execute_batch(
-    targets: Vec<String>,
-    values: Vec<u64>,
-    datas: Vec<Vec<u8>>,
-    predecessor: Option<[u8; 32]>,
-    salt: [u8; 32],
-) {
-    let ctx = context();
-    let caller = ctx.sender();
-
-    // Similar validation to execute_single
-    let storage = storage();
-    let role_counts: Map<Role, u64> = Map::new("role_counts");
-    let executor_count = match role_counts.get(&Role::EXECUTOR_ROLE) {
-        Ok(Some(count)) => count,
-        _ => {
-            log("Failed to read executor role count");
-            return;
+        // Check if caller has CANCELLER_ROLE
+        if !has_role(CANCELLER_ROLE, caller) && !is_admin() {
+            return Err(rejected("Caller does not have CANCELLER_ROLE or ADMIN_ROLE"));
         }
-    };
 
-    if executor_count > 0 && !has_role(Role::EXECUTOR_ROLE, &caller) {
-        log("Caller does not have EXECUTOR_ROLE");
-        return;
-    }
+        let progress = load_progress(&id)?.ok_or_else(|| rejected("Operation does not exist"))?;
 
-    // Generate batch operation ID
-    let batch_operation_id = hash_operation_batch(&targets, &values, &datas, &predecessor, &salt);
-
-    log(&format!(
-        "Batch operation {:?} executed",
-        batch_operation_id
-    ));
-    event!("OperationExecuted",
-        id: hex::encode(batch_operation_id),
-        index: 0,
-        target: "batch".to_string(),
-        value: 0,
-        data: "".to_string(),
-        success: true
-    );
-}
-
-/// Get the current state of an operation
-#[unsafe(no_mangle)]
-pub extern "C" fn get_operation_state(id: [u8; 32]) -> u8 {
-    let operations: Map<[u8; 32], Operation> = Map::new("operations");
-    match operations.get(&id) {
-        Ok(Some(op)) => {
-            if op.executed {
-                3 // Executed
-            } else if op.cancelled {
-                4 // Cancelled (using 4 for cancelled)
-            } else if get_timestamp() >= op.ready_timestamp {
-                2 // Ready
-            } else {
-                1 // Pending
-            }
+        // Check if operation can be cancelled
+        if progress.executed {
+            return Err(rejected("Cannot cancel already executed operation"));
         }
-        Ok(None) => 0, // Unset
-        Err(_) => 0,   // Unset (error case)
-    }
-}
-
-/// Check if an operation is pending (scheduled but not ready)
-#[unsafe(no_mangle)]
-pub extern "C" fn is_operation_pending(id: [u8; 32]) -> bool {
-    get_operation_state(id) == 1 // Pending
-}
 
-/// Check if an operation is ready to execute
-#[unsafe(no_mangle)]
-pub extern "C" fn is_operation_ready(id: [u8; 32]) -> bool {
-    get_operation_state(id) == 2 // Ready
-}
+        if progress.cancelled {
+            return Err(rejected("Operation is already cancelled"));
+        }
 
-/// Check if an operation has been executed
-#[unsafe(no_mangle)]
-pub extern "C" fn is_operation_done(id: [u8; 32]) -> bool {
-    get_operation_state(id) == 3 // Executed
-}
+        // Cancel the operation, single or batch
+        let mut operations: Map<[u8; 32], Operation> = Map::new("operations");
+        if let Some(mut operation) = operations.get(&id)? {
+            operation.cancelled = true;
+            operations.set(&id, &operation)?;
+        } else {
+            let mut batches: Map<[u8; 32], BatchOperation> = Map::new("batches");
+            if let Some(mut batch) = batches.get(&id)? {
+                batch.cancelled = true;
+                batches.set(&id, &batch)?;
+            }
+        }
 
-/// Get the timestamp when an operation becomes ready
-#[unsafe(no_mangle)]
-pub extern "C" fn get_timestamp_op(id: [u8; 32]) -> u64 {
-    let operations: Map<[u8; 32], Operation> = Map::new("operations");
-    match operations.get(&id) {
-        Ok(Some(op)) => op.ready_timestamp,
-        _ => 0,
+        info!(id = hex::encode(id); "Operation cancelled");
+        event!("OperationCancelled", id: hex::encode(id));
+        Ok(())
+    }
+
+    /// Execute a ready operation
+    ///
+    /// # Arguments
+    /// * `target` - Contract address to call
+    /// * `value` - CHERT amount to send
+    /// * `data` - Calldata for the operation
+    /// * `predecessor` - Operation ID that must execute first (optional)
+    /// * `salt` - Random bytes for unique operation ID
+    fn execute(
+        &mut self,
+        target: Address,
+        value: u64,
+        data: Vec<u8>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<()> {
+        let ctx = context();
+        let caller = ctx.sender();
+
+        // Check if caller has EXECUTOR_ROLE (or empty executor role means anyone can execute)
+        require_executor(caller)?;
+
+        // Generate operation ID and check if it exists
+        let operation_id = hash_operation(&target, value, &data, &predecessor, &salt);
+        let mut operations: Map<[u8; 32], Operation> = Map::new("operations");
+        let mut operation = operations
+            .get(&operation_id)?
+            .ok_or_else(|| rejected("Operation does not exist"))?;
+
+        // Check if operation can be executed
+        require_ready(&Progress {
+            ready_timestamp: operation.ready_timestamp,
+            executed: operation.executed,
+            cancelled: operation.cancelled,
+        })?;
+
+        // Check predecessor if specified
+        require_predecessor_executed(&operation.predecessor)?;
+
+        // Mark operation as executed
+        operation.executed = true;
+        operations.set(&operation_id, &operation)?;
+
+        // In a real implementation, this is where you'd execute the actual call
+        // For now, we'll just log the execution
+        info!(id = hex::encode(operation_id); "Operation executed");
+        event!("OperationExecuted",
+            id: hex::encode(operation_id),
+            index: 0,
+            target: target,
+            value: value,
+            data: hex::encode(&data),
+            success: true
+        );
+        Ok(())
+    }
+
+    /// Execute a batch of operations atomically
+    fn execute_batch(
+        &mut self,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        datas: Vec<Vec<u8>>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<()> {
+        let ctx = context();
+        let caller = ctx.sender();
+
+        // Check if caller has EXECUTOR_ROLE (or empty executor role means anyone can execute)
+        require_executor(caller)?;
+
+        // Generate batch operation ID and check if it exists
+        let batch_operation_id = hash_operation_batch(&targets, &values, &datas, &predecessor, &salt);
+        let mut batches: Map<[u8; 32], BatchOperation> = Map::new("batches");
+        let mut batch = batches
+            .get(&batch_operation_id)?
+            .ok_or_else(|| rejected("Operation does not exist"))?;
+
+        // Check if batch can be executed
+        require_ready(&Progress {
+            ready_timestamp: batch.ready_timestamp,
+            executed: batch.executed,
+            cancelled: batch.cancelled,
+        })?;
+
+        // Check predecessor if specified
+        require_predecessor_executed(&batch.predecessor)?;
+
+        // Mark batch as executed
+        batch.executed = true;
+        batches.set(&batch_operation_id, &batch)?;
+
+        // As with single operations, the calls themselves are only logged
+        info!(id = hex::encode(batch_operation_id), calls = batch.targets.len(); "Batch operation executed");
+        for (index, target) in batch.targets.iter().enumerate() {
+            event!("OperationExecuted",
+                id: hex::encode(batch_operation_id),
+                index: index,
+                target: target,
+                value: batch.values[index],
+                data: hex::encode(&batch.datas[index]),
+                success: true
+            );
+        }
+        Ok(())
+    }
+
+    /// Get the current state of an operation
+    fn get_operation_state(&self, id: [u8; 32]) -> ContractResult<u8> {
+        let state = match load_progress(&id)? {
+            Some(op) => {
+                if op.executed {
+                    OperationState::Executed
+                } else if op.cancelled {
+                    OperationState::Cancelled
+                } else if get_timestamp() >= op.ready_timestamp {
+                    OperationState::Ready
+                } else {
+                    OperationState::Pending
+                }
+            }
+            None => OperationState::Unset,
+        };
+        Ok(state as u8)
     }
-}
 
-/// Get the minimum delay period
-#[unsafe(no_mangle)]
-pub extern "C" fn get_min_delay() -> u64 {
-    let storage = storage();
-    match storage.get::<TimelockConfig>("config") {
-        Ok(Some(c)) => c.min_delay,
-        _ => 172800, // Default 2 days
+    /// Check if an operation is pending (scheduled but not ready)
+    fn is_operation_pending(&self, id: [u8; 32]) -> ContractResult<bool> {
+        Ok(self.get_operation_state(id)? == OperationState::Pending as u8)
     }
-}
-
-/// Check if an account has a specific role
-#[unsafe(no_mangle)]
-pub extern "C" fn has_role_check(role: u8, account: String) -> bool {
-    let role_enum = match role {
-        0 => Role::PROPOSER_ROLE,
-        1 => Role::EXECUTOR_ROLE,
-        2 => Role::CANCELLER_ROLE,
-        3 => Role::ADMIN_ROLE,
-        _ => {
-            log("Invalid role value");
-            return false;
-        }
-    };
 
-    has_role(role_enum, &account)
-}
-
-/// Calculate the operation ID for given parameters
-#[unsafe(no_mangle)]
-pub extern "C" fn hash_operation_fn(
-    target: String,
-    value: u64,
-    data: Vec<u8>,
-    predecessor: Option<[u8; 32]>,
-    salt: [u8; 32],
-) -> [u8; 32] {
-    hash_operation(&target, value, &data, &predecessor, &salt)
-}
+    /// Check if an operation is ready to execute
+    fn is_operation_ready(&self, id: [u8; 32]) -> ContractResult<bool> {
+        Ok(self.get_operation_state(id)? == OperationState::Ready as u8)
+    }
 
-/// Calculate the operation ID for a batch operation
-#[unsafe(no_mangle)]
-pub extern "C" fn hash_operation_batch_fn(
-    targets: Vec<String>,
-    values: Vec<u64>,
-    datas: Vec<Vec<u8>>,
-    predecessor: Option<[u8; 32]>,
-    salt: [u8; 32],
-) -> [u8; 32] {
-    hash_operation_batch(&targets, &values, &datas, &predecessor, &salt)
-}
+    /// Check if an operation has been executed
+    fn is_operation_done(&self, id: [u8; 32]) -> ContractResult<bool> {
+        Ok(self.get_operation_state(id)? == OperationState::Executed as u8)
+    }
 
-/// Update the minimum delay period (requires ADMIN_ROLE and timelock execution)
-#[unsafe(no_mangle)]
-pub extern "C" fn update_delay(new_delay: u64) {
-    let ctx = context();
-    let caller = ctx.sender();
+    /// Get the timestamp when an operation becomes ready
+    fn get_timestamp_op(&self, id: [u8; 32]) -> ContractResult<u64> {
+        Ok(load_progress(&id)?.map_or(0, |op| op.ready_timestamp))
+    }
 
-    // Only admin can call this directly, or it must be called through timelock
-    if !is_admin() {
-        log("Only admin can update delay directly");
-        return;
+    /// Get the minimum delay period
+    fn get_min_delay(&self) -> ContractResult<u64> {
+        let storage = storage();
+        let config = storage.get::<TimelockConfig>("config")?;
+        Ok(config.map_or(172800, |c| c.min_delay)) // Default 2 days
     }
 
-    // Validate new delay
-    if new_delay == 0 || new_delay > 30 * 24 * 60 * 60 {
-        log("Invalid delay: must be > 0 and <= 30 days");
-        return;
+    /// Check if an account has a specific role
+    fn has_role_check(&self, role: u8, account: Address) -> ContractResult<bool> {
+        let role_name = role_from_id(role).ok_or_else(|| invalid_argument("Invalid role value"))?;
+        Ok(has_role(role_name, &account))
     }
 
-    let mut storage = storage();
-    match storage.get::<TimelockConfig>("config") {
-        Ok(Some(mut c)) => {
-            let old_delay = c.min_delay;
-            c.min_delay = new_delay;
+    /// Calculate the operation ID for given parameters
+    fn hash_operation_fn(
+        &self,
+        target: Address,
+        value: u64,
+        data: Vec<u8>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<[u8; 32]> {
+        Ok(hash_operation(&target, value, &data, &predecessor, &salt))
+    }
 
-            if storage.set("config", &c).is_err() {
-                log("Failed to update config");
-                return;
-            }
+    /// Calculate the operation ID for a batch operation
+    fn hash_operation_batch_fn(
+        &self,
+        targets: Vec<Address>,
+        values: Vec<u64>,
+        datas: Vec<Vec<u8>>,
+        predecessor: Option<[u8; 32]>,
+        salt: [u8; 32],
+    ) -> ContractResult<[u8; 32]> {
+        Ok(hash_operation_batch(&targets, &values, &datas, &predecessor, &salt))
+    }
 
-            log(&format!(
-                "Delay updated from {} to {}",
-                old_delay, new_delay
-            ));
-            event!("MinDelayChanged", old_delay: old_delay, new_delay: new_delay);
+    /// Update the minimum delay period (requires ADMIN_ROLE and timelock execution)
+    fn update_delay(&mut self, new_delay: u64) -> ContractResult<()> {
+        // Only admin can call this directly, or it must be called through timelock
+        if !is_admin() {
+            return Err(rejected("Only admin can update delay directly"));
         }
-        _ => {
-            log("Failed to load config");
-            return;
+
+        // Validate new delay
+        if new_delay == 0 || new_delay > 30 * 24 * 60 * 60 {
+            return Err(invalid_argument("Invalid delay: must be > 0 and <= 30 days"));
         }
-    };
-}
 
-/// Grant a role to an account (requires ADMIN_ROLE)
-#[unsafe(no_mangle)]
-pub extern "C" fn grant_role(role: u8, account: String) {
-    let ctx = context();
-    let caller = ctx.sender();
+        let mut storage = storage();
+        let mut config: TimelockConfig = storage
+            .get("config")?
+            .ok_or_else(|| rejected("Timelock is not initialized"))?;
+        let old_delay = config.min_delay;
+        config.min_delay = new_delay;
+        storage.set("config", &config)?;
 
-    if !is_admin() {
-        log("Only admin can grant roles");
-        return;
+        info!(old_delay = old_delay, new_delay = new_delay; "Delay updated");
+        event!("MinDelayChanged", old_delay: old_delay, new_delay: new_delay);
+        Ok(())
     }
 
-    let role_enum = match role {
-        0 => Role::PROPOSER_ROLE,
-        1 => Role::EXECUTOR_ROLE,
-        2 => Role::CANCELLER_ROLE,
-        3 => Role::ADMIN_ROLE,
-        _ => {
-            log("Invalid role value");
-            return;
-        }
-    };
+    /// Grant a role to an account (requires ADMIN_ROLE)
+    fn grant_role(&mut self, role: u8, account: Address) -> ContractResult<()> {
+        let ctx = context();
+        let caller = ctx.sender();
 
-    // Check if role already exists
-    if has_role(role_enum, &account) {
-        log("Account already has this role");
-        return;
-    }
+        if !is_admin() {
+            return Err(rejected("Only admin can grant roles"));
+        }
 
-    // Grant the role
-    let mut roles: Map<(Role, String), bool> = Map::new("roles");
-    let mut role_counts: Map<Role, u64> = Map::new("role_counts");
+        let role_name = role_from_id(role).ok_or_else(|| invalid_argument("Invalid role value"))?;
 
-    if roles.set(&(role_enum, account.clone()), &true).is_err() {
-        log("Failed to set role");
-        return;
-    }
+        // Check if role already exists
+        if has_role(role_name, &account) {
+            return Err(rejected("Account already has this role"));
+        }
 
-    // Update role count
-    let current_count = role_counts.get(&role_enum).ok().flatten().unwrap_or(0);
-    let new_count = current_count + 1;
+        // Grant the role; AccessControl tracks members and emits `RoleGranted`
+        AccessControl::grant_role(caller, &account, role_name)?;
 
-    if role_counts.set(&role_enum, &new_count).is_err() {
-        log("Failed to update role count");
-        return;
+        info!(account = account; "Role granted");
+        Ok(())
     }
 
-    log(&format!("Role granted to {}", account));
-    event!("RoleGranted", role: role_enum, account: account, granter: caller);
-}
-
-/// Revoke a role from an account (requires ADMIN_ROLE)
-#[unsafe(no_mangle)]
-pub extern "C" fn revoke_role(role: u8, account: String) {
-    let ctx = context();
-    let caller = ctx.sender();
+    /// Revoke a role from an account (requires ADMIN_ROLE)
+    fn revoke_role(&mut self, role: u8, account: Address) -> ContractResult<()> {
+        let ctx = context();
+        let caller = ctx.sender();
 
-    if !is_admin() {
-        log("Only admin can revoke roles");
-        return;
-    }
-
-    let role_enum = match role {
-        0 => Role::PROPOSER_ROLE,
-        1 => Role::EXECUTOR_ROLE,
-        2 => Role::CANCELLER_ROLE,
-        3 => Role::ADMIN_ROLE,
-        _ => {
-            log("Invalid role value");
-            return;
+        if !is_admin() {
+            return Err(rejected("Only admin can revoke roles"));
         }
-    };
 
-    // Check if role exists
-    if !has_role(role_enum, &account) {
-        log("Account does not have this role");
-        return;
-    }
+        let role_name = role_from_id(role).ok_or_else(|| invalid_argument("Invalid role value"))?;
 
-    // Cannot revoke last admin
-    if role_enum == Role::ADMIN_ROLE {
-        let role_counts: Map<Role, u64> = Map::new("role_counts");
-        let admin_count = role_counts
-            .get(&Role::ADMIN_ROLE)
-            .ok()
-            .flatten()
-            .unwrap_or(0);
-        if admin_count <= 1 {
-            log("Cannot revoke last admin role");
-            return;
+        // Check if role exists
+        if !has_role(role_name, &account) {
+            return Err(rejected("Account does not have this role"));
         }
-    }
-
-    // Revoke the role
-    let mut roles: Map<(Role, String), bool> = Map::new("roles");
-    let mut role_counts: Map<Role, u64> = Map::new("role_counts");
 
-    if roles.set(&(role_enum, account.clone()), &false).is_err() {
-        log("Failed to revoke role");
-        return;
-    }
+        // Cannot revoke the last admin whose grant has not expired
+        if role_name == ADMIN_ROLE && AccessControl::active_role_member_count(ADMIN_ROLE)? <= 1 {
+            return Err(rejected("Cannot revoke last admin role"));
+        }
 
-    // Update role count
-    let current_count = role_counts.get(&role_enum).ok().flatten().unwrap_or(1);
-    let new_count = current_count.saturating_sub(1);
+        // Revoke the role; AccessControl emits `RoleRevoked`
+        AccessControl::revoke_role(caller, &account, role_name)?;
 
-    if role_counts.set(&role_enum, &new_count).is_err() {
-        log("Failed to update role count");
-        return;
+        info!(account = account; "Role revoked");
+        Ok(())
     }
-
-    log(&format!("Role revoked from {}", account));
-    event!("RoleRevoked", role: role_enum, account: account, revoker: caller);
 }
@@ -0,0 +1,78 @@
+//! Machine-readable interface of the timelock, checked against `abi.json`.
+
+use silica_contract_sdk::abi::{self, AbiBuilder, ContractAbi};
+use silica_contract_sdk::timelock::TimelockRef;
+use timelock_contract::{
+    ADMIN_ROLE, BatchOperation, CANCELLER_ROLE, EXECUTOR_ROLE, Operation, PROPOSER_ROLE,
+    TimelockConfig, TimelockContract,
+};
+
+fn timelock_abi() -> ContractAbi {
+    AbiBuilder::new("timelock-contract", env!("CARGO_PKG_VERSION"))
+        .interface(TimelockRef::describe)
+        .string_event(
+            "TimelockInitialized",
+            &["min_delay", "proposers", "executors", "admin"],
+        )
+        .string_event(
+            "OperationScheduled",
+            &[
+                "id",
+                "index",
+                "target",
+                "value",
+                "data",
+                "predecessor",
+                "delay",
+                "ready_timestamp",
+            ],
+        )
+        .string_event("OperationCancelled", &["id"])
+        .string_event(
+            "OperationExecuted",
+            &["id", "index", "target", "value", "data", "success"],
+        )
+        .string_event("MinDelayChanged", &["old_delay", "new_delay"])
+        .error("MissingRole", "Caller does not have the required role")
+        .error("InvalidRole", "Invalid role value")
+        .error("InvalidAccount", "Invalid account address")
+        .error("InvalidDelay", "Invalid delay: must be > 0 and <= 30 days")
+        .error("OperationExists", "Operation already exists")
+        .error("OperationNotFound", "Operation does not exist")
+        .error("OperationNotReady", "Operation is not ready to execute")
+        .error("OperationExecuted", "Operation already executed")
+        .error("OperationCancelled", "Operation is cancelled")
+        .error(
+            "PredecessorPending",
+            "Predecessor operation must be executed first",
+        )
+        .access_control(&[PROPOSER_ROLE, EXECUTOR_ROLE, CANCELLER_ROLE, ADMIN_ROLE])
+        .value::<TimelockConfig>("config")
+        .map::<[u8; 32], Operation>("operations")
+        .map::<[u8; 32], BatchOperation>("batches")
+        .build()
+        .expect("timelock ABI")
+}
+
+#[test]
+fn abi_is_compatible() {
+    abi::check_file(
+        &timelock_abi(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/abi.json"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn abi_entrypoints_are_exported() {
+    // Builds require every ABI entrypoint as an export without parameters.
+    for entrypoint in timelock_abi().entrypoints {
+        assert!(
+            TimelockContract::EXPORTS
+                .iter()
+                .any(|(name, _)| *name == entrypoint.name),
+            "{} is not exported",
+            entrypoint.name
+        );
+    }
+}
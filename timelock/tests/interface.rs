@@ -0,0 +1,166 @@
+//! The exported entrypoints answer calls made through the SDK's `TimelockRef`,
+//! the way the governor queues proposals.
+
+use silica_contract_sdk::address::Address;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::security::AccessControl;
+use silica_contract_sdk::timelock::{TimelockRef, hash_operation, hash_operation_batch, state};
+use timelock_contract::TimelockContract;
+
+/// Deploy the timelock and run as the governor calling it, returning the
+/// governor's and the timelock's addresses.
+fn setup() -> (Address, Address) {
+    mock::reset();
+    let governor = Address::contract([0x60; 32]);
+    let address = Address::contract([0x71; 32]);
+    mock::deploy_exports([0x71; 32], address.as_str(), TimelockContract::EXPORTS);
+    mock::set_contract_address(governor.as_str());
+    mock::set_sender(governor.as_str());
+    mock::set_origin(Address::from_public_key(&[1; 32]).as_str());
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_700_000_000);
+    (governor, address)
+}
+
+#[test]
+fn callers_receive_return_data() {
+    let (governor, address) = setup();
+
+    let timelock = TimelockRef::new(address);
+    timelock
+        .initialize(3600, vec![governor.clone()], vec![], governor.clone())
+        .unwrap();
+    assert_eq!(timelock.get_min_delay().unwrap(), 3600);
+
+    let target = Address::contract([9; 32]);
+    let id = timelock
+        .schedule(target.clone(), 0, vec![1, 2], None, [7; 32], 3600)
+        .unwrap();
+    assert_eq!(id, hash_operation(&target, 0, &[1, 2], &None, &[7; 32]));
+    assert_eq!(timelock.get_operation_state(id).unwrap(), state::PENDING);
+
+    // The operation becomes ready once the block clock passes its delay.
+    mock::set_block_timestamp(1_700_003_600);
+    assert_eq!(timelock.get_operation_state(id).unwrap(), state::READY);
+
+    // A second initialize fails and cannot change the delay.
+    assert!(
+        timelock
+            .initialize(1, vec![governor.clone()], vec![], governor.clone())
+            .is_err()
+    );
+    assert_eq!(timelock.get_min_delay().unwrap(), 3600);
+
+    // Rescheduling the same operation is rejected rather than answered with an id.
+    assert!(
+        timelock
+            .schedule(target, 0, vec![1, 2], None, [7; 32], 3600)
+            .is_err()
+    );
+}
+
+#[test]
+fn callers_without_the_proposer_role_cannot_schedule() {
+    let (governor, address) = setup();
+
+    let proposer = Address::contract([0x61; 32]);
+    let timelock = TimelockRef::new(address);
+    timelock
+        .initialize(3600, vec![proposer], vec![], governor)
+        .unwrap();
+
+    let target = Address::contract([9; 32]);
+    let id = hash_operation(&target, 0, &[1, 2], &None, &[7; 32]);
+    assert!(
+        timelock
+            .schedule(target, 0, vec![1, 2], None, [7; 32], 3600)
+            .is_err()
+    );
+    assert_eq!(timelock.get_operation_state(id).unwrap(), state::UNSET);
+}
+
+#[test]
+fn expired_executor_grants_leave_execution_open() {
+    let (governor, address) = setup();
+
+    let timelock = TimelockRef::new(address.clone());
+    timelock
+        .initialize(3600, vec![governor.clone()], vec![], governor.clone())
+        .unwrap();
+    let target = Address::contract([9; 32]);
+    let id = timelock
+        .schedule(target.clone(), 0, vec![1, 2], None, [7; 32], 3600)
+        .unwrap();
+
+    // The admin grants the executor role to another contract for an hour past
+    // the delay, so the governor may not execute meanwhile.
+    mock::set_contract_address(address.as_str());
+    let executor = Address::contract([0x62; 32]);
+    AccessControl::grant_role_until(
+        &governor,
+        &executor,
+        timelock_contract::EXECUTOR_ROLE,
+        1_700_007_200,
+    )
+    .unwrap();
+    mock::set_contract_address(governor.as_str());
+
+    mock::set_block_timestamp(1_700_003_600);
+    assert!(
+        timelock
+            .execute(target.clone(), 0, vec![1, 2], None, [7; 32])
+            .is_err()
+    );
+
+    // Once the grant lapses nobody holds the role, so anyone can execute.
+    mock::set_block_timestamp(1_700_007_200);
+    timelock
+        .execute(target, 0, vec![1, 2], None, [7; 32])
+        .unwrap();
+    assert_eq!(timelock.get_operation_state(id).unwrap(), state::EXECUTED);
+}
+
+#[test]
+fn batches_are_stored_and_executed_once_ready() {
+    let (governor, address) = setup();
+
+    let timelock = TimelockRef::new(address);
+    timelock
+        .initialize(3600, vec![governor.clone()], vec![], governor)
+        .unwrap();
+    let targets = vec![Address::contract([8; 32]), Address::contract([9; 32])];
+    let datas = vec![vec![1], vec![2, 3]];
+    let schedule = |delay| {
+        timelock.schedule_batch(
+            targets.clone(),
+            vec![0, 5],
+            datas.clone(),
+            None,
+            [7; 32],
+            delay,
+        )
+    };
+    let execute =
+        || timelock.execute_batch(targets.clone(), vec![0, 5], datas.clone(), None, [7; 32]);
+
+    // Nothing to execute before the batch is scheduled, and no shorter delay.
+    assert!(execute().is_err());
+    assert!(schedule(3599).is_err());
+
+    let id = schedule(3600).unwrap();
+    assert_eq!(
+        id,
+        hash_operation_batch(&targets, &[0, 5], &datas, &None, &[7; 32])
+    );
+    assert_eq!(timelock.get_operation_state(id).unwrap(), state::PENDING);
+    assert_eq!(timelock.get_timestamp_op(id).unwrap(), 1_700_003_600);
+    assert!(schedule(3600).is_err());
+    assert!(execute().is_err());
+
+    mock::set_block_timestamp(1_700_003_600);
+    mock::take_events();
+    execute().unwrap();
+    assert_eq!(timelock.get_operation_state(id).unwrap(), state::EXECUTED);
+    assert_eq!(mock::take_events().len(), 2);
+    assert!(execute().is_err());
+}
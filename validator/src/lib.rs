@@ -0,0 +1,554 @@
+//! Static checks for compiled contracts
+//!
+//! A contract that builds is not necessarily one the runtime will accept.
+//! [`validate`] checks a `.wasm` artifact before it is deployed:
+//!
+//! - **Imports** - Only functions from the SDK's [`HOST_FUNCTIONS`] list, from
+//!   the `env` module, with exactly the declared signature.
+//! - **Exports** - `memory`, plus every export the [`Policy`] requires, each a
+//!   function that takes no wasm parameters.
+//! - **Determinism** - No floating point, no threads or atomics, and no SIMD
+//!   unless the policy allows it. Relaxed SIMD is never allowed.
+//! - **Memory** - Initial and maximum memory, and any `memory.grow` by a
+//!   constant, stay within [`Policy::max_memory_pages`].
+//! - **Size** - The module fits [`Policy::max_size`], if set.
+//!
+//! Policies usually come from the contract's `Cargo.toml`:
+//!
+//! ```toml
+//! [package.metadata.silica-contract]
+//! max-size = 65536
+//! max-memory-pages = 16
+//! allow-simd = false
+//! required-exports = ["initialize"]
+//! ```
+//!
+//! [`HOST_FUNCTIONS`]: silica_contract_sdk::ffi::HOST_FUNCTIONS
+
+use serde::Deserialize;
+use silica_contract_sdk::ffi::{HostType, host_function};
+use std::collections::BTreeSet;
+use std::fmt;
+use wasmparser::{
+    CompositeInnerType, ExternalKind, FuncType, FuncValidatorAllocations, Operator, Parser,
+    Payload, TypeRef, ValType, ValidPayload, Validator, WasmFeatures,
+};
+
+/// Key of the validator's table under `[package.metadata]`.
+pub const METADATA_KEY: &str = "silica-contract";
+
+/// Module the runtime's host functions are imported from.
+pub const HOST_MODULE: &str = "env";
+
+/// Export the runtime reads and writes call buffers through.
+pub const MEMORY_EXPORT: &str = "memory";
+
+/// Wasm page size in bytes.
+pub const PAGE_SIZE: u64 = 65_536;
+
+/// Memory limit when a contract does not set one: 1 MiB.
+pub const DEFAULT_MAX_MEMORY_PAGES: u32 = 16;
+
+/// What a contract is checked against.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Policy {
+    /// Largest allowed module, in bytes
+    pub max_size: Option<u64>,
+    /// Most 64 KiB pages of linear memory the contract may use
+    pub max_memory_pages: u32,
+    /// Allow SIMD instructions; their results are deterministic, but not
+    /// every runtime build supports them
+    pub allow_simd: bool,
+    /// Function exports that must be present besides `memory`
+    pub required_exports: Vec<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            max_memory_pages: DEFAULT_MAX_MEMORY_PAGES,
+            allow_simd: false,
+            required_exports: Vec::new(),
+        }
+    }
+}
+
+impl Policy {
+    /// Wasm features a contract may use under this policy.
+    pub fn features(&self) -> WasmFeatures {
+        let mut features = WasmFeatures::WASM2;
+        features.remove(WasmFeatures::FLOATS | WasmFeatures::SATURATING_FLOAT_TO_INT);
+        features.set(WasmFeatures::SIMD, self.allow_simd);
+        features
+    }
+}
+
+/// One way a module breaks its policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The module could not be parsed at all.
+    Malformed(String),
+    /// The module is invalid or uses a disallowed feature such as floating
+    /// point or SIMD.
+    Rejected {
+        offset: usize,
+        message: String,
+    },
+    /// An import from outside `env`, or of something other than a function.
+    ForeignImport {
+        module: String,
+        name: String,
+    },
+    /// An `env` function the runtime does not provide.
+    UnknownHostFunction(String),
+    /// An `env` function imported with the wrong signature.
+    HostSignature {
+        name: String,
+        expected: String,
+        found: String,
+    },
+    MissingExport(String),
+    /// A required export that is not a parameterless function.
+    BadEntrypoint(String),
+    /// Declared memory exceeds the limit, in pages.
+    MemoryLimit {
+        pages: u64,
+        limit: u32,
+    },
+    /// A `memory.grow` by a constant that would exceed the limit, in pages.
+    MemoryGrow {
+        pages: u64,
+        limit: u32,
+    },
+    /// The module is larger than its size budget.
+    SizeBudget {
+        size: u64,
+        budget: u64,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(message) => write!(f, "malformed module: {}", message),
+            Self::Rejected { offset, message } => {
+                write!(f, "rejected at offset {:#x}: {}", offset, message)
+            }
+            Self::ForeignImport { module, name } => write!(
+                f,
+                "import `{}::{}` is not a host function from `{}`",
+                module, name, HOST_MODULE
+            ),
+            Self::UnknownHostFunction(name) => {
+                write!(f, "host function `{}` is not provided by the runtime", name)
+            }
+            Self::HostSignature {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "host function `{}` imported as {}, the runtime provides {}",
+                name, found, expected
+            ),
+            Self::MissingExport(name) => write!(f, "missing export `{}`", name),
+            Self::BadEntrypoint(name) => {
+                write!(f, "export `{}` must be a function without parameters", name)
+            }
+            Self::MemoryLimit { pages, limit } => write!(
+                f,
+                "memory of {} pages exceeds the limit of {} pages",
+                pages, limit
+            ),
+            Self::MemoryGrow { pages, limit } => write!(
+                f,
+                "memory.grow to {} pages exceeds the limit of {} pages",
+                pages, limit
+            ),
+            Self::SizeBudget { size, budget } => write!(
+                f,
+                "module is {} bytes, over its budget of {} bytes",
+                size, budget
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Check `wasm` against `policy`, returning every violation found.
+pub fn validate(wasm: &[u8], policy: &Policy) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if let Some(budget) = policy.max_size {
+        let size = wasm.len() as u64;
+        if size > budget {
+            violations.push(Violation::SizeBudget { size, budget });
+        }
+    }
+    if let Err(violation) = Module::check(wasm, policy, &mut violations) {
+        violations.push(violation);
+    }
+    violations
+}
+
+#[derive(Default)]
+struct Module {
+    /// Function types by type index; `None` for non-function types
+    types: Vec<Option<FuncType>>,
+    /// Type index of every function, imports first
+    functions: Vec<u32>,
+    initial_pages: u64,
+    exports: Vec<(String, ExternalKind, u32)>,
+}
+
+impl Module {
+    fn check(
+        wasm: &[u8],
+        policy: &Policy,
+        violations: &mut Vec<Violation>,
+    ) -> Result<(), Violation> {
+        let mut module = Module::default();
+        let mut validator = Some(Validator::new_with_features(policy.features()));
+        let mut allocations = FuncValidatorAllocations::default();
+
+        for payload in Parser::new(0).parse_all(wasm) {
+            let payload = payload.map_err(|error| Violation::Malformed(error.message().into()))?;
+
+            // Stop validating after the first rejection but keep collecting
+            // the structural checks below.
+            if let Some(active) = validator.as_mut() {
+                let result = match active.payload(&payload) {
+                    Ok(ValidPayload::Func(function, body)) => {
+                        let mut function = function.into_validator(allocations);
+                        let result = function.validate(&body);
+                        allocations = function.into_allocations();
+                        result
+                    }
+                    Ok(_) => Ok(()),
+                    Err(error) => Err(error),
+                };
+                if let Err(error) = result {
+                    violations.push(Violation::Rejected {
+                        offset: error.offset(),
+                        message: error.message().into(),
+                    });
+                    validator = None;
+                }
+            }
+
+            module.read(payload, policy, violations)?;
+        }
+
+        module.check_exports(policy, violations);
+        Ok(())
+    }
+
+    fn read(
+        &mut self,
+        payload: Payload<'_>,
+        policy: &Policy,
+        violations: &mut Vec<Violation>,
+    ) -> Result<(), Violation> {
+        let malformed =
+            |error: wasmparser::BinaryReaderError| Violation::Malformed(error.message().into());
+        match payload {
+            Payload::TypeSection(reader) => {
+                for group in reader {
+                    for ty in group.map_err(malformed)?.into_types() {
+                        self.types.push(match ty.composite_type.inner {
+                            CompositeInnerType::Func(func) => Some(func),
+                            _ => None,
+                        });
+                    }
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader.into_imports() {
+                    let import = import.map_err(malformed)?;
+                    match import.ty {
+                        TypeRef::Func(ty) if import.module == HOST_MODULE => {
+                            self.functions.push(ty);
+                            self.check_host_import(import.name, ty, violations);
+                        }
+                        _ => violations.push(Violation::ForeignImport {
+                            module: import.module.into(),
+                            name: import.name.into(),
+                        }),
+                    }
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                for ty in reader {
+                    self.functions.push(ty.map_err(malformed)?);
+                }
+            }
+            Payload::MemorySection(reader) => {
+                for memory in reader {
+                    let memory = memory.map_err(malformed)?;
+                    self.initial_pages = memory.initial;
+                    let largest = memory.maximum.unwrap_or(0).max(memory.initial);
+                    if largest > u64::from(policy.max_memory_pages) {
+                        violations.push(Violation::MemoryLimit {
+                            pages: largest,
+                            limit: policy.max_memory_pages,
+                        });
+                    }
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export.map_err(malformed)?;
+                    self.exports
+                        .push((export.name.into(), export.kind, export.index));
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                let mut grown = BTreeSet::new();
+                let mut previous = None;
+                for operator in body.get_operators_reader().map_err(malformed)? {
+                    let operator = operator.map_err(malformed)?;
+                    if let (Operator::MemoryGrow { .. }, Some(delta)) = (&operator, previous) {
+                        let pages = self.initial_pages + u64::from(delta);
+                        if pages > u64::from(policy.max_memory_pages) && grown.insert(pages) {
+                            violations.push(Violation::MemoryGrow {
+                                pages,
+                                limit: policy.max_memory_pages,
+                            });
+                        }
+                    }
+                    previous = match operator {
+                        Operator::I32Const { value } => Some(value as u32),
+                        _ => None,
+                    };
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn check_host_import(&self, name: &str, ty: u32, violations: &mut Vec<Violation>) {
+        let Some(expected) = host_function(name) else {
+            violations.push(Violation::UnknownHostFunction(name.into()));
+            return;
+        };
+        let Some(found) = self.func_type(ty) else {
+            return;
+        };
+        let matches = |declared: &[HostType], actual: &[ValType]| {
+            declared.len() == actual.len()
+                && declared
+                    .iter()
+                    .zip(actual)
+                    .all(|(declared, actual)| val_type(*declared) == *actual)
+        };
+        if !matches(expected.params, found.params()) || !matches(expected.results, found.results())
+        {
+            violations.push(Violation::HostSignature {
+                name: name.into(),
+                expected: signature(
+                    &expected
+                        .params
+                        .iter()
+                        .map(|ty| val_type(*ty))
+                        .collect::<Vec<_>>(),
+                    &expected
+                        .results
+                        .iter()
+                        .map(|ty| val_type(*ty))
+                        .collect::<Vec<_>>(),
+                ),
+                found: signature(found.params(), found.results()),
+            });
+        }
+    }
+
+    fn check_exports(&self, policy: &Policy, violations: &mut Vec<Violation>) {
+        let find = |name: &str| self.exports.iter().find(|export| export.0 == name);
+        match find(MEMORY_EXPORT) {
+            Some((_, ExternalKind::Memory, _)) => {}
+            Some(_) => violations.push(Violation::BadEntrypoint(MEMORY_EXPORT.into())),
+            None => violations.push(Violation::MissingExport(MEMORY_EXPORT.into())),
+        }
+        for name in &policy.required_exports {
+            match find(name) {
+                None => violations.push(Violation::MissingExport(name.clone())),
+                Some((_, ExternalKind::Func, index)) => {
+                    let takes_params = self
+                        .functions
+                        .get(*index as usize)
+                        .and_then(|ty| self.func_type(*ty))
+                        .is_none_or(|ty| !ty.params().is_empty());
+                    if takes_params {
+                        violations.push(Violation::BadEntrypoint(name.clone()));
+                    }
+                }
+                Some(_) => violations.push(Violation::BadEntrypoint(name.clone())),
+            }
+        }
+    }
+
+    fn func_type(&self, ty: u32) -> Option<&FuncType> {
+        self.types.get(ty as usize).and_then(Option::as_ref)
+    }
+}
+
+fn val_type(ty: HostType) -> ValType {
+    match ty {
+        HostType::I32 => ValType::I32,
+        HostType::I64 => ValType::I64,
+    }
+}
+
+fn signature(params: &[ValType], results: &[ValType]) -> String {
+    let list = |types: &[ValType]| {
+        types
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!("({}) -> ({})", list(params), list(results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(wat: &str, policy: &Policy) -> Vec<Violation> {
+        validate(&wat::parse_str(wat).unwrap(), policy)
+    }
+
+    fn entrypoints(names: &[&str]) -> Policy {
+        Policy {
+            required_exports: names.iter().map(|name| name.to_string()).collect(),
+            ..Policy::default()
+        }
+    }
+
+    const CONTRACT: &str = r#"
+        (module
+          (import "env" "get_call_data_length" (func (result i32)))
+          (import "env" "get_block_height" (func (result i64)))
+          (memory (export "memory") 2)
+          (func (export "run") (result i32) (call 0))
+          (func (export "helper") (param i32)))
+    "#;
+
+    #[test]
+    fn accepts_a_well_formed_contract() {
+        assert_eq!(check(CONTRACT, &entrypoints(&["run"])), []);
+    }
+
+    #[test]
+    fn checks_imports_against_the_host_functions() {
+        let violations = check(
+            r#"(module
+                 (import "env" "get_block_height" (func (result i32)))
+                 (import "env" "read_file" (func))
+                 (import "wasi" "fd_write" (func))
+                 (memory (export "memory") 1))"#,
+            &Policy::default(),
+        );
+        assert_eq!(
+            violations,
+            [
+                Violation::HostSignature {
+                    name: "get_block_height".into(),
+                    expected: "() -> (i64)".into(),
+                    found: "() -> (i32)".into(),
+                },
+                Violation::UnknownHostFunction("read_file".into()),
+                Violation::ForeignImport {
+                    module: "wasi".into(),
+                    name: "fd_write".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_memory_and_parameterless_entrypoints() {
+        let violations = check(CONTRACT, &entrypoints(&["run", "helper", "missing"]));
+        assert_eq!(
+            violations,
+            [
+                Violation::BadEntrypoint("helper".into()),
+                Violation::MissingExport("missing".into()),
+            ]
+        );
+        assert_eq!(
+            check("(module (func (export \"run\")))", &Policy::default()),
+            [Violation::MissingExport("memory".into())]
+        );
+    }
+
+    #[test]
+    fn rejects_floating_point() {
+        let violations = check(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "run") (drop (f64.add (f64.const 1) (f64.const 2)))))"#,
+            &Policy::default(),
+        );
+        assert_eq!(violations.len(), 1);
+        assert!(
+            matches!(&violations[0], Violation::Rejected { message, .. } if message.contains("floating-point"))
+        );
+    }
+
+    #[test]
+    fn rejects_simd_unless_allowed() {
+        let simd = r#"(module
+            (memory (export "memory") 1)
+            (func (export "run") (drop (i32x4.add (v128.const i64x2 1 2) (v128.const i64x2 3 4)))))"#;
+        assert!(matches!(
+            check(simd, &Policy::default())[..],
+            [Violation::Rejected { .. }]
+        ));
+        let allowed = Policy {
+            allow_simd: true,
+            ..Policy::default()
+        };
+        assert_eq!(check(simd, &allowed), []);
+    }
+
+    #[test]
+    fn enforces_memory_and_size_limits() {
+        let wat = r#"(module
+            (memory (export "memory") 4 32)
+            (func (export "run") (drop (memory.grow (i32.const 20)))))"#;
+        let policy = Policy {
+            max_size: Some(8),
+            ..Policy::default()
+        };
+        let wasm = wat::parse_str(wat).unwrap();
+        assert_eq!(
+            validate(&wasm, &policy),
+            [
+                Violation::SizeBudget {
+                    size: wasm.len() as u64,
+                    budget: 8,
+                },
+                Violation::MemoryLimit {
+                    pages: 32,
+                    limit: DEFAULT_MAX_MEMORY_PAGES,
+                },
+                Violation::MemoryGrow {
+                    pages: 24,
+                    limit: DEFAULT_MAX_MEMORY_PAGES,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_modules() {
+        assert!(matches!(
+            validate(b"\0asm\x01\0\0\0\x7f", &Policy::default())[..],
+            [Violation::Malformed(_)]
+        ));
+    }
+}